@@ -0,0 +1,389 @@
+//! Zero-width assertions.
+//!
+//! The needles in this module match an empty range at positions satisfying
+//! some condition, like the `^`, `$` and `\b` anchors of regular expressions.
+//! The condition depends on the content around the position, which is looked
+//! up in the whole hay even if the search is restricted to a smaller range.
+//!
+//! The assertions can be searched in `&str`, `&[u8]` and `&Wtf8` haystacks.
+//! They are also consumers, and thus can be combined with other needles:
+//!
+//! ```rust
+//! extern crate pattern_3;
+//! use pattern_3::anchors::{StartOfLine, WordBoundary};
+//! use pattern_3::combinators::NeedleExt;
+//! use pattern_3::ext::{match_indices, replace_with};
+//!
+//! let word = WordBoundary.then("cat").then(WordBoundary);
+//! assert_eq!(
+//!     match_indices("cat concat cats cat", word).collect::<Vec<_>>(),
+//!     vec![(0, "cat"), (16, "cat")]
+//! );
+//!
+//! let mut quoted = String::new();
+//! replace_with("a\nb\n", StartOfLine, |_| "> ", |s| quoted.push_str(s));
+//! assert_eq!(quoted, "> a\n> b\n> ");
+//! ```
+//!
+//! Unique haystacks like `&mut str` discard the content outside the searched
+//! range, so the assertions are only implemented for shared haystacks.
+
+use needle::*;
+use haystack::{Hay, Span};
+use omgwtf8::Wtf8;
+use memchr::{memchr, memrchr};
+use std::ops::Range;
+use std::str;
+
+/// Matches the start of the hay, like `\A` in regular expressions.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::anchors::StartOfHay;
+/// use pattern_3::ext::match_ranges;
+///
+/// assert_eq!(match_ranges("abc", StartOfHay).collect::<Vec<_>>(), vec![(0..0, "")]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StartOfHay;
+
+/// Matches the end of the hay, like `\z` in regular expressions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EndOfHay;
+
+/// Matches the start of a line, like `^` in multi-line regular expressions.
+///
+/// This is the start of the hay, and every position right after a `\n`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::anchors::StartOfLine;
+/// use pattern_3::ext::match_indices;
+///
+/// let hay = "a\nb\n";
+/// assert_eq!(
+///     match_indices(hay, StartOfLine).map(|(i, _)| i).collect::<Vec<_>>(),
+///     vec![0, 2, 4]
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StartOfLine;
+
+/// Matches the end of a line, like `$` in multi-line regular expressions.
+///
+/// This is the end of the hay, and every position right before a `\n`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EndOfLine;
+
+/// Matches a Unicode word boundary, like `\b` in regular expressions.
+///
+/// A word boundary is a position where exactly one of the characters before
+/// and after it is a word character. Word characters are the alphanumeric
+/// characters and the underscore `_`. The start and end of the hay, as well as
+/// invalid UTF-8 sequences and unpaired surrogates, are treated as non-word
+/// characters.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::anchors::WordBoundary;
+/// use pattern_3::ext::split;
+///
+/// assert_eq!(
+///     split("été, 2018!", WordBoundary).collect::<Vec<_>>(),
+///     vec!["", "été", ", ", "2018", "!"]
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WordBoundary;
+
+/// Matches an ASCII word boundary, like `(?-u:\b)` in regular expressions.
+///
+/// This is the same as [`WordBoundary`], except that only ASCII letters,
+/// digits and the underscore `_` are considered word characters.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiWordBoundary;
+
+#[derive(Copy, Clone, Debug)]
+enum Anchor {
+    StartOfHay,
+    EndOfHay,
+    StartOfLine,
+    EndOfLine,
+    WordBoundary { ascii: bool },
+}
+
+/// Decodes the character starting at byte `i`.
+fn char_after(hay: &[u8], i: usize) -> Option<char> {
+    let width = match *hay.get(i)? {
+        b @ 0x00..=0x7f => return Some(b as char),
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return None,
+    };
+    let bytes = hay.get(i..(i + width))?;
+    str::from_utf8(bytes).ok()?.chars().next()
+}
+
+/// Decodes the character ending at byte `i`.
+fn char_before(hay: &[u8], i: usize) -> Option<char> {
+    if i == 0 {
+        return None;
+    }
+    let mut start = i - 1;
+    while hay[start] & 0xc0 == 0x80 {
+        if start == 0 || i - start == 4 {
+            return None;
+        }
+        start -= 1;
+    }
+    str::from_utf8(&hay[start..i]).ok()?.chars().next()
+}
+
+impl Anchor {
+    #[inline]
+    fn is_word(self, c: Option<char>) -> bool {
+        match c {
+            Some(c) => match self {
+                Anchor::WordBoundary { ascii: true } => c.is_ascii_alphanumeric() || c == '_',
+                _ => c.is_alphanumeric() || c == '_',
+            },
+            None => false,
+        }
+    }
+
+    /// Checks whether the assertion holds at byte `i` of the hay.
+    fn holds(self, hay: &[u8], i: usize) -> bool {
+        match self {
+            Anchor::StartOfHay => i == 0,
+            Anchor::EndOfHay => i == hay.len(),
+            Anchor::StartOfLine => i == 0 || hay[i - 1] == b'\n',
+            Anchor::EndOfLine => i == hay.len() || hay[i] == b'\n',
+            Anchor::WordBoundary { .. } => {
+                self.is_word(char_before(hay, i)) != self.is_word(char_after(hay, i))
+            }
+        }
+    }
+
+    /// Finds the first position in `range` (inclusive of `range.end`) where
+    /// the assertion holds.
+    fn find(self, hay: &[u8], range: Range<usize>) -> Option<usize> {
+        let pos = match self {
+            Anchor::StartOfHay => 0,
+            Anchor::EndOfHay => hay.len(),
+            Anchor::StartOfLine if range.start == 0 => 0,
+            Anchor::StartOfLine => memchr(b'\n', &hay[(range.start - 1)..range.end])? + range.start,
+            Anchor::EndOfLine => {
+                let end = if range.end == hay.len() { range.end } else { range.end + 1 };
+                memchr(b'\n', &hay[range.start..end]).map_or(hay.len(), |p| p + range.start)
+            }
+            Anchor::WordBoundary { .. } => (range.start..=range.end).find(|&i| self.holds(hay, i))?,
+        };
+        if range.start <= pos && pos <= range.end {
+            Some(pos)
+        } else {
+            None
+        }
+    }
+
+    /// Finds the last position in `range` (inclusive of `range.end`) where
+    /// the assertion holds.
+    fn rfind(self, hay: &[u8], range: Range<usize>) -> Option<usize> {
+        let pos = match self {
+            Anchor::StartOfHay => 0,
+            Anchor::EndOfHay => hay.len(),
+            Anchor::StartOfLine => {
+                let start = range.start.saturating_sub(1);
+                memrchr(b'\n', &hay[start..range.end]).map_or(0, |p| p + start + 1)
+            }
+            Anchor::EndOfLine if range.end == hay.len() => hay.len(),
+            Anchor::EndOfLine => memrchr(b'\n', &hay[range.start..=range.end])? + range.start,
+            Anchor::WordBoundary { .. } => (range.start..=range.end).rev().find(|&i| self.holds(hay, i))?,
+        };
+        if range.start <= pos && pos <= range.end {
+            Some(pos)
+        } else {
+            None
+        }
+    }
+}
+
+/// Searcher and consumer of the assertions.
+///
+/// Just like other searchers, a position which has been found is skipped when
+/// searching again in the same direction, so that iterating over all matches
+/// always makes progress.
+#[derive(Clone, Debug)]
+pub struct AnchorSearcher {
+    anchor: Anchor,
+    last_front: Option<usize>,
+    last_back: Option<usize>,
+}
+
+impl AnchorSearcher {
+    #[inline]
+    fn new(anchor: Anchor) -> Self {
+        AnchorSearcher {
+            anchor,
+            last_front: None,
+            last_back: None,
+        }
+    }
+
+    fn search_bytes<N>(&mut self, span: Span<&[u8]>, next_index: N) -> Option<Range<usize>>
+    where
+        N: FnOnce(usize) -> usize,
+    {
+        let (hay, range) = span.into_parts();
+        let mut pos = self.anchor.find(hay, range.clone())?;
+        if self.last_front == Some(pos) {
+            if pos == range.end {
+                return None;
+            }
+            pos = self.anchor.find(hay, next_index(pos)..range.end)?;
+        }
+        self.last_front = Some(pos);
+        Some(pos..pos)
+    }
+
+    fn rsearch_bytes<N>(&mut self, span: Span<&[u8]>, prev_index: N) -> Option<Range<usize>>
+    where
+        N: FnOnce(usize) -> usize,
+    {
+        let (hay, range) = span.into_parts();
+        let mut pos = self.anchor.rfind(hay, range.clone())?;
+        if self.last_back == Some(pos) {
+            if pos == range.start {
+                return None;
+            }
+            pos = self.anchor.rfind(hay, range.start..prev_index(pos))?;
+        }
+        self.last_back = Some(pos);
+        Some(pos..pos)
+    }
+
+    #[inline]
+    fn consume_bytes(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        if self.anchor.holds(hay, range.start) {
+            Some(range.start)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn rconsume_bytes(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        if self.anchor.holds(hay, range.end) {
+            Some(range.end)
+        } else {
+            None
+        }
+    }
+}
+
+unsafe impl Searcher<[u8]> for AnchorSearcher {
+    #[inline]
+    fn search(&mut self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        self.search_bytes(span, |i| i + 1)
+    }
+}
+
+unsafe impl ReverseSearcher<[u8]> for AnchorSearcher {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        self.rsearch_bytes(span, |i| i - 1)
+    }
+}
+
+unsafe impl Consumer<[u8]> for AnchorSearcher {
+    #[inline]
+    fn consume(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        self.consume_bytes(span)
+    }
+}
+
+unsafe impl ReverseConsumer<[u8]> for AnchorSearcher {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        self.rconsume_bytes(span)
+    }
+}
+
+// The matched positions are always character boundaries: the start and end
+// of hay, the positions around `\n`, and the positions between two decodable
+// characters (at least one of them is decodable at a word boundary).
+macro_rules! impl_searcher {
+    ($ty:ty) => {
+        unsafe impl Searcher<$ty> for AnchorSearcher {
+            #[inline]
+            fn search(&mut self, span: Span<&$ty>) -> Option<Range<usize>> {
+                let (hay, _) = span.clone().into_parts();
+                self.search_bytes(span.as_bytes(), |i| unsafe { hay.next_index(i) })
+            }
+        }
+
+        unsafe impl ReverseSearcher<$ty> for AnchorSearcher {
+            #[inline]
+            fn rsearch(&mut self, span: Span<&$ty>) -> Option<Range<usize>> {
+                let (hay, _) = span.clone().into_parts();
+                self.rsearch_bytes(span.as_bytes(), |i| unsafe { hay.prev_index(i) })
+            }
+        }
+
+        unsafe impl Consumer<$ty> for AnchorSearcher {
+            #[inline]
+            fn consume(&mut self, span: Span<&$ty>) -> Option<usize> {
+                self.consume_bytes(span.as_bytes())
+            }
+        }
+
+        unsafe impl ReverseConsumer<$ty> for AnchorSearcher {
+            #[inline]
+            fn rconsume(&mut self, span: Span<&$ty>) -> Option<usize> {
+                self.rconsume_bytes(span.as_bytes())
+            }
+        }
+    }
+}
+
+impl_searcher!(str);
+impl_searcher!(Wtf8);
+
+macro_rules! impl_needle {
+    ($needle:ident => $anchor:expr) => {
+        impl_needle!($needle => $anchor; &'h str, &'h [u8], &'h Wtf8);
+    };
+    ($needle:ident => $anchor:expr; $($ty:ty),+) => {
+        $(
+            impl<'h> Needle<$ty> for $needle {
+                type Searcher = AnchorSearcher;
+                type Consumer = AnchorSearcher;
+
+                #[inline]
+                fn into_searcher(self) -> Self::Searcher {
+                    AnchorSearcher::new($anchor)
+                }
+
+                #[inline]
+                fn into_consumer(self) -> Self::Consumer {
+                    AnchorSearcher::new($anchor)
+                }
+            }
+        )+
+    };
+}
+
+impl_needle!(StartOfHay => Anchor::StartOfHay);
+impl_needle!(EndOfHay => Anchor::EndOfHay);
+impl_needle!(StartOfLine => Anchor::StartOfLine);
+impl_needle!(EndOfLine => Anchor::EndOfLine);
+impl_needle!(WordBoundary => Anchor::WordBoundary { ascii: false });
+impl_needle!(AsciiWordBoundary => Anchor::WordBoundary { ascii: true });
@@ -0,0 +1,551 @@
+//! Approximate matching.
+//!
+//! An [`Approx`] needle matches the substrings which are within a maximum
+//! distance of a string or slice. Two metrics are supported: the Levenshtein
+//! distance counts the insertions, deletions and substitutions needed to turn
+//! one into the other, and the Hamming distance counts only substitutions, so
+//! every match has the same length as the needle.
+//!
+//! Since there are many overlapping matches around every occurrence, the
+//! searcher reports the match ending first, extended for as long as the
+//! distance keeps decreasing. Its start is then chosen to minimize the
+//! distance, preferring the longest match on ties. The distance of the last
+//! match is available from [`ApproxSearcher::distance`], and
+//! [`match_distances`] iterates over the matches together with their
+//! distances.
+//!
+//! Needles of at most 64 elements are matched with bit-parallel algorithms
+//! (Myers' algorithm for the Levenshtein distance, and the bitap algorithm
+//! for the Hamming distance), which take time proportional to the length of
+//! the hay. Longer needles fall back to computing the full table of
+//! distances, which takes time proportional to the length of the hay times
+//! the length of the needle.
+//!
+//! ```rust
+//! extern crate pattern_3;
+//! use pattern_3::approx::{match_distances, Approx};
+//! use pattern_3::ext::{find, match_ranges};
+//!
+//! let text = "the quick brwn fox jumpd over";
+//! assert_eq!(find(text, Approx::levenshtein("brown", 1)), Some(10));
+//! assert_eq!(
+//!     match_ranges(text, Approx::levenshtein("jumps", 1)).collect::<Vec<_>>(),
+//!     vec![(19..24, "jumpd")]
+//! );
+//! assert_eq!(
+//!     match_distances("fax fix fox", Approx::hamming("fox", 1)).collect::<Vec<_>>(),
+//!     vec![(0..3, "fax", 1), (4..7, "fix", 1), (8..11, "fox", 0)]
+//! );
+//! ```
+//!
+//! [`Approx`]: struct.Approx.html
+//! [`ApproxSearcher::distance`]: struct.ApproxSearcher.html#method.distance
+//! [`match_distances`]: fn.match_distances.html
+
+use needle::*;
+use haystack::{Hay, Haystack, Span};
+use std::borrow::Borrow;
+use std::cmp::min;
+use std::ops::{Deref, Range};
+
+/// The distance used by an [`Approx`](struct.Approx.html) needle.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// Counts insertions, deletions and substitutions.
+    Levenshtein,
+    /// Counts substitutions only.
+    Hamming,
+}
+
+/// Needle matching the substrings within a maximum distance of a string or
+/// slice.
+///
+/// This type is available only with the `std` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::approx::Approx;
+/// use pattern_3::ext::{contains, split};
+///
+/// assert!(contains("recieve", Approx::levenshtein("receive", 2)));
+/// assert!(!contains("recieve", Approx::hamming("receive", 1)));
+///
+/// let hay = &[1, 2, 3, 9, 5, 0, 1, 2, 4, 4, 5][..];
+/// assert_eq!(
+///     split(hay, Approx::hamming(&[2, 3, 4][..], 1)).collect::<Vec<_>>(),
+///     vec![&[1][..], &[5, 0, 1][..], &[5][..]]
+/// );
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Approx<N> {
+    needle: N,
+    max_distance: usize,
+    metric: Metric,
+}
+
+impl<N> Approx<N> {
+    /// Matches the substrings within `max_distance` insertions, deletions or
+    /// substitutions of `needle`.
+    #[inline]
+    pub fn levenshtein(needle: N, max_distance: usize) -> Self {
+        Approx { needle, max_distance, metric: Metric::Levenshtein }
+    }
+
+    /// Matches the substrings within `max_distance` substitutions of
+    /// `needle`.
+    #[inline]
+    pub fn hamming(needle: N, max_distance: usize) -> Self {
+        Approx { needle, max_distance, metric: Metric::Hamming }
+    }
+
+    /// Returns the distance used to compare the needle.
+    #[inline]
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+}
+
+/// The longest needle matched with bit-parallel algorithms.
+const MAX_BIT_PARALLEL_LEN: usize = 64;
+
+/// Searcher for an [`Approx`](struct.Approx.html) needle.
+///
+/// The needle is stored as `N`, which is `&[T]` for slices and `Vec<char>`
+/// for strings.
+#[derive(Clone, Debug)]
+pub struct ApproxSearcher<N> {
+    needle: N,
+    max_distance: usize,
+    metric: Metric,
+    /// For bit-parallel matching, the distinct elements of the needle (as the
+    /// index of their first occurrence) with the mask of their positions.
+    masks: Vec<(usize, u64)>,
+    /// Bit-parallel state: `[pv, mv]` for Myers' algorithm, and one state per
+    /// number of substitutions for the bitap algorithm.
+    bits: Vec<u64>,
+    /// Column of the table of distances, when not using bit-parallel state.
+    column: Vec<usize>,
+    /// Distance of the substring ending at the current position.
+    score: usize,
+    distance: usize,
+    allow_empty_match_front: bool,
+}
+
+impl<T, N> ApproxSearcher<N>
+where
+    T: PartialEq,
+    N: Deref<Target = [T]>,
+{
+    fn new(needle: N, max_distance: usize, metric: Metric) -> Self {
+        let mut masks = Vec::<(usize, u64)>::new();
+        if needle.len() <= MAX_BIT_PARALLEL_LEN {
+            for (i, elem) in needle.iter().enumerate() {
+                match masks.iter().position(|&(j, _)| needle[j] == *elem) {
+                    Some(k) => masks[k].1 |= 1 << i,
+                    None => masks.push((i, 1 << i)),
+                }
+            }
+        }
+        ApproxSearcher {
+            bits: Vec::new(),
+            column: Vec::new(),
+            score: 0,
+            distance: 0,
+            allow_empty_match_front: true,
+            needle,
+            max_distance,
+            metric,
+            masks,
+        }
+    }
+
+    /// Returns the distance between the needle and the last match found.
+    #[inline]
+    pub fn distance(&self) -> usize {
+        self.distance
+    }
+
+    #[inline]
+    fn bit_parallel(&self) -> bool {
+        let len = self.needle.len();
+        len != 0 && len <= MAX_BIT_PARALLEL_LEN
+    }
+
+    #[inline]
+    fn mask(&self, elem: &T) -> u64 {
+        let needle = &*self.needle;
+        self.masks.iter()
+            .find(|&&(i, _)| needle[i] == *elem)
+            .map_or(0, |&(_, mask)| mask)
+    }
+
+    /// Resets the state to match substrings starting anywhere.
+    fn reset(&mut self) {
+        let len = self.needle.len();
+        let infinity = usize::MAX;
+        self.bits.clear();
+        self.column.clear();
+        match self.metric {
+            Metric::Levenshtein => {
+                self.score = len;
+                if self.bit_parallel() {
+                    self.bits.extend_from_slice(&[!0, 0]);
+                } else {
+                    self.column.extend(0..=len);
+                }
+            }
+            Metric::Hamming => {
+                self.score = if len == 0 { 0 } else { infinity };
+                if self.bit_parallel() {
+                    let states = min(self.max_distance, len) + 1;
+                    self.bits.resize(states, 0);
+                } else {
+                    self.column.push(0);
+                    self.column.resize(len + 1, infinity);
+                }
+            }
+        }
+    }
+
+    /// Advances the state by one element of the hay, updating the distance of
+    /// the best substring ending after it.
+    fn step(&mut self, elem: &T) {
+        let len = self.needle.len();
+        if len == 0 {
+            return;
+        }
+        let bit_parallel = self.bit_parallel();
+        let high: u64 = if bit_parallel { 1 << (len - 1) } else { 0 };
+        match (self.metric, bit_parallel) {
+            (Metric::Levenshtein, true) => {
+                let eq = self.mask(elem);
+                let (pv, mv) = (self.bits[0], self.bits[1]);
+                let xv = eq | mv;
+                let xh = ((eq & pv).wrapping_add(pv) ^ pv) | eq;
+                let ph = mv | !(xh | pv);
+                let mh = pv & xh;
+                if ph & high != 0 {
+                    self.score += 1;
+                } else if mh & high != 0 {
+                    self.score -= 1;
+                }
+                let (ph, mh) = (ph << 1, mh << 1);
+                self.bits[0] = mh | !(xv | ph);
+                self.bits[1] = ph & xv;
+            }
+            (Metric::Hamming, true) => {
+                let eq = self.mask(elem);
+                // With `d` substitutions, the needle is matched up to some
+                // position either by a matching element after a match with
+                // `d` substitutions, or by any element after a match with
+                // `d - 1` substitutions.
+                let mut substituted = 0;
+                for (d, state) in self.bits.iter_mut().enumerate() {
+                    let old = *state;
+                    *state = ((old << 1) | 1) & eq;
+                    if d != 0 {
+                        *state |= (substituted << 1) | 1;
+                    }
+                    substituted = old;
+                }
+                self.score = self.bits.iter()
+                    .position(|state| state & high != 0)
+                    .unwrap_or(usize::MAX);
+            }
+            (Metric::Levenshtein, false) => {
+                let mut diagonal = self.column[0];
+                for i in 1..=len {
+                    let above = self.column[i];
+                    let cost = (self.needle[i - 1] != *elem) as usize;
+                    self.column[i] = min(min(above, self.column[i - 1]) + 1, diagonal + cost);
+                    diagonal = above;
+                }
+                self.score = self.column[len];
+            }
+            (Metric::Hamming, false) => {
+                for i in (1..=len).rev() {
+                    let cost = (self.needle[i - 1] != *elem) as usize;
+                    self.column[i] = self.column[i - 1].saturating_add(cost);
+                }
+                self.score = self.column[len];
+            }
+        }
+    }
+
+    /// Finds where a match ending at `end` with the distance `score` starts,
+    /// given the elements before `end` in reverse order.
+    fn find_start<Q, I>(&self, end: usize, score: usize, rev_elems: I) -> (usize, usize)
+    where
+        Q: Borrow<T>,
+        I: Iterator<Item = (usize, Q)>,
+    {
+        let len = self.needle.len();
+        let mut rev_elems = rev_elems;
+        if let Metric::Hamming = self.metric {
+            let start = match len {
+                0 => end,
+                _ => rev_elems.nth(len - 1).map_or(end, |(i, _)| i),
+            };
+            return (start, score);
+        }
+
+        // `column[i]` is the distance between the last `i` elements of the
+        // needle and the elements from the current position to `end`.
+        let mut column = (0..=len).collect::<Vec<_>>();
+        let mut best = (column[len], end);
+        let mut count = 0;
+        for (i, elem) in rev_elems {
+            count += 1;
+            if count > len + best.0 {
+                break;
+            }
+            let mut diagonal = column[0];
+            column[0] = count;
+            for j in 1..=len {
+                let above = column[j];
+                let cost = (self.needle[len - j] != *elem.borrow()) as usize;
+                column[j] = min(min(above, column[j - 1]) + 1, diagonal + cost);
+                diagonal = above;
+            }
+            if column[len] <= best.0 {
+                best = (column[len], i);
+            }
+        }
+        (best.1, best.0)
+    }
+
+    /// Finds the next match, given the elements from `start` (with the
+    /// positions after them), and a function returning the elements between
+    /// `start` and some position in reverse order (with the positions before
+    /// them).
+    fn search_in<Q, I, F, J>(&mut self, start: usize, elems: I, mut rev_elems: F) -> Option<Range<usize>>
+    where
+        Q: Borrow<T>,
+        I: Iterator<Item = (usize, Q)>,
+        F: FnMut(usize) -> J,
+        J: Iterator<Item = (usize, Q)>,
+    {
+        self.reset();
+        let mut best = None;
+        if self.score <= self.max_distance && self.allow_empty_match_front {
+            best = Some((start, self.score));
+        }
+        let mut elems = elems;
+        let mut next = None;
+        for (i, elem) in &mut elems {
+            self.step(elem.borrow());
+            match best {
+                None if self.score <= self.max_distance => best = Some((i, self.score)),
+                Some((_, score)) if self.score < score => best = Some((i, self.score)),
+                Some(_) => {
+                    next = Some(i);
+                    break;
+                }
+                None => {}
+            }
+        }
+        let (mut end, score) = best?;
+        let (start, distance) = self.find_start(end, score, rev_elems(end));
+
+        // Substituting the last elements is as good as deleting them, so
+        // prefer the longer match if it starts at the same position.
+        while let Some(i) = next {
+            if self.score != score || self.find_start(i, score, rev_elems(i)).0 != start {
+                break;
+            }
+            end = i;
+            next = elems.next().map(|(i, elem)| {
+                self.step(elem.borrow());
+                i
+            });
+        }
+
+        self.distance = distance;
+        self.allow_empty_match_front = false;
+        Some(start..end)
+    }
+
+    /// Finds the longest prefix of the elements within the maximum distance,
+    /// preferring the smallest distance.
+    fn consume_in<Q, I>(&mut self, start: usize, elems: I) -> Option<usize>
+    where
+        Q: Borrow<T>,
+        I: Iterator<Item = (usize, Q)>,
+    {
+        let len = self.needle.len();
+        let max_distance = self.max_distance;
+        let mut best = None;
+        match self.metric {
+            Metric::Levenshtein => {
+                let mut column = (0..=len).collect::<Vec<_>>();
+                if len <= max_distance {
+                    best = Some((len, start));
+                }
+                for (count, (i, elem)) in elems.enumerate() {
+                    let mut diagonal = column[0];
+                    column[0] = count + 1;
+                    for j in 1..=len {
+                        let above = column[j];
+                        let cost = (self.needle[j - 1] != *elem.borrow()) as usize;
+                        column[j] = min(min(above, column[j - 1]) + 1, diagonal + cost);
+                        diagonal = above;
+                    }
+                    let better = match best {
+                        Some((d, _)) => column[len] <= d,
+                        None => true,
+                    };
+                    if column[len] <= max_distance && better {
+                        best = Some((column[len], i));
+                    }
+                    if column.iter().all(|&d| d > max_distance) {
+                        break;
+                    }
+                }
+            }
+            Metric::Hamming => {
+                let mut end = start;
+                let mut distance = 0;
+                let mut elems = elems;
+                for expected in self.needle.iter() {
+                    let (i, elem) = elems.next()?;
+                    if *expected != *elem.borrow() {
+                        distance += 1;
+                    }
+                    end = i;
+                }
+                if distance <= max_distance {
+                    best = Some((distance, end));
+                }
+            }
+        }
+        let (distance, end) = best?;
+        self.distance = distance;
+        Some(end)
+    }
+}
+
+unsafe impl<T: PartialEq> Searcher<[T]> for ApproxSearcher<&[T]> {
+    fn search(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let start = range.start;
+        let elems = hay[range.clone()].iter().enumerate().map(|(i, e)| (start + i + 1, e));
+        self.search_in(start, elems, |end| {
+            hay[start..end].iter().enumerate().rev().map(move |(i, e)| (start + i, e))
+        })
+    }
+}
+
+unsafe impl<T: PartialEq> Consumer<[T]> for ApproxSearcher<&[T]> {
+    fn consume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let start = range.start;
+        let elems = hay[range].iter().enumerate().map(|(i, e)| (start + i + 1, e));
+        self.consume_in(start, elems)
+    }
+}
+
+unsafe impl Searcher<str> for ApproxSearcher<Vec<char>> {
+    fn search(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let start = range.start;
+        let elems = hay[range.clone()].char_indices().map(|(i, c)| (start + i + c.len_utf8(), c));
+        self.search_in(start, elems, |end| {
+            hay[start..end].char_indices().rev().map(move |(i, c)| (start + i, c))
+        })
+    }
+}
+
+unsafe impl Consumer<str> for ApproxSearcher<Vec<char>> {
+    fn consume(&mut self, span: Span<&str>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let start = range.start;
+        let elems = hay[range].char_indices().map(|(i, c)| (start + i + c.len_utf8(), c));
+        self.consume_in(start, elems)
+    }
+}
+
+impl<H> Needle<H> for Approx<&str>
+where
+    H: Haystack<Target = str>,
+{
+    type Searcher = ApproxSearcher<Vec<char>>;
+    type Consumer = ApproxSearcher<Vec<char>>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        ApproxSearcher::new(self.needle.chars().collect(), self.max_distance, self.metric)
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        ApproxSearcher::new(self.needle.chars().collect(), self.max_distance, self.metric)
+    }
+}
+
+impl<'p, T, H> Needle<H> for Approx<&'p [T]>
+where
+    T: PartialEq + 'p,
+    H: Haystack<Target = [T]>,
+{
+    type Searcher = ApproxSearcher<&'p [T]>;
+    type Consumer = ApproxSearcher<&'p [T]>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        ApproxSearcher::new(self.needle, self.max_distance, self.metric)
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        ApproxSearcher::new(self.needle, self.max_distance, self.metric)
+    }
+}
+
+/// An iterator over the matches of an approximate needle and their distances.
+///
+/// This struct is created by the [`match_distances`](fn.match_distances.html)
+/// function.
+#[derive(Clone, Debug)]
+pub struct MatchDistances<H, N>
+where
+    H: Haystack,
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+{
+    searcher: ApproxSearcher<N>,
+    rest: Span<H>,
+}
+
+impl<H, N> Iterator for MatchDistances<H, N>
+where
+    H: Haystack,
+    ApproxSearcher<N>: Searcher<H::Target>,
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+{
+    type Item = (Range<<H::Target as Hay>::Index>, H, usize);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest.take();
+        let range = self.searcher.search(rest.borrow())?;
+        let [_, middle, right] = unsafe { rest.split_around(range) };
+        self.rest = right;
+        let range = middle.original_range();
+        Some((range, Span::into(middle), self.searcher.distance))
+    }
+}
+
+/// Returns an iterator over the matches of an approximate needle, with their
+/// ranges and their distances to the needle.
+pub fn match_distances<H, P, N>(haystack: H, needle: P) -> MatchDistances<H, N>
+where
+    H: Haystack,
+    P: Needle<H, Searcher = ApproxSearcher<N>>,
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+{
+    MatchDistances {
+        searcher: needle.into_searcher(),
+        rest: haystack.into(),
+    }
+}
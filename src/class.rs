@@ -0,0 +1,855 @@
+//! Codeword classes.
+//!
+//! A *codeword class* is a needle which matches exactly one codeword, i.e. a
+//! single `char` of a string or a single element of a slice. Characters,
+//! character slices, ranges and predicates are all codeword classes, and this
+//! module provides combinators to compute their [`union`], [`intersection`]
+//! and [`complement`] as a single needle.
+//!
+//! When the combined class is known to only contain ASCII characters, the
+//! string is searched byte-wise, using `memchr` if the class has at most three
+//! members, and a class of a single non-ASCII character is searched like that
+//! character. Likewise, byte classes are searched using `memchr` or a 256-bit
+//! bitmap, unless their members can only be found by calling a closure on
+//! every byte.
+//!
+//! ```rust
+//! extern crate pattern_3;
+//! use pattern_3::class::{complement, intersection, union};
+//! use pattern_3::ext::{split, trim_start};
+//!
+//! let separators = union(',', union(';', ':'));
+//! assert_eq!(
+//!     split("a,b;c:d", separators).collect::<Vec<_>>(),
+//!     vec!["a", "b", "c", "d"]
+//! );
+//!
+//! let consonants = intersection('a'..='z', complement(&['a', 'e', 'i', 'o', 'u'][..]));
+//! assert_eq!(trim_start("strength", consonants), "ength");
+//!
+//! let not_digits = complement(0..10);
+//! assert_eq!(trim_start(&[100, 20, 3, 40][..], not_digits), &[3, 40]);
+//! ```
+
+use needle::*;
+use haystack::Span;
+use slices::elem::Elem;
+use slices::func::ElemSearcher;
+use slices::set::{ByteSet, Bytes};
+use strings::char::CharSearcher;
+use strings::func::MultiCharSearcher;
+use std::ops::{Range, RangeBounds, RangeFrom, RangeInclusive, RangeTo, RangeToInclusive};
+
+//------------------------------------------------------------------------------
+// Classes
+//------------------------------------------------------------------------------
+
+/// A set of characters which can be combined with other character classes.
+pub trait CharClass {
+    /// Checks whether the character is a member of this class.
+    fn contains(&mut self, c: char) -> bool;
+
+    /// Returns the members of this class as a 128-bit bitmap if all of them
+    /// are ASCII characters, where bit `b` is set when the character `b` is a
+    /// member.
+    ///
+    /// Returning `None` is always correct, but prevents the byte-wise fast
+    /// path from being used.
+    #[inline]
+    fn ascii_members(&mut self) -> Option<u128> {
+        None
+    }
+
+    /// Returns the only member of this class if it is known to have exactly
+    /// one.
+    ///
+    /// Returning `None` is always correct, but prevents searching for the
+    /// character using `memchr`.
+    #[inline]
+    fn single_member(&mut self) -> Option<char> {
+        None
+    }
+}
+
+/// A set of elements which can be combined with other element classes.
+pub trait ElemClass<T: ?Sized> {
+    /// Checks whether the element is a member of this class.
+    fn contains(&mut self, elem: &T) -> bool;
+}
+
+impl CharClass for char {
+    #[inline]
+    fn contains(&mut self, c: char) -> bool {
+        *self == c
+    }
+
+    #[inline]
+    fn ascii_members(&mut self) -> Option<u128> {
+        if self.is_ascii() {
+            Some(1 << (*self as u32))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn single_member(&mut self) -> Option<char> {
+        Some(*self)
+    }
+}
+
+impl CharClass for &[char] {
+    #[inline]
+    fn contains(&mut self, c: char) -> bool {
+        <[char]>::contains(self, &c)
+    }
+
+    #[inline]
+    fn ascii_members(&mut self) -> Option<u128> {
+        if self.iter().all(|c| c.is_ascii()) {
+            Some(self.iter().fold(0, |bitmap, c| bitmap | 1 << (*c as u32)))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn single_member(&mut self) -> Option<char> {
+        let first = *self.first()?;
+        if self.iter().all(|c| *c == first) {
+            Some(first)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the bitmap of the characters `start..end` if all of them are ASCII.
+#[inline]
+fn ascii_range(start: u32, end: u32) -> Option<u128> {
+    if end > 0x80 {
+        None
+    } else if start >= end {
+        Some(0)
+    } else {
+        Some((!0u128 >> (128 - (end - start))) << start)
+    }
+}
+
+impl CharClass for Range<char> {
+    #[inline]
+    fn contains(&mut self, c: char) -> bool {
+        self.start <= c && c < self.end
+    }
+
+    #[inline]
+    fn ascii_members(&mut self) -> Option<u128> {
+        ascii_range(self.start as u32, self.end as u32)
+    }
+}
+
+impl CharClass for RangeInclusive<char> {
+    #[inline]
+    fn contains(&mut self, c: char) -> bool {
+        *self.start() <= c && c <= *self.end()
+    }
+
+    #[inline]
+    fn ascii_members(&mut self) -> Option<u128> {
+        ascii_range(*self.start() as u32, *self.end() as u32 + 1)
+    }
+
+    #[inline]
+    fn single_member(&mut self) -> Option<char> {
+        if self.start() == self.end() {
+            Some(*self.start())
+        } else {
+            None
+        }
+    }
+}
+
+impl CharClass for RangeFrom<char> {
+    #[inline]
+    fn contains(&mut self, c: char) -> bool {
+        self.start <= c
+    }
+}
+
+impl CharClass for RangeTo<char> {
+    #[inline]
+    fn contains(&mut self, c: char) -> bool {
+        c < self.end
+    }
+
+    #[inline]
+    fn ascii_members(&mut self) -> Option<u128> {
+        ascii_range(0, self.end as u32)
+    }
+}
+
+impl CharClass for RangeToInclusive<char> {
+    #[inline]
+    fn contains(&mut self, c: char) -> bool {
+        c <= self.end
+    }
+
+    #[inline]
+    fn ascii_members(&mut self) -> Option<u128> {
+        ascii_range(0, self.end as u32 + 1)
+    }
+}
+
+impl<F: FnMut(char) -> bool> CharClass for F {
+    #[inline]
+    fn contains(&mut self, c: char) -> bool {
+        self(c)
+    }
+}
+
+impl<T, F: FnMut(&T) -> bool> ElemClass<T> for F {
+    #[inline]
+    fn contains(&mut self, elem: &T) -> bool {
+        self(elem)
+    }
+}
+
+impl<T: PartialEq> ElemClass<T> for Elem<T> {
+    #[inline]
+    fn contains(&mut self, elem: &T) -> bool {
+        self.0 == *elem
+    }
+}
+
+macro_rules! impl_elem_class_for_range {
+    ($($range:ident)+) => {$(
+        impl<T: PartialOrd> ElemClass<T> for $range<T> {
+            #[inline]
+            fn contains(&mut self, elem: &T) -> bool {
+                RangeBounds::contains(self, elem)
+            }
+        }
+    )+}
+}
+
+impl_elem_class_for_range!(Range RangeInclusive RangeFrom RangeTo RangeToInclusive);
+
+impl ElemClass<u8> for ByteSet {
+    #[inline]
+    fn contains(&mut self, elem: &u8) -> bool {
+        ByteSet::contains(self, *elem)
+    }
+}
+
+/// Collects the bytes accepted by `f` into a 256-bit bitmap.
+#[inline]
+fn byte_bitmap<F: FnMut(u8) -> bool>(mut f: F) -> [u64; 4] {
+    let mut bitmap = [0u64; 4];
+    for b in 0..=255u8 {
+        if f(b) {
+            bitmap[(b >> 6) as usize] |= 1 << (b & 63);
+        }
+    }
+    bitmap
+}
+
+/// Checks whether the byte `b` is set in the bitmap.
+#[inline]
+fn has_byte(bitmap: &[u64; 4], b: u8) -> bool {
+    bitmap[(b >> 6) as usize] & (1 << (b & 63)) != 0
+}
+
+/// Computes the members of a byte class ahead of the search.
+trait ByteMembers {
+    /// Returns the members of this class as a 256-bit bitmap, where bit `b` is
+    /// set when the byte `b` is a member.
+    ///
+    /// Returns `None` if they can only be found by calling a closure on every
+    /// byte.
+    fn byte_members(&mut self) -> Option<[u64; 4]>;
+}
+
+impl<C> ByteMembers for C {
+    #[inline]
+    default fn byte_members(&mut self) -> Option<[u64; 4]> {
+        None
+    }
+}
+
+impl ByteMembers for Elem<u8> {
+    #[inline]
+    fn byte_members(&mut self) -> Option<[u64; 4]> {
+        let b = self.0;
+        Some(byte_bitmap(|x| x == b))
+    }
+}
+
+impl ByteMembers for ByteSet {
+    #[inline]
+    fn byte_members(&mut self) -> Option<[u64; 4]> {
+        let set = *self;
+        Some(byte_bitmap(|b| set.contains(b)))
+    }
+}
+
+macro_rules! impl_byte_members_for_range {
+    ($($range:ident)+) => {$(
+        impl ByteMembers for $range<u8> {
+            #[inline]
+            fn byte_members(&mut self) -> Option<[u64; 4]> {
+                Some(byte_bitmap(|b| RangeBounds::contains(self, &b)))
+            }
+        }
+    )+}
+}
+
+impl_byte_members_for_range!(Range RangeInclusive RangeFrom RangeTo RangeToInclusive);
+
+//------------------------------------------------------------------------------
+// Combinators
+//------------------------------------------------------------------------------
+
+/// The union of two codeword classes, created by [`union`].
+#[derive(Copy, Clone, Debug)]
+pub struct Union<A, B>(A, B);
+
+/// The intersection of two codeword classes, created by [`intersection`].
+#[derive(Copy, Clone, Debug)]
+pub struct Intersection<A, B>(A, B);
+
+/// The complement of a codeword class, created by [`complement`].
+#[derive(Copy, Clone, Debug)]
+pub struct Complement<A>(A);
+
+/// Creates a class matching codewords which are members of either `a` or `b`.
+#[inline]
+pub fn union<A, B>(a: A, b: B) -> Union<A, B> {
+    Union(a, b)
+}
+
+/// Creates a class matching codewords which are members of both `a` and `b`.
+#[inline]
+pub fn intersection<A, B>(a: A, b: B) -> Intersection<A, B> {
+    Intersection(a, b)
+}
+
+/// Creates a class matching codewords which are not members of `a`.
+#[inline]
+pub fn complement<A>(a: A) -> Complement<A> {
+    Complement(a)
+}
+
+/// Keeps the members of an ASCII-only class which are also members of
+/// `other`.
+#[inline]
+fn filter_ascii<C: CharClass>(mut bitmap: u128, other: &mut C) -> u128 {
+    let mut result = 0;
+    while bitmap != 0 {
+        let b = bitmap.trailing_zeros();
+        bitmap &= bitmap - 1;
+        if other.contains(b as u8 as char) {
+            result |= 1 << b;
+        }
+    }
+    result
+}
+
+impl<A: CharClass, B: CharClass> CharClass for Union<A, B> {
+    #[inline]
+    fn contains(&mut self, c: char) -> bool {
+        self.0.contains(c) || self.1.contains(c)
+    }
+
+    #[inline]
+    fn ascii_members(&mut self) -> Option<u128> {
+        Some(self.0.ascii_members()? | self.1.ascii_members()?)
+    }
+
+    #[inline]
+    fn single_member(&mut self) -> Option<char> {
+        match (self.0.single_member(), self.1.single_member()) {
+            (Some(a), Some(b)) if a == b => Some(a),
+            _ => None,
+        }
+    }
+}
+
+impl<A: CharClass, B: CharClass> CharClass for Intersection<A, B> {
+    #[inline]
+    fn contains(&mut self, c: char) -> bool {
+        self.0.contains(c) && self.1.contains(c)
+    }
+
+    #[inline]
+    fn ascii_members(&mut self) -> Option<u128> {
+        match (self.0.ascii_members(), self.1.ascii_members()) {
+            (Some(a), Some(b)) => Some(a & b),
+            (Some(a), None) => Some(filter_ascii(a, &mut self.1)),
+            (None, Some(b)) => Some(filter_ascii(b, &mut self.0)),
+            (None, None) => None,
+        }
+    }
+
+    #[inline]
+    fn single_member(&mut self) -> Option<char> {
+        if let Some(a) = self.0.single_member() {
+            if self.1.contains(a) {
+                return Some(a);
+            }
+        } else if let Some(b) = self.1.single_member() {
+            if self.0.contains(b) {
+                return Some(b);
+            }
+        }
+        None
+    }
+}
+
+impl<A: CharClass> CharClass for Complement<A> {
+    #[inline]
+    fn contains(&mut self, c: char) -> bool {
+        !self.0.contains(c)
+    }
+}
+
+impl<T, A: ElemClass<T>, B: ElemClass<T>> ElemClass<T> for Union<A, B> {
+    #[inline]
+    fn contains(&mut self, elem: &T) -> bool {
+        self.0.contains(elem) || self.1.contains(elem)
+    }
+}
+
+impl<T, A: ElemClass<T>, B: ElemClass<T>> ElemClass<T> for Intersection<A, B> {
+    #[inline]
+    fn contains(&mut self, elem: &T) -> bool {
+        self.0.contains(elem) && self.1.contains(elem)
+    }
+}
+
+impl<T, A: ElemClass<T>> ElemClass<T> for Complement<A> {
+    #[inline]
+    fn contains(&mut self, elem: &T) -> bool {
+        !self.0.contains(elem)
+    }
+}
+
+impl<A, B> ByteMembers for Union<A, B> {
+    #[inline]
+    fn byte_members(&mut self) -> Option<[u64; 4]> {
+        let a = self.0.byte_members()?;
+        let b = self.1.byte_members()?;
+        Some([a[0] | b[0], a[1] | b[1], a[2] | b[2], a[3] | b[3]])
+    }
+}
+
+impl<A: ElemClass<u8>, B: ElemClass<u8>> ByteMembers for Intersection<A, B> {
+    #[inline]
+    fn byte_members(&mut self) -> Option<[u64; 4]> {
+        let (a, b) = match (self.0.byte_members(), self.1.byte_members()) {
+            (Some(a), Some(b)) => (a, b),
+            (Some(a), None) => (a, byte_bitmap(|x| has_byte(&a, x) && self.1.contains(&x))),
+            (None, Some(b)) => (byte_bitmap(|x| has_byte(&b, x) && self.0.contains(&x)), b),
+            (None, None) => return None,
+        };
+        Some([a[0] & b[0], a[1] & b[1], a[2] & b[2], a[3] & b[3]])
+    }
+}
+
+impl<A> ByteMembers for Complement<A> {
+    #[inline]
+    fn byte_members(&mut self) -> Option<[u64; 4]> {
+        let a = self.0.byte_members()?;
+        Some([!a[0], !a[1], !a[2], !a[3]])
+    }
+}
+
+//------------------------------------------------------------------------------
+// Searchers
+//------------------------------------------------------------------------------
+
+/// How the members of a character class are searched for.
+#[derive(Clone, Debug)]
+enum CharClassKind {
+    /// All members are ASCII characters, the string is searched byte-wise.
+    Ascii(Bytes),
+    /// The class has a single member.
+    Char(CharSearcher),
+    /// Every character is checked against the class.
+    Chars,
+}
+
+/// Searcher of a character class.
+#[derive(Clone, Debug)]
+pub struct CharClassSearcher<C> {
+    class: C,
+    kind: CharClassKind,
+}
+
+impl<C: CharClass> CharClassSearcher<C> {
+    #[inline]
+    fn new(mut class: C) -> Self {
+        let kind = if let Some(mut bitmap) = class.ascii_members() {
+            let mut members = [0u8; 128];
+            let mut count = 0;
+            while bitmap != 0 {
+                members[count] = bitmap.trailing_zeros() as u8;
+                bitmap &= bitmap - 1;
+                count += 1;
+            }
+            CharClassKind::Ascii(Bytes::new(&members[..count]))
+        } else if let Some(c) = class.single_member() {
+            CharClassKind::Char(CharSearcher::new(c))
+        } else {
+            CharClassKind::Chars
+        };
+        CharClassSearcher { class, kind }
+    }
+
+    #[inline]
+    fn chars(&mut self) -> MultiCharSearcher<impl FnMut(char) -> bool + '_> {
+        let class = &mut self.class;
+        MultiCharSearcher::new(move |c| class.contains(c))
+    }
+}
+
+unsafe impl<C: CharClass> Searcher<str> for CharClassSearcher<C> {
+    #[inline]
+    fn search(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        match self.kind {
+            CharClassKind::Ascii(ascii) => ascii.search(span.as_bytes()),
+            CharClassKind::Char(ref mut searcher) => searcher.search(span),
+            CharClassKind::Chars => self.chars().search(span),
+        }
+    }
+}
+
+unsafe impl<C: CharClass> ReverseSearcher<str> for CharClassSearcher<C> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        match self.kind {
+            CharClassKind::Ascii(ascii) => ascii.rsearch(span.as_bytes()),
+            CharClassKind::Char(ref mut searcher) => searcher.rsearch(span),
+            CharClassKind::Chars => self.chars().rsearch(span),
+        }
+    }
+}
+
+unsafe impl<C: CharClass> Consumer<str> for CharClassSearcher<C> {
+    #[inline]
+    fn consume(&mut self, span: Span<&str>) -> Option<usize> {
+        match self.kind {
+            CharClassKind::Ascii(ascii) => ascii.consume(span.as_bytes()),
+            CharClassKind::Char(ref mut searcher) => searcher.consume(span),
+            CharClassKind::Chars => self.chars().consume(span),
+        }
+    }
+
+    #[inline]
+    fn trim_start(&mut self, hay: &str) -> usize {
+        match self.kind {
+            CharClassKind::Ascii(ascii) => ascii.trim_start(hay.as_bytes()),
+            CharClassKind::Char(ref mut searcher) => searcher.trim_start(hay),
+            CharClassKind::Chars => self.chars().trim_start(hay),
+        }
+    }
+}
+
+unsafe impl<C: CharClass> ReverseConsumer<str> for CharClassSearcher<C> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&str>) -> Option<usize> {
+        match self.kind {
+            CharClassKind::Ascii(ascii) => ascii.rconsume(span.as_bytes()),
+            CharClassKind::Char(ref mut searcher) => searcher.rconsume(span),
+            CharClassKind::Chars => self.chars().rconsume(span),
+        }
+    }
+
+    #[inline]
+    fn trim_end(&mut self, hay: &str) -> usize {
+        match self.kind {
+            CharClassKind::Ascii(ascii) => ascii.trim_end(hay.as_bytes()),
+            CharClassKind::Char(ref mut searcher) => searcher.trim_end(hay),
+            CharClassKind::Chars => self.chars().trim_end(hay),
+        }
+    }
+}
+
+unsafe impl<C: CharClass> DoubleEndedSearcher<str> for CharClassSearcher<C> {}
+unsafe impl<C: CharClass> DoubleEndedConsumer<str> for CharClassSearcher<C> {}
+
+/// Searches for the members of an element class, specialized to use the
+/// members computed ahead for classes of bytes.
+trait ClassElem: Sized {
+    fn members<C>(class: &mut C) -> Option<Bytes>;
+    fn search<C: ElemClass<Self>>(s: &mut ElemClassSearcher<C>, span: Span<&[Self]>) -> Option<Range<usize>>;
+    fn rsearch<C: ElemClass<Self>>(s: &mut ElemClassSearcher<C>, span: Span<&[Self]>) -> Option<Range<usize>>;
+    fn consume<C: ElemClass<Self>>(s: &mut ElemClassSearcher<C>, span: Span<&[Self]>) -> Option<usize>;
+    fn rconsume<C: ElemClass<Self>>(s: &mut ElemClassSearcher<C>, span: Span<&[Self]>) -> Option<usize>;
+    fn trim_start<C: ElemClass<Self>>(s: &mut ElemClassSearcher<C>, hay: &[Self]) -> usize;
+    fn trim_end<C: ElemClass<Self>>(s: &mut ElemClassSearcher<C>, hay: &[Self]) -> usize;
+}
+
+impl<T> ClassElem for T {
+    #[inline]
+    default fn members<C>(_: &mut C) -> Option<Bytes> {
+        None
+    }
+
+    #[inline]
+    default fn search<C: ElemClass<T>>(s: &mut ElemClassSearcher<C>, span: Span<&[T]>) -> Option<Range<usize>> {
+        s.elems().search(span)
+    }
+
+    #[inline]
+    default fn rsearch<C: ElemClass<T>>(s: &mut ElemClassSearcher<C>, span: Span<&[T]>) -> Option<Range<usize>> {
+        s.elems().rsearch(span)
+    }
+
+    #[inline]
+    default fn consume<C: ElemClass<T>>(s: &mut ElemClassSearcher<C>, span: Span<&[T]>) -> Option<usize> {
+        s.elems().consume(span)
+    }
+
+    #[inline]
+    default fn rconsume<C: ElemClass<T>>(s: &mut ElemClassSearcher<C>, span: Span<&[T]>) -> Option<usize> {
+        s.elems().rconsume(span)
+    }
+
+    #[inline]
+    default fn trim_start<C: ElemClass<T>>(s: &mut ElemClassSearcher<C>, hay: &[T]) -> usize {
+        s.elems().trim_start(hay)
+    }
+
+    #[inline]
+    default fn trim_end<C: ElemClass<T>>(s: &mut ElemClassSearcher<C>, hay: &[T]) -> usize {
+        s.elems().trim_end(hay)
+    }
+}
+
+impl ClassElem for u8 {
+    #[inline]
+    fn members<C>(class: &mut C) -> Option<Bytes> {
+        class.byte_members().map(Bytes::from_bitmap)
+    }
+
+    #[inline]
+    fn search<C: ElemClass<u8>>(s: &mut ElemClassSearcher<C>, span: Span<&[u8]>) -> Option<Range<usize>> {
+        match s.bytes {
+            Some(bytes) => bytes.search(span),
+            None => s.elems().search(span),
+        }
+    }
+
+    #[inline]
+    fn rsearch<C: ElemClass<u8>>(s: &mut ElemClassSearcher<C>, span: Span<&[u8]>) -> Option<Range<usize>> {
+        match s.bytes {
+            Some(bytes) => bytes.rsearch(span),
+            None => s.elems().rsearch(span),
+        }
+    }
+
+    #[inline]
+    fn consume<C: ElemClass<u8>>(s: &mut ElemClassSearcher<C>, span: Span<&[u8]>) -> Option<usize> {
+        match s.bytes {
+            Some(bytes) => bytes.consume(span),
+            None => s.elems().consume(span),
+        }
+    }
+
+    #[inline]
+    fn rconsume<C: ElemClass<u8>>(s: &mut ElemClassSearcher<C>, span: Span<&[u8]>) -> Option<usize> {
+        match s.bytes {
+            Some(bytes) => bytes.rconsume(span),
+            None => s.elems().rconsume(span),
+        }
+    }
+
+    #[inline]
+    fn trim_start<C: ElemClass<u8>>(s: &mut ElemClassSearcher<C>, hay: &[u8]) -> usize {
+        match s.bytes {
+            Some(bytes) => bytes.trim_start(hay),
+            None => s.elems().trim_start(hay),
+        }
+    }
+
+    #[inline]
+    fn trim_end<C: ElemClass<u8>>(s: &mut ElemClassSearcher<C>, hay: &[u8]) -> usize {
+        match s.bytes {
+            Some(bytes) => bytes.trim_end(hay),
+            None => s.elems().trim_end(hay),
+        }
+    }
+}
+
+/// Searcher of an element class.
+#[derive(Clone, Debug)]
+pub struct ElemClassSearcher<C> {
+    class: C,
+    /// The members of the class, if it is a class of bytes whose members are
+    /// known.
+    bytes: Option<Bytes>,
+}
+
+impl<C> ElemClassSearcher<C> {
+    #[inline]
+    fn new<T>(mut class: C) -> Self
+    where
+        C: ElemClass<T>,
+    {
+        let bytes = T::members(&mut class);
+        ElemClassSearcher { class, bytes }
+    }
+
+    #[inline]
+    fn elems<T>(&mut self) -> ElemSearcher<impl FnMut(&T) -> bool + '_>
+    where
+        C: ElemClass<T>,
+    {
+        let class = &mut self.class;
+        ElemSearcher::new(move |x: &T| class.contains(x))
+    }
+}
+
+unsafe impl<T, C: ElemClass<T>> Searcher<[T]> for ElemClassSearcher<C> {
+    #[inline]
+    fn search(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        T::search(self, span)
+    }
+}
+
+unsafe impl<T, C: ElemClass<T>> ReverseSearcher<[T]> for ElemClassSearcher<C> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        T::rsearch(self, span)
+    }
+}
+
+unsafe impl<T, C: ElemClass<T>> Consumer<[T]> for ElemClassSearcher<C> {
+    #[inline]
+    fn consume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        T::consume(self, span)
+    }
+
+    #[inline]
+    fn trim_start(&mut self, hay: &[T]) -> usize {
+        T::trim_start(self, hay)
+    }
+}
+
+unsafe impl<T, C: ElemClass<T>> ReverseConsumer<[T]> for ElemClassSearcher<C> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        T::rconsume(self, span)
+    }
+
+    #[inline]
+    fn trim_end(&mut self, hay: &[T]) -> usize {
+        T::trim_end(self, hay)
+    }
+}
+
+unsafe impl<T, C: ElemClass<T>> DoubleEndedSearcher<[T]> for ElemClassSearcher<C> {}
+unsafe impl<T, C: ElemClass<T>> DoubleEndedConsumer<[T]> for ElemClassSearcher<C> {}
+
+//------------------------------------------------------------------------------
+// Needles
+//------------------------------------------------------------------------------
+
+macro_rules! impl_needle {
+    ([$($gen:tt)*] $class:ty) => {
+        impl<'h, $($gen)*> Needle<&'h str> for $class
+        where
+            $class: CharClass,
+        {
+            type Searcher = CharClassSearcher<Self>;
+            type Consumer = CharClassSearcher<Self>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                CharClassSearcher::new(self)
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                CharClassSearcher::new(self)
+            }
+        }
+
+        impl<'h, $($gen)*> Needle<&'h mut str> for $class
+        where
+            $class: CharClass,
+        {
+            type Searcher = CharClassSearcher<Self>;
+            type Consumer = CharClassSearcher<Self>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                CharClassSearcher::new(self)
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                CharClassSearcher::new(self)
+            }
+        }
+
+        impl<'h, T, $($gen)*> Needle<&'h [T]> for $class
+        where
+            $class: ElemClass<T>,
+        {
+            type Searcher = ElemClassSearcher<Self>;
+            type Consumer = ElemClassSearcher<Self>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                ElemClassSearcher::new(self)
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                ElemClassSearcher::new(self)
+            }
+        }
+
+        impl<'h, T, $($gen)*> Needle<&'h mut [T]> for $class
+        where
+            $class: ElemClass<T>,
+        {
+            type Searcher = ElemClassSearcher<Self>;
+            type Consumer = ElemClassSearcher<Self>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                ElemClassSearcher::new(self)
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                ElemClassSearcher::new(self)
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl<T, $($gen)*> Needle<Vec<T>> for $class
+        where
+            $class: ElemClass<T>,
+        {
+            type Searcher = ElemClassSearcher<Self>;
+            type Consumer = ElemClassSearcher<Self>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                ElemClassSearcher::new(self)
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                ElemClassSearcher::new(self)
+            }
+        }
+    }
+}
+
+impl_needle!([A, B] Union<A, B>);
+impl_needle!([A, B] Intersection<A, B>);
+impl_needle!([A] Complement<A>);
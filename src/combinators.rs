@@ -0,0 +1,1302 @@
+//! Needle combinators.
+//!
+//! This module provides ways to compose existing needles into a larger one.
+//! Sequences and repetitions are built from the consumers of their parts, so
+//! they can be used with [`starts_with`](::ext::starts_with) and
+//! [`trim_start`](::ext::trim_start) directly, and are searched by trying to
+//! consume the needle at every position of the haystack.
+//!
+//! Composition is greedy without backtracking: each part consumes as much as
+//! its own consumer does, and the remaining parts must match right after it.
+//!
+//! Lookaround wrappers like [`NeedleExt::preceded_by`] restrict the matches of
+//! a needle by checking the content around them, which is looked up in the
+//! whole hay. Unique haystacks like `&mut str` discard the content outside the
+//! searched range, so the context is only fully visible in shared haystacks.
+//!
+//! ```rust
+//! extern crate pattern_3;
+//! use pattern_3::combinators::{repeat, NeedleExt};
+//! use pattern_3::ext::{match_ranges, trim_start};
+//!
+//! let digits = repeat(|c: char| c.is_ascii_digit(), 1..);
+//! assert_eq!(
+//!     match_ranges("at 12:30, 7:05", digits.then(':')).collect::<Vec<_>>(),
+//!     vec![(3..6, "12:"), (10..12, "7:")]
+//! );
+//!
+//! assert_eq!(trim_start("ababa", repeat("ab", ..)), "a");
+//! ```
+
+use needle::*;
+use haystack::{Hay, Haystack, Span};
+use std::ops::{Bound, Range, RangeBounds};
+
+//------------------------------------------------------------------------------
+// Scanning searcher
+//------------------------------------------------------------------------------
+
+/// Searcher which tries to consume at every position of the haystack.
+///
+/// Just like other searchers, after a match is found, an empty match at the
+/// start of the next span is skipped, so that iterating over all matches
+/// always makes progress.
+#[derive(Clone, Debug)]
+pub struct ScanSearcher<C> {
+    consumer: C,
+    allow_empty_match_front: bool,
+    allow_empty_match_back: bool,
+}
+
+impl<C> ScanSearcher<C> {
+    #[inline]
+    fn new(consumer: C) -> Self {
+        ScanSearcher {
+            consumer,
+            allow_empty_match_front: true,
+            allow_empty_match_back: true,
+        }
+    }
+}
+
+unsafe impl<A, C> Searcher<A> for ScanSearcher<C>
+where
+    A: Hay + ?Sized,
+    C: Consumer<A>,
+{
+    fn search(&mut self, span: Span<&A>) -> Option<Range<A::Index>> {
+        let (hay, range) = span.into_parts();
+        let mut start = range.start;
+        loop {
+            let span = unsafe { Span::from_parts(hay, start..range.end) };
+            if let Some(end) = self.consumer.consume(span) {
+                if end != start || start != range.start || self.allow_empty_match_front {
+                    self.allow_empty_match_front = false;
+                    return Some(start..end);
+                }
+            }
+            if start == range.end {
+                return None;
+            }
+            start = unsafe { hay.next_index(start) };
+        }
+    }
+}
+
+unsafe impl<A, C> ReverseSearcher<A> for ScanSearcher<C>
+where
+    A: Hay + ?Sized,
+    C: ReverseConsumer<A>,
+{
+    fn rsearch(&mut self, span: Span<&A>) -> Option<Range<A::Index>> {
+        let (hay, range) = span.into_parts();
+        let mut end = range.end;
+        loop {
+            let span = unsafe { Span::from_parts(hay, range.start..end) };
+            if let Some(start) = self.consumer.rconsume(span) {
+                if start != end || end != range.end || self.allow_empty_match_back {
+                    self.allow_empty_match_back = false;
+                    return Some(start..end);
+                }
+            }
+            if end == range.start {
+                return None;
+            }
+            end = unsafe { hay.prev_index(end) };
+        }
+    }
+}
+
+unsafe impl<A, C> CapturingSearcher<A> for ScanSearcher<C>
+where
+    A: Hay + ?Sized,
+{
+    #[inline]
+    fn captures_len(&self) -> usize {
+        CapturingSearcher::<A>::captures_len(&self.consumer)
+    }
+
+    #[inline]
+    fn read_captures(&self, captures: &mut [Option<Range<A::Index>>]) {
+        CapturingSearcher::<A>::read_captures(&self.consumer, captures)
+    }
+}
+
+//------------------------------------------------------------------------------
+// Sequence
+//------------------------------------------------------------------------------
+
+/// A needle matching two needles one after another, created by
+/// [`NeedleExt::then`].
+#[derive(Copy, Clone, Debug)]
+pub struct Then<P, Q>(P, Q);
+
+/// Consumer of [`Then`].
+#[derive(Clone, Debug)]
+pub struct ThenConsumer<C, D>(C, D);
+
+unsafe impl<A, C, D> Consumer<A> for ThenConsumer<C, D>
+where
+    A: Hay + ?Sized,
+    C: Consumer<A>,
+    D: Consumer<A>,
+{
+    #[inline]
+    fn consume(&mut self, span: Span<&A>) -> Option<A::Index> {
+        let (hay, range) = span.clone().into_parts();
+        let mid = self.0.consume(span)?;
+        self.1.consume(unsafe { Span::from_parts(hay, mid..range.end) })
+    }
+}
+
+unsafe impl<A, C, D> ReverseConsumer<A> for ThenConsumer<C, D>
+where
+    A: Hay + ?Sized,
+    C: ReverseConsumer<A>,
+    D: ReverseConsumer<A>,
+{
+    #[inline]
+    fn rconsume(&mut self, span: Span<&A>) -> Option<A::Index> {
+        let (hay, range) = span.clone().into_parts();
+        let mid = self.1.rconsume(span)?;
+        self.0.rconsume(unsafe { Span::from_parts(hay, range.start..mid) })
+    }
+}
+
+unsafe impl<A, C, D> CapturingSearcher<A> for ThenConsumer<C, D>
+where
+    A: Hay + ?Sized,
+{
+    #[inline]
+    fn captures_len(&self) -> usize {
+        CapturingSearcher::<A>::captures_len(&self.0) + CapturingSearcher::<A>::captures_len(&self.1)
+    }
+
+    #[inline]
+    fn read_captures(&self, captures: &mut [Option<Range<A::Index>>]) {
+        let (first, second) = captures.split_at_mut(CapturingSearcher::<A>::captures_len(&self.0));
+        CapturingSearcher::<A>::read_captures(&self.0, first);
+        CapturingSearcher::<A>::read_captures(&self.1, second);
+    }
+}
+
+impl<H, P, Q> Needle<H> for Then<P, Q>
+where
+    H: Haystack,
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+    P: Needle<H>,
+    Q: Needle<H>,
+{
+    type Searcher = ScanSearcher<ThenConsumer<P::Consumer, Q::Consumer>>;
+    type Consumer = ThenConsumer<P::Consumer, Q::Consumer>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        ScanSearcher::new(Needle::<H>::into_consumer(self))
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        ThenConsumer(self.0.into_consumer(), self.1.into_consumer())
+    }
+}
+
+/// Extension methods for combining needles.
+pub trait NeedleExt: Sized {
+    /// Creates a needle which matches `self` immediately followed by `next`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// extern crate pattern_3;
+    /// use pattern_3::combinators::NeedleExt;
+    /// use pattern_3::ext::{find, starts_with};
+    ///
+    /// assert!(starts_with("v1.2", 'v'.then(char::is_numeric)));
+    /// assert_eq!(find("if (x)", "if".then(' ').then('(')), Some(0));
+    /// ```
+    #[inline]
+    fn then<Q>(self, next: Q) -> Then<Self, Q> {
+        Then(self, next)
+    }
+
+    /// Creates a needle which matches `self` only when it immediately follows
+    /// a match of `context`.
+    ///
+    /// The context is not part of the match, and is looked up in the whole
+    /// hay, even outside the searched range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// extern crate pattern_3;
+    /// use pattern_3::combinators::NeedleExt;
+    /// use pattern_3::ext::match_indices;
+    ///
+    /// assert_eq!(
+    ///     match_indices("$1 + 2 + $3", char::is_numeric.preceded_by('$')).collect::<Vec<_>>(),
+    ///     vec![(1, "1"), (10, "3")]
+    /// );
+    /// ```
+    #[inline]
+    fn preceded_by<Q>(self, context: Q) -> PrecededBy<Self, Q> {
+        PrecededBy { needle: self, context, negated: false }
+    }
+
+    /// Creates a needle which matches `self` only when it does not
+    /// immediately follow a match of `context`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// extern crate pattern_3;
+    /// use pattern_3::combinators::NeedleExt;
+    /// use pattern_3::ext::split;
+    ///
+    /// assert_eq!(
+    ///     split(r"a:b\:c:d", ':'.not_preceded_by('\\')).collect::<Vec<_>>(),
+    ///     vec!["a", r"b\:c", "d"]
+    /// );
+    /// ```
+    #[inline]
+    fn not_preceded_by<Q>(self, context: Q) -> PrecededBy<Self, Q> {
+        PrecededBy { needle: self, context, negated: true }
+    }
+
+    /// Creates a needle which matches `self` only when it is immediately
+    /// followed by a match of `context`.
+    ///
+    /// The context is not part of the match, and is looked up in the whole
+    /// hay, even outside the searched range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// extern crate pattern_3;
+    /// use pattern_3::combinators::NeedleExt;
+    /// use pattern_3::ext::find;
+    ///
+    /// let negative = '-'.followed_by(|c: char| c.is_ascii_digit());
+    /// assert_eq!(find("a - b, -1", negative), Some(7));
+    /// ```
+    #[inline]
+    fn followed_by<Q>(self, context: Q) -> FollowedBy<Self, Q> {
+        FollowedBy { needle: self, context, negated: false }
+    }
+
+    /// Creates a needle which matches `self` only when it is not immediately
+    /// followed by a match of `context`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// extern crate pattern_3;
+    /// use pattern_3::combinators::NeedleExt;
+    /// use pattern_3::ext::rfind;
+    ///
+    /// assert_eq!(rfind("x = y;\nz", ';'.not_followed_by('\n')), None);
+    /// assert_eq!(rfind("x = y; z", ';'.not_followed_by('\n')), Some(5));
+    /// ```
+    #[inline]
+    fn not_followed_by<Q>(self, context: Q) -> FollowedBy<Self, Q> {
+        FollowedBy { needle: self, context, negated: true }
+    }
+}
+
+impl<P> NeedleExt for P {}
+
+//------------------------------------------------------------------------------
+// Repetition
+//------------------------------------------------------------------------------
+
+/// A needle matching consecutive repetitions of a needle, created by
+/// [`repeat`].
+#[derive(Copy, Clone, Debug)]
+pub struct Repeat<P> {
+    needle: P,
+    min: usize,
+    max: usize,
+}
+
+/// Creates a needle which matches `needle` repeated consecutively, with the
+/// number of repetitions inside `count`.
+///
+/// The repetition is greedy: as many repetitions as possible (up to the upper
+/// bound of `count`) are consumed. If the needle matches an empty string, all
+/// remaining repetitions are considered matched.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::combinators::repeat;
+/// use pattern_3::ext::{matches, starts_with};
+///
+/// assert_eq!(
+///     matches("aaaaaaa", repeat('a', 2..=3)).collect::<Vec<_>>(),
+///     vec!["aaa", "aaa"]
+/// );
+/// assert!(starts_with(&[0, 0, 1][..], repeat(&[0][..], 2..)));
+/// assert!(!starts_with(&[0, 1][..], repeat(&[0][..], 2..)));
+/// ```
+#[inline]
+pub fn repeat<P, R: RangeBounds<usize>>(needle: P, count: R) -> Repeat<P> {
+    let min = match count.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let max = match count.end_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&0) => return Repeat { needle, min: 1, max: 0 },
+        Bound::Excluded(&n) => n - 1,
+        Bound::Unbounded => usize::MAX,
+    };
+    Repeat { needle, min, max }
+}
+
+/// Consumer of [`Repeat`].
+#[derive(Clone, Debug)]
+pub struct RepeatConsumer<C> {
+    consumer: C,
+    min: usize,
+    max: usize,
+    /// Whether the needle matched at least once in the most recent match.
+    matched: bool,
+}
+
+impl<C> RepeatConsumer<C> {
+    /// Repeatedly calls `step` until it fails, makes no progress or the
+    /// maximum count is reached. Returns the final position if the minimum
+    /// count is reached.
+    ///
+    /// If `restore` is true and the final call of `step` failed, the last
+    /// successful step is repeated, so that the consumer reports the captures
+    /// of the last repetition.
+    #[inline]
+    fn run<I, F>(&mut self, mut pos: I, restore: bool, mut step: F) -> Option<I>
+    where
+        I: Copy + Eq,
+        F: FnMut(&mut C, I) -> Option<I>,
+    {
+        let mut count = 0;
+        let mut last_step = None;
+        let mut failed = false;
+        while count < self.max {
+            match step(&mut self.consumer, pos) {
+                Some(next) if next != pos => {
+                    last_step = Some(pos);
+                    pos = next;
+                    count += 1;
+                }
+                Some(_) => {
+                    last_step = Some(pos);
+                    count = count.max(self.min);
+                    break;
+                }
+                None => {
+                    failed = true;
+                    break;
+                }
+            }
+        }
+        if self.min <= count && count <= self.max {
+            self.matched = last_step.is_some();
+            if let (true, true, Some(last_step)) = (restore, failed, last_step) {
+                step(&mut self.consumer, last_step);
+            }
+            Some(pos)
+        } else {
+            None
+        }
+    }
+}
+
+unsafe impl<A, C> Consumer<A> for RepeatConsumer<C>
+where
+    A: Hay + ?Sized,
+    C: Consumer<A>,
+{
+    #[inline]
+    fn consume(&mut self, span: Span<&A>) -> Option<A::Index> {
+        let (hay, range) = span.into_parts();
+        let restore = CapturingSearcher::<A>::captures_len(&self.consumer) != 0;
+        self.run(range.start, restore, |consumer, pos| {
+            consumer.consume(unsafe { Span::from_parts(hay, pos..range.end) })
+        })
+    }
+}
+
+unsafe impl<A, C> ReverseConsumer<A> for RepeatConsumer<C>
+where
+    A: Hay + ?Sized,
+    C: ReverseConsumer<A>,
+{
+    #[inline]
+    fn rconsume(&mut self, span: Span<&A>) -> Option<A::Index> {
+        let (hay, range) = span.into_parts();
+        let restore = CapturingSearcher::<A>::captures_len(&self.consumer) != 0;
+        self.run(range.end, restore, |consumer, pos| {
+            consumer.rconsume(unsafe { Span::from_parts(hay, range.start..pos) })
+        })
+    }
+}
+
+unsafe impl<A, C> CapturingSearcher<A> for RepeatConsumer<C>
+where
+    A: Hay + ?Sized,
+{
+    #[inline]
+    fn captures_len(&self) -> usize {
+        CapturingSearcher::<A>::captures_len(&self.consumer)
+    }
+
+    #[inline]
+    fn read_captures(&self, captures: &mut [Option<Range<A::Index>>]) {
+        if self.matched {
+            CapturingSearcher::<A>::read_captures(&self.consumer, captures);
+        } else {
+            for c in captures {
+                *c = None;
+            }
+        }
+    }
+}
+
+impl<H, P> Needle<H> for Repeat<P>
+where
+    H: Haystack,
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+    P: Needle<H>,
+{
+    type Searcher = ScanSearcher<RepeatConsumer<P::Consumer>>;
+    type Consumer = RepeatConsumer<P::Consumer>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        ScanSearcher::new(Needle::<H>::into_consumer(self))
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        RepeatConsumer {
+            consumer: self.needle.into_consumer(),
+            min: self.min,
+            max: self.max,
+            matched: false,
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Alternation
+//------------------------------------------------------------------------------
+
+/// A needle matching any of several needles, created by [`any_of`].
+#[derive(Copy, Clone, Debug)]
+pub struct AnyOf<T> {
+    needles: T,
+    kind: MatchKind,
+}
+
+/// Creates a needle which matches any of the needles in the tuple.
+///
+/// The needles may be of different types, as long as all of them can be
+/// searched in the same haystack. Tuples of 2 to 8 needles are supported.
+///
+/// The leftmost match among all needles is reported (or the rightmost when
+/// searching backward). If several needles match at the same position, the
+/// one listed first is preferred by default, which can be changed using
+/// [`.match_kind()`](AnyOf::match_kind). The index of the needle which
+/// produced the most recent match can be retrieved using
+/// [`.branch()`](AnyOfSearcher::branch).
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::MatchKind;
+/// use pattern_3::combinators::any_of;
+/// use pattern_3::ext::{find, split, rsplit};
+///
+/// let needle = any_of(("-->", '\n', |c: char| c.is_ascii_digit()));
+/// assert_eq!(find("a --> b", needle), Some(2));
+/// assert_eq!(
+///     split("a-->b\nc1d", needle).collect::<Vec<_>>(),
+///     vec!["a", "b", "c", "d"]
+/// );
+/// assert_eq!(
+///     rsplit("a-->b\nc1d", needle).collect::<Vec<_>>(),
+///     vec!["d", "c", "b", "a"]
+/// );
+///
+/// let needle = any_of(("-", "->")).match_kind(MatchKind::LeftmostLongest);
+/// assert_eq!(split("a->b-c", needle).collect::<Vec<_>>(), vec!["a", "b", "c"]);
+/// ```
+#[inline]
+pub fn any_of<T>(needles: T) -> AnyOf<T> {
+    AnyOf {
+        needles,
+        kind: MatchKind::LeftmostFirst,
+    }
+}
+
+impl<T> AnyOf<T> {
+    /// Sets how to choose between needles matching at the same position.
+    #[inline]
+    pub fn match_kind(self, kind: MatchKind) -> Self {
+        AnyOf { kind, ..self }
+    }
+}
+
+/// Conditions a branch match must satisfy to be accepted.
+#[derive(Copy, Clone, Debug)]
+struct Filter {
+    /// Whether an empty match at the boundary of the range is accepted.
+    allow_empty_match: bool,
+    /// Whether any match at the boundary of the range is rejected, because a
+    /// preferred branch had an empty match rejected there.
+    blocked: bool,
+}
+
+impl Filter {
+    /// Checks whether a match touching the boundary of the range should be
+    /// rejected, and whether it was rejected for being empty.
+    #[inline]
+    fn check(self, m: &Range<usize>, at_boundary: bool) -> Result<(), bool> {
+        if !at_boundary {
+            Ok(())
+        } else if m.start == m.end && !self.allow_empty_match {
+            Err(true)
+        } else if self.blocked {
+            Err(false)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Searches a single branch, returning the first accepted match and whether
+/// an empty match at the start of the range has been rejected.
+fn search_branch<A, S>(
+    searcher: &mut S,
+    hay: &A,
+    range: Range<usize>,
+    filter: Filter,
+) -> (Option<Range<usize>>, bool)
+where
+    A: Hay<Index = usize> + ?Sized,
+    S: Searcher<A>,
+{
+    let mut rejected_empty = false;
+    let mut searched = range.clone();
+    let found = loop {
+        match searcher.search(unsafe { Span::from_parts(hay, searched.clone()) }) {
+            Some(m) => match filter.check(&m, m.start == range.start) {
+                Ok(()) => break Some(m),
+                Err(empty) => {
+                    rejected_empty |= empty;
+                    if searched.start == range.end {
+                        break None;
+                    }
+                    searched.start = unsafe { hay.next_index(searched.start) };
+                }
+            },
+            None => break None,
+        }
+    };
+    (found, rejected_empty)
+}
+
+/// Searches a single branch backward, returning the last accepted match and
+/// whether an empty match at the end of the range has been rejected.
+fn rsearch_branch<A, S>(
+    searcher: &mut S,
+    hay: &A,
+    range: Range<usize>,
+    filter: Filter,
+) -> (Option<Range<usize>>, bool)
+where
+    A: Hay<Index = usize> + ?Sized,
+    S: ReverseSearcher<A>,
+{
+    let mut rejected_empty = false;
+    let mut searched = range.clone();
+    let found = loop {
+        match searcher.rsearch(unsafe { Span::from_parts(hay, searched.clone()) }) {
+            Some(m) => match filter.check(&m, m.end == range.end) {
+                Ok(()) => break Some(m),
+                Err(empty) => {
+                    rejected_empty |= empty;
+                    if searched.end == range.start {
+                        break None;
+                    }
+                    searched.end = unsafe { hay.prev_index(searched.end) };
+                }
+            },
+            None => break None,
+        }
+    };
+    (found, rejected_empty)
+}
+
+/// Searcher and consumer of [`AnyOf`].
+///
+/// Besides implementing the searcher and consumer traits, this type also
+/// remembers which needle produced the most recent match, which can be
+/// retrieved using [`.branch()`](AnyOfSearcher::branch).
+#[derive(Clone, Debug)]
+pub struct AnyOfSearcher<T> {
+    searchers: T,
+    kind: MatchKind,
+    allow_empty_match_front: bool,
+    allow_empty_match_back: bool,
+    /// The branch and position of the most recent match if it was empty.
+    ///
+    /// The branch would match there again, so the following branches must
+    /// not match at that position either.
+    empty_front: Option<(usize, usize)>,
+    empty_back: Option<(usize, usize)>,
+    branch: Option<usize>,
+}
+
+impl<T> AnyOfSearcher<T> {
+    /// Returns the index of the needle which produced the most recent match,
+    /// or `None` if nothing was matched.
+    #[inline]
+    pub fn branch(&self) -> Option<usize> {
+        self.branch
+    }
+
+    /// Picks the better of the current best candidate and a new one.
+    ///
+    /// `forward` selects whether the candidates are compared by their start
+    /// (leftmost) or end (rightmost). If the branch had an empty match
+    /// rejected at the boundary, it would have been preferred over the later
+    /// branches, so their matches at the boundary are rejected as well.
+    #[inline]
+    fn choose(
+        &self,
+        best: &mut Option<(usize, Range<usize>)>,
+        filter: &mut Filter,
+        i: usize,
+        m: Option<Range<usize>>,
+        rejected_empty: bool,
+        forward: bool,
+    ) {
+        filter.blocked |= rejected_empty && self.kind == MatchKind::LeftmostFirst;
+        let m = match m {
+            Some(m) => m,
+            None => return,
+        };
+        let better = match *best {
+            None => true,
+            Some((_, ref b)) => {
+                let (pos, best_pos) = if forward { (b.start, m.start) } else { (m.end, b.end) };
+                best_pos < pos || best_pos == pos && match self.kind {
+                    MatchKind::LeftmostFirst => false,
+                    MatchKind::LeftmostLongest => m.end - m.start > b.end - b.start,
+                }
+            }
+        };
+        if better {
+            *best = Some((i, m));
+        }
+    }
+
+    #[inline]
+    fn report(&mut self, best: Option<(usize, Range<usize>)>) -> Option<Range<usize>> {
+        self.branch = best.as_ref().map(|b| b.0);
+        best.map(|b| b.1)
+    }
+
+    /// Picks the better of the current best consumed position and a new one.
+    #[inline]
+    fn choose_consumed(&self, best: &mut Option<(usize, usize)>, i: usize, pos: Option<usize>, forward: bool) {
+        let pos = match pos {
+            Some(pos) => pos,
+            None => return,
+        };
+        let better = match (*best, self.kind) {
+            (None, _) => true,
+            (Some(_), MatchKind::LeftmostFirst) => false,
+            (Some((_, b)), MatchKind::LeftmostLongest) => if forward { pos > b } else { pos < b },
+        };
+        if better {
+            *best = Some((i, pos));
+        }
+    }
+}
+
+macro_rules! impl_any_of {
+    ($($idx:tt: $P:ident $S:ident),+) => {
+        impl<H, $($P),+> Needle<H> for AnyOf<($($P,)+)>
+        where
+            H: Haystack,
+            H::Target: Hay<Index = usize>,
+            $($P: Needle<H>,)+
+        {
+            type Searcher = AnyOfSearcher<($($P::Searcher,)+)>;
+            type Consumer = AnyOfSearcher<($($P::Consumer,)+)>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                AnyOfSearcher {
+                    searchers: ($(self.needles.$idx.into_searcher(),)+),
+                    kind: self.kind,
+                    allow_empty_match_front: true,
+                    allow_empty_match_back: true,
+                    empty_front: None,
+                    empty_back: None,
+                    branch: None,
+                }
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                AnyOfSearcher {
+                    searchers: ($(self.needles.$idx.into_consumer(),)+),
+                    kind: self.kind,
+                    allow_empty_match_front: true,
+                    allow_empty_match_back: true,
+                    empty_front: None,
+                    empty_back: None,
+                    branch: None,
+                }
+            }
+        }
+
+        unsafe impl<A, $($S),+> Searcher<A> for AnyOfSearcher<($($S,)+)>
+        where
+            A: Hay<Index = usize> + ?Sized,
+            $($S: Searcher<A>,)+
+        {
+            fn search(&mut self, span: Span<&A>) -> Option<Range<usize>> {
+                let (hay, range) = span.into_parts();
+                let mut filter = Filter {
+                    allow_empty_match: self.allow_empty_match_front,
+                    blocked: false,
+                };
+                let mut best = None;
+                $(
+                    let (m, rejected_empty) = search_branch(
+                        &mut self.searchers.$idx,
+                        hay,
+                        range.clone(),
+                        filter,
+                    );
+                    let rejected_empty = rejected_empty || self.empty_front == Some(($idx, range.start));
+                    self.choose(&mut best, &mut filter, $idx, m, rejected_empty, true);
+                )+
+                if best.is_some() {
+                    self.allow_empty_match_front = false;
+                }
+                self.empty_front = match best {
+                    Some((i, ref m)) if m.start == m.end => Some((i, m.start)),
+                    _ => None,
+                };
+                self.report(best)
+            }
+        }
+
+        unsafe impl<A, $($S),+> ReverseSearcher<A> for AnyOfSearcher<($($S,)+)>
+        where
+            A: Hay<Index = usize> + ?Sized,
+            $($S: ReverseSearcher<A>,)+
+        {
+            fn rsearch(&mut self, span: Span<&A>) -> Option<Range<usize>> {
+                let (hay, range) = span.into_parts();
+                let mut filter = Filter {
+                    allow_empty_match: self.allow_empty_match_back,
+                    blocked: false,
+                };
+                let mut best = None;
+                $(
+                    let (m, rejected_empty) = rsearch_branch(
+                        &mut self.searchers.$idx,
+                        hay,
+                        range.clone(),
+                        filter,
+                    );
+                    let rejected_empty = rejected_empty || self.empty_back == Some(($idx, range.end));
+                    self.choose(&mut best, &mut filter, $idx, m, rejected_empty, false);
+                )+
+                if best.is_some() {
+                    self.allow_empty_match_back = false;
+                }
+                self.empty_back = match best {
+                    Some((i, ref m)) if m.start == m.end => Some((i, m.end)),
+                    _ => None,
+                };
+                self.report(best)
+            }
+        }
+
+        unsafe impl<A, $($S),+> Consumer<A> for AnyOfSearcher<($($S,)+)>
+        where
+            A: Hay<Index = usize> + ?Sized,
+            $($S: Consumer<A>,)+
+        {
+            fn consume(&mut self, span: Span<&A>) -> Option<usize> {
+                let mut best = None;
+                $(
+                    let pos = self.searchers.$idx.consume(span.clone());
+                    self.choose_consumed(&mut best, $idx, pos, true);
+                )+
+                self.branch = best.map(|b| b.0);
+                best.map(|b| b.1)
+            }
+        }
+
+        unsafe impl<A, $($S),+> ReverseConsumer<A> for AnyOfSearcher<($($S,)+)>
+        where
+            A: Hay<Index = usize> + ?Sized,
+            $($S: ReverseConsumer<A>,)+
+        {
+            fn rconsume(&mut self, span: Span<&A>) -> Option<usize> {
+                let mut best = None;
+                $(
+                    let pos = self.searchers.$idx.rconsume(span.clone());
+                    self.choose_consumed(&mut best, $idx, pos, false);
+                )+
+                self.branch = best.map(|b| b.0);
+                best.map(|b| b.1)
+            }
+        }
+
+        unsafe impl<A, $($S),+> CapturingSearcher<A> for AnyOfSearcher<($($S,)+)>
+        where
+            A: Hay<Index = usize> + ?Sized,
+        {
+            fn captures_len(&self) -> usize {
+                0 $(+ CapturingSearcher::<A>::captures_len(&self.searchers.$idx))+
+            }
+
+            fn read_captures(&self, captures: &mut [Option<Range<usize>>]) {
+                let mut offset = 0;
+                $(
+                    let len = CapturingSearcher::<A>::captures_len(&self.searchers.$idx);
+                    let group = &mut captures[offset..(offset + len)];
+                    if self.branch == Some($idx) {
+                        CapturingSearcher::<A>::read_captures(&self.searchers.$idx, group);
+                    } else {
+                        for c in group {
+                            *c = None;
+                        }
+                    }
+                    offset += len;
+                )+
+                let _ = offset;
+            }
+        }
+    }
+}
+
+impl_any_of!(0: P0 S0, 1: P1 S1);
+impl_any_of!(0: P0 S0, 1: P1 S1, 2: P2 S2);
+impl_any_of!(0: P0 S0, 1: P1 S1, 2: P2 S2, 3: P3 S3);
+impl_any_of!(0: P0 S0, 1: P1 S1, 2: P2 S2, 3: P3 S3, 4: P4 S4);
+impl_any_of!(0: P0 S0, 1: P1 S1, 2: P2 S2, 3: P3 S3, 4: P4 S4, 5: P5 S5);
+impl_any_of!(0: P0 S0, 1: P1 S1, 2: P2 S2, 3: P3 S3, 4: P4 S4, 5: P5 S5, 6: P6 S6);
+impl_any_of!(0: P0 S0, 1: P1 S1, 2: P2 S2, 3: P3 S3, 4: P4 S4, 5: P5 S5, 6: P6 S6, 7: P7 S7);
+
+//------------------------------------------------------------------------------
+// Lookaround
+//------------------------------------------------------------------------------
+
+/// A needle matching only when preceded (or not) by another needle, created
+/// by [`NeedleExt::preceded_by`] and [`NeedleExt::not_preceded_by`].
+#[derive(Copy, Clone, Debug)]
+pub struct PrecededBy<P, Q> {
+    needle: P,
+    context: Q,
+    negated: bool,
+}
+
+/// A needle matching only when followed (or not) by another needle, created
+/// by [`NeedleExt::followed_by`] and [`NeedleExt::not_followed_by`].
+#[derive(Copy, Clone, Debug)]
+pub struct FollowedBy<P, Q> {
+    needle: P,
+    context: Q,
+    negated: bool,
+}
+
+/// Searcher and consumer of [`PrecededBy`].
+#[derive(Clone, Debug)]
+pub struct PrecededBySearcher<S, C> {
+    searcher: S,
+    // an unused copy of the searcher, to restart after a rejected match.
+    fresh: Option<S>,
+    context: C,
+    negated: bool,
+}
+
+/// Searcher and consumer of [`FollowedBy`].
+#[derive(Clone, Debug)]
+pub struct FollowedBySearcher<S, C> {
+    searcher: S,
+    // an unused copy of the searcher, to restart after a rejected match.
+    fresh: Option<S>,
+    context: C,
+    negated: bool,
+}
+
+impl<S, C> PrecededBySearcher<S, C> {
+    /// Checks whether the context condition holds before the match.
+    #[inline]
+    fn accepts<A>(&mut self, hay: &A, m: &Range<A::Index>) -> bool
+    where
+        A: Hay + ?Sized,
+        C: ReverseConsumer<A>,
+    {
+        let span = unsafe { Span::from_parts(hay, hay.start_index()..m.start) };
+        self.context.rconsume(span).is_some() != self.negated
+    }
+}
+
+impl<S, C> FollowedBySearcher<S, C> {
+    /// Checks whether the context condition holds after the match.
+    #[inline]
+    fn accepts<A>(&mut self, hay: &A, m: &Range<A::Index>) -> bool
+    where
+        A: Hay + ?Sized,
+        C: Consumer<A>,
+    {
+        let span = unsafe { Span::from_parts(hay, m.end..hay.end_index()) };
+        self.context.consume(span).is_some() != self.negated
+    }
+}
+
+/// Copies a searcher which has not searched yet, if it can be cloned.
+///
+/// A searcher skips an empty match at the start of the span following a
+/// match. When resuming after a rejected match, this would also skip an empty
+/// match which was never reported, so the lookaround searchers restart with a
+/// fresh copy instead.
+trait Pristine: Sized {
+    fn pristine(&self) -> Option<Self>;
+}
+
+impl<S> Pristine for S {
+    #[inline]
+    default fn pristine(&self) -> Option<Self> {
+        None
+    }
+}
+
+impl<S: Clone> Pristine for S {
+    #[inline]
+    fn pristine(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+}
+
+macro_rules! impl_lookaround {
+    ($searcher:ident, $context:ident, $needle:ident) => {
+        unsafe impl<A, S, C> Searcher<A> for $searcher<S, C>
+        where
+            A: Hay + ?Sized,
+            S: Searcher<A>,
+            C: $context<A>,
+        {
+            fn search(&mut self, span: Span<&A>) -> Option<Range<A::Index>> {
+                let (hay, range) = span.into_parts();
+                let mut restarted = None;
+                let mut start = range.start;
+                loop {
+                    let m = {
+                        let searcher = restarted.as_mut().unwrap_or(&mut self.searcher);
+                        searcher.search(unsafe { Span::from_parts(hay, start..range.end) })?
+                    };
+                    if self.accepts(hay, &m) {
+                        return Some(m);
+                    }
+                    if m.start == m.end {
+                        // The searcher skips this empty match when resuming
+                        // at the same position.
+                        start = m.start;
+                    } else {
+                        // A rejected match may overlap with an accepted one,
+                        // so resume right after its start.
+                        start = unsafe { hay.next_index(m.start) };
+                        restarted = self.fresh.as_ref().and_then(Pristine::pristine);
+                    }
+                }
+            }
+        }
+
+        unsafe impl<A, S, C> ReverseSearcher<A> for $searcher<S, C>
+        where
+            A: Hay + ?Sized,
+            S: ReverseSearcher<A>,
+            C: $context<A>,
+        {
+            fn rsearch(&mut self, span: Span<&A>) -> Option<Range<A::Index>> {
+                let (hay, range) = span.into_parts();
+                let mut restarted = None;
+                let mut end = range.end;
+                loop {
+                    let m = {
+                        let searcher = restarted.as_mut().unwrap_or(&mut self.searcher);
+                        searcher.rsearch(unsafe { Span::from_parts(hay, range.start..end) })?
+                    };
+                    if self.accepts(hay, &m) {
+                        return Some(m);
+                    }
+                    if m.start == m.end {
+                        end = m.end;
+                    } else {
+                        end = unsafe { hay.prev_index(m.end) };
+                        restarted = self.fresh.as_ref().and_then(Pristine::pristine);
+                    }
+                }
+            }
+        }
+
+        unsafe impl<A, S, C> Consumer<A> for $searcher<S, C>
+        where
+            A: Hay + ?Sized,
+            S: Consumer<A>,
+            C: $context<A>,
+        {
+            #[inline]
+            fn consume(&mut self, span: Span<&A>) -> Option<A::Index> {
+                let (hay, range) = span.clone().into_parts();
+                let end = self.searcher.consume(span)?;
+                if self.accepts(hay, &(range.start..end)) {
+                    Some(end)
+                } else {
+                    None
+                }
+            }
+        }
+
+        unsafe impl<A, S, C> ReverseConsumer<A> for $searcher<S, C>
+        where
+            A: Hay + ?Sized,
+            S: ReverseConsumer<A>,
+            C: $context<A>,
+        {
+            #[inline]
+            fn rconsume(&mut self, span: Span<&A>) -> Option<A::Index> {
+                let (hay, range) = span.clone().into_parts();
+                let start = self.searcher.rconsume(span)?;
+                if self.accepts(hay, &(start..range.end)) {
+                    Some(start)
+                } else {
+                    None
+                }
+            }
+        }
+
+        unsafe impl<A, S, C> CapturingSearcher<A> for $searcher<S, C>
+        where
+            A: Hay + ?Sized,
+        {
+            #[inline]
+            fn captures_len(&self) -> usize {
+                CapturingSearcher::<A>::captures_len(&self.searcher)
+            }
+
+            #[inline]
+            fn read_captures(&self, captures: &mut [Option<Range<A::Index>>]) {
+                CapturingSearcher::<A>::read_captures(&self.searcher, captures)
+            }
+        }
+
+        impl<H, P, Q> Needle<H> for $needle<P, Q>
+        where
+            H: Haystack,
+            H::Target: Hay, // FIXME: RFC 2089 or 2289
+            P: Needle<H>,
+            Q: Needle<H>,
+            Q::Consumer: $context<H::Target>,
+        {
+            type Searcher = $searcher<P::Searcher, Q::Consumer>;
+            type Consumer = $searcher<P::Consumer, Q::Consumer>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                let searcher = self.needle.into_searcher();
+                $searcher {
+                    fresh: searcher.pristine(),
+                    searcher,
+                    context: self.context.into_consumer(),
+                    negated: self.negated,
+                }
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                $searcher {
+                    searcher: self.needle.into_consumer(),
+                    fresh: None,
+                    context: self.context.into_consumer(),
+                    negated: self.negated,
+                }
+            }
+        }
+    }
+}
+
+impl_lookaround!(PrecededBySearcher, ReverseConsumer, PrecededBy);
+impl_lookaround!(FollowedBySearcher, Consumer, FollowedBy);
+
+//------------------------------------------------------------------------------
+// Capture groups
+//------------------------------------------------------------------------------
+
+/// A needle recording its matches as a capture group, created by
+/// [`capture`].
+#[derive(Copy, Clone, Debug)]
+pub struct Capture<P>(P);
+
+/// Creates a needle which matches the same as `needle`, and reports the
+/// matched range as a capture group.
+///
+/// The groups of a composite needle are numbered in the order they appear,
+/// starting from 1 (group 0 being the whole match). Groups inside a
+/// [`repeat`] report their last repetition, and groups inside the branches
+/// of [`any_of`] which did not match are reported as `None`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::combinators::{capture, repeat, NeedleExt};
+/// use pattern_3::ext::captures;
+///
+/// let digits = repeat(|c: char| c.is_ascii_digit(), 1..);
+/// let time = capture(digits).then(':').then(capture(digits));
+///
+/// let caps = captures("at 12:30", time).unwrap();
+/// assert_eq!(caps.get(0), Some("12:30"));
+/// assert_eq!(caps.get(1), Some("12"));
+/// assert_eq!(caps.get(2), Some("30"));
+/// ```
+#[inline]
+pub fn capture<P>(needle: P) -> Capture<P> {
+    Capture(needle)
+}
+
+/// Searcher and consumer of [`Capture`].
+#[derive(Clone, Debug)]
+pub struct CaptureSearcher<S, I> {
+    searcher: S,
+    last: Option<Range<I>>,
+}
+
+impl<S, I: Copy> CaptureSearcher<S, I> {
+    #[inline]
+    fn record(&mut self, range: Option<Range<I>>) -> Option<Range<I>> {
+        self.last = range.clone();
+        range
+    }
+}
+
+unsafe impl<A, S> Searcher<A> for CaptureSearcher<S, A::Index>
+where
+    A: Hay + ?Sized,
+    S: Searcher<A>,
+{
+    #[inline]
+    fn search(&mut self, span: Span<&A>) -> Option<Range<A::Index>> {
+        let range = self.searcher.search(span);
+        self.record(range)
+    }
+}
+
+unsafe impl<A, S> ReverseSearcher<A> for CaptureSearcher<S, A::Index>
+where
+    A: Hay + ?Sized,
+    S: ReverseSearcher<A>,
+{
+    #[inline]
+    fn rsearch(&mut self, span: Span<&A>) -> Option<Range<A::Index>> {
+        let range = self.searcher.rsearch(span);
+        self.record(range)
+    }
+}
+
+unsafe impl<A, S> Consumer<A> for CaptureSearcher<S, A::Index>
+where
+    A: Hay + ?Sized,
+    S: Consumer<A>,
+{
+    #[inline]
+    fn consume(&mut self, span: Span<&A>) -> Option<A::Index> {
+        let start = span.original_range().start;
+        let end = self.searcher.consume(span);
+        self.record(end.map(|end| start..end));
+        end
+    }
+}
+
+unsafe impl<A, S> ReverseConsumer<A> for CaptureSearcher<S, A::Index>
+where
+    A: Hay + ?Sized,
+    S: ReverseConsumer<A>,
+{
+    #[inline]
+    fn rconsume(&mut self, span: Span<&A>) -> Option<A::Index> {
+        let end = span.original_range().end;
+        let start = self.searcher.rconsume(span);
+        self.record(start.map(|start| start..end));
+        start
+    }
+}
+
+unsafe impl<A, S> DoubleEndedSearcher<A> for CaptureSearcher<S, A::Index>
+where
+    A: Hay + ?Sized,
+    S: DoubleEndedSearcher<A>,
+{}
+
+unsafe impl<A, S> DoubleEndedConsumer<A> for CaptureSearcher<S, A::Index>
+where
+    A: Hay + ?Sized,
+    S: DoubleEndedConsumer<A>,
+{}
+
+unsafe impl<A, S> CapturingSearcher<A> for CaptureSearcher<S, A::Index>
+where
+    A: Hay + ?Sized,
+{
+    #[inline]
+    fn captures_len(&self) -> usize {
+        1 + CapturingSearcher::<A>::captures_len(&self.searcher)
+    }
+
+    #[inline]
+    fn read_captures(&self, captures: &mut [Option<Range<A::Index>>]) {
+        captures[0] = self.last.clone();
+        CapturingSearcher::<A>::read_captures(&self.searcher, &mut captures[1..]);
+    }
+}
+
+impl<H, P> Needle<H> for Capture<P>
+where
+    H: Haystack,
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+    P: Needle<H>,
+{
+    type Searcher = CaptureSearcher<P::Searcher, <H::Target as Hay>::Index>;
+    type Consumer = CaptureSearcher<P::Consumer, <H::Target as Hay>::Index>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        CaptureSearcher {
+            searcher: self.0.into_searcher(),
+            last: None,
+        }
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        CaptureSearcher {
+            searcher: self.0.into_consumer(),
+            last: None,
+        }
+    }
+}
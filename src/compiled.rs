@@ -0,0 +1,111 @@
+use std::fmt;
+
+/// A needle which can be preprocessed once into a [`Compiled`] needle.
+///
+/// The searcher and consumer are created when compiling, and cloned for every
+/// search, so they should be cheap to clone.
+pub trait Compile: Sized {
+    /// The searcher created when compiling.
+    type Searcher: Clone;
+
+    /// The consumer created when compiling.
+    type Consumer: Clone;
+
+    /// Produces the searcher for this needle.
+    fn compile_searcher(&self) -> Self::Searcher;
+
+    /// Produces the consumer for this needle.
+    fn compile_consumer(&self) -> Self::Consumer;
+}
+
+/// A needle whose searcher and consumer are created in advance.
+///
+/// Searching a substring with the two-way algorithm first computes a
+/// factorization of the needle, which can take longer than the search itself
+/// when the haystacks are short. A compiled needle does this work once, and
+/// `&Compiled` can then be used as a needle many times, each use only cloning
+/// the prepared state.
+///
+/// Needles which can be compiled implement [`Compile`]. These are `&str`,
+/// `&[T]` and `&Wtf8`, and `&CharSet` for sets of characters. A compiled
+/// needle can be searched in the same haystacks as the needle itself, e.g. a
+/// compiled `&str` in both `str` and `Wtf8` haystacks, but a compiled `&[u8]`
+/// only in `[u8]` haystacks, since its matches could split a character:
+///
+/// ```compile_fail
+/// extern crate pattern_3;
+/// use pattern_3::Compiled;
+/// use pattern_3::ext::find;
+///
+/// let needle = Compiled::new(&b"\xa9"[..]);
+/// find("é", &needle);
+/// ```
+///
+/// A compiled needle is `Send` and `Sync` whenever its searcher and consumer
+/// are, so it can be shared across threads.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::Compiled;
+/// use pattern_3::ext::{split, find};
+///
+/// let separator = Compiled::new("::");
+/// let paths = ["std::ops::Range", "core::mem", "alloc"];
+/// let segments = paths.iter().map(|p| split(*p, &separator).count()).collect::<Vec<_>>();
+/// assert_eq!(segments, vec![3, 2, 1]);
+///
+/// let magic = Compiled::new(&b"\x7fELF"[..]);
+/// assert_eq!(find(&b"..\x7fELF.."[..], &magic), Some(2));
+/// ```
+pub struct Compiled<N: Compile> {
+    searcher: N::Searcher,
+    consumer: N::Consumer,
+}
+
+impl<N: Compile> Compiled<N> {
+    /// Preprocesses the needle.
+    #[inline]
+    pub fn new(needle: N) -> Self {
+        Compiled {
+            searcher: needle.compile_searcher(),
+            consumer: needle.compile_consumer(),
+        }
+    }
+
+    /// Returns a copy of the prepared searcher.
+    #[inline]
+    pub(crate) fn searcher(&self) -> N::Searcher {
+        self.searcher.clone()
+    }
+
+    /// Returns a copy of the prepared consumer.
+    #[inline]
+    pub(crate) fn consumer(&self) -> N::Consumer {
+        self.consumer.clone()
+    }
+}
+
+impl<N: Compile> Clone for Compiled<N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Compiled {
+            searcher: self.searcher.clone(),
+            consumer: self.consumer.clone(),
+        }
+    }
+}
+
+impl<N: Compile> fmt::Debug for Compiled<N>
+where
+    N::Searcher: fmt::Debug,
+    N::Consumer: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Compiled")
+            .field("searcher", &self.searcher)
+            .field("consumer", &self.consumer)
+            .finish()
+    }
+}
@@ -1,5 +1,9 @@
 use haystack::{Hay, Haystack, Span};
+#[cfg(feature = "std")]
+use haystack::SharedHaystack;
 use needle::{Needle, Searcher, ReverseSearcher, DoubleEndedSearcher, Consumer, ReverseConsumer, DoubleEndedConsumer};
+#[cfg(feature = "std")]
+use needle::CapturingSearcher;
 use std::iter::FusedIterator;
 use std::ops::Range;
 use std::fmt;
@@ -874,3 +878,239 @@ where
     }
     writer(Span::into(src));
 }
+
+//------------------------------------------------------------------------------
+// Captures
+//------------------------------------------------------------------------------
+
+/// The capture groups of a match, returned by [`captures`] and
+/// [`captures_iter`].
+///
+/// Group 0 is the whole match, and the groups introduced by
+/// [`capture`](::combinators::capture) are numbered from 1.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct Captures<H>
+where
+    H: SharedHaystack,
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+{
+    haystack: H,
+    ranges: Vec<Option<Range<<H::Target as Hay>::Index>>>,
+}
+
+#[cfg(feature = "std")]
+impl<H> Captures<H>
+where
+    H: SharedHaystack,
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+{
+    fn new<S>(haystack: H, range: Range<<H::Target as Hay>::Index>, searcher: &S) -> Self
+    where
+        S: CapturingSearcher<H::Target>,
+    {
+        let mut ranges = vec![None; 1 + searcher.captures_len()];
+        ranges[0] = Some(range);
+        searcher.read_captures(&mut ranges[1..]);
+        Captures { haystack, ranges }
+    }
+
+    /// Returns the number of groups, including group 0.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Always returns `false`, since group 0 is always present.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Returns the range of group `i`, or `None` if the group did not
+    /// participate in the match.
+    #[inline]
+    pub fn range(&self, i: usize) -> Option<Range<<H::Target as Hay>::Index>> {
+        self.ranges.get(i)?.clone()
+    }
+
+    /// Returns the content of group `i`, or `None` if the group did not
+    /// participate in the match.
+    #[inline]
+    pub fn get(&self, i: usize) -> Option<H> {
+        let range = self.range(i)?;
+        Some(unsafe { self.haystack.clone().slice_unchecked(range) })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Captures<&str> {
+    /// Expands a replacement template using the groups, and appends the
+    /// result to `dst`.
+    ///
+    /// `$n` and `${n}` are replaced by the content of group `n` (or nothing
+    /// if the group did not participate in the match), and `$$` is replaced
+    /// by a literal `$`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// extern crate pattern_3;
+    /// use pattern_3::combinators::{capture, NeedleExt};
+    /// use pattern_3::ext::captures;
+    ///
+    /// let caps = captures("k=v", capture('k').then('=').then(capture('v'))).unwrap();
+    /// let mut dst = String::new();
+    /// caps.expand("$2${1}0 costs $$5", &mut dst);
+    /// assert_eq!(dst, "vk0 costs $5");
+    /// ```
+    pub fn expand(&self, template: &str, dst: &mut String) {
+        let mut rest = template;
+        while let Some(i) = rest.find('$') {
+            dst.push_str(&rest[..i]);
+            rest = &rest[(i + 1)..];
+            let (digits, after) = if rest.starts_with('{') {
+                match rest.find('}') {
+                    Some(j) => (&rest[1..j], &rest[(j + 1)..]),
+                    None => ("", rest),
+                }
+            } else {
+                let j = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+                (&rest[..j], &rest[j..])
+            };
+            match digits.parse::<usize>() {
+                Ok(n) => {
+                    dst.push_str(self.get(n).unwrap_or(""));
+                    rest = after;
+                }
+                Err(_) if rest.starts_with('$') => {
+                    dst.push('$');
+                    rest = &rest[1..];
+                }
+                Err(_) => dst.push('$'),
+            }
+        }
+        dst.push_str(rest);
+    }
+}
+
+#[cfg(feature = "std")]
+pub fn captures<H, P>(haystack: H, needle: P) -> Option<Captures<H>>
+where
+    H: SharedHaystack,
+    P: Needle<H>,
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+{
+    let mut searcher = needle.into_searcher();
+    let range = searcher.search((*haystack).into())?;
+    Some(Captures::new(haystack, range, &searcher))
+}
+
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct CapturesIter<H, S>
+where
+    H: SharedHaystack,
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+{
+    searcher: S,
+    haystack: H,
+    rest: Span<H>,
+}
+
+#[cfg(feature = "std")]
+impl<H, S> Iterator for CapturesIter<H, S>
+where
+    H: SharedHaystack,
+    S: Searcher<H::Target>,
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+{
+    type Item = Captures<H>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest.take();
+        let range = self.searcher.search(rest.borrow())?;
+        let [_, _, right] = unsafe { rest.split_around(range.clone()) };
+        self.rest = right;
+        Some(Captures::new(self.haystack.clone(), range, &self.searcher))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H, S> FusedIterator for CapturesIter<H, S>
+where
+    H: SharedHaystack,
+    S: Searcher<H::Target>,
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+{}
+
+#[cfg(feature = "std")]
+pub fn captures_iter<H, P>(haystack: H, needle: P) -> CapturesIter<H, P::Searcher>
+where
+    H: SharedHaystack,
+    P: Needle<H>,
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+{
+    CapturesIter {
+        searcher: needle.into_searcher(),
+        rest: haystack.clone().into(),
+        haystack,
+    }
+}
+
+#[cfg(feature = "std")]
+pub fn replace_with_captures<H, P, F, W>(src: H, from: P, replacer: F, writer: W)
+where
+    H: SharedHaystack,
+    P: Needle<H>,
+    F: FnMut(&Captures<H>) -> H,
+    W: FnMut(H),
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+{
+    replacen_with_captures(src, from, replacer, usize::MAX, writer)
+}
+
+#[cfg(feature = "std")]
+pub fn replacen_with_captures<H, P, F, W>(src: H, from: P, mut replacer: F, mut n: usize, mut writer: W)
+where
+    H: SharedHaystack,
+    P: Needle<H>,
+    F: FnMut(&Captures<H>) -> H,
+    W: FnMut(H),
+    H::Target: Hay, // FIXME: RFC 2089 or 2289
+{
+    let mut searcher = from.into_searcher();
+    let haystack = src.clone();
+    let mut src = Span::from(src);
+    while n != 0 {
+        n -= 1;
+        let range = match searcher.search(src.borrow()) {
+            Some(range) => range,
+            None => break,
+        };
+        let captures = Captures::new(haystack.clone(), range.clone(), &searcher);
+        let [left, _, right] = unsafe { src.split_around(range) };
+        writer(Span::into(left));
+        writer(replacer(&captures));
+        src = right;
+    }
+    writer(Span::into(src));
+}
+
+#[cfg(feature = "std")]
+pub fn replace_template<'h, P>(src: &'h str, from: P, template: &str) -> String
+where
+    P: Needle<&'h str>,
+{
+    let mut result = String::with_capacity(src.len());
+    let mut searcher = from.into_searcher();
+    let mut last = 0;
+    while let Some(range) = searcher.search(unsafe { Span::from_parts(src, last..src.len()) }) {
+        result.push_str(&src[last..range.start]);
+        Captures::new(src, range.clone(), &searcher).expand(template, &mut result);
+        last = range.end;
+    }
+    result.push_str(&src[last..]);
+    result
+}
@@ -0,0 +1,562 @@
+//! Shell-style wildcard patterns.
+//!
+//! A [`Glob`] is compiled from a pattern string with the following syntax:
+//!
+//! * `?` matches any single character except `/`.
+//! * `*` matches any sequence of characters except `/`.
+//! * `**` matches any sequence of characters, including `/`. When followed by
+//!   a `/`, the whole `**/` may also match nothing, so `a/**/b` matches `a/b`.
+//! * `[abc]`, `[a-z]` match a single character from the class, and `[!a-z]`
+//!   or `[^a-z]` a single character outside of it. A `]` right after the
+//!   opening bracket is part of the class. Classes never match `/`.
+//! * `\` escapes the next character, so `\*` matches a literal `*`.
+//!
+//! Anything else matches itself. A `[` without the closing `]` and a trailing
+//! `\` are treated as literal characters, so every string is a valid pattern.
+//!
+//! Globs can be searched in `&str`, `&[u8]` and (on Unix) `&Path` haystacks.
+//! Byte haystacks are decoded as UTF-8, with every invalid byte treated as a
+//! single character that only wildcards and negated classes can match.
+//!
+//! A search finds the leftmost match, and among the matches starting there,
+//! the longest one. A reverse search finds the rightmost match, and the
+//! longest among those ending there. Matching is done by simulating all
+//! possible expansions of the wildcards at once, so it takes time
+//! proportional to the length of the hay times the length of the pattern.
+//! The literal text before the first wildcard (or after the last one when
+//! searching backward) is located with the two-way algorithm first.
+//!
+//! ```rust
+//! extern crate pattern_3;
+//! use pattern_3::glob::Glob;
+//! use pattern_3::ext::{ends_with, match_ranges, starts_with};
+//! use std::path::Path;
+//!
+//! let path = Path::new("src/strings/mod.rs");
+//! assert!(starts_with(path, Glob::new("src/**")));
+//! assert!(ends_with(path, Glob::new("*.[rR][sS]")));
+//! assert!(!starts_with(path, Glob::new("*/mod.rs")));
+//!
+//! assert!(starts_with("user-ab-cd", Glob::new("user-??-*")));
+//! assert_eq!(
+//!     match_ranges("logs/a.log/b.txt/c.log", Glob::new("*.log")).collect::<Vec<_>>(),
+//!     vec![(5..10, "a.log"), (17..22, "c.log")]
+//! );
+//! ```
+//!
+//! [`Glob`]: struct.Glob.html
+
+use needle::*;
+use haystack::Span;
+use slices::slice::TwoWaySearcher;
+use std::borrow::Cow;
+use std::mem;
+use std::ops::Range;
+#[cfg(unix)]
+use std::path::Path;
+use std::str;
+
+/// A single character position of a glob.
+#[derive(Clone, Debug)]
+enum Unit {
+    Char(char),
+    NotSlash,
+    Any,
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+impl Unit {
+    /// Checks whether the unit matches a decoded character. `None` stands for
+    /// an invalid byte.
+    fn matches(&self, c: Option<char>) -> bool {
+        match *self {
+            Unit::Char(expected) => c == Some(expected),
+            Unit::NotSlash => c != Some('/'),
+            Unit::Any => true,
+            Unit::Class { negated, ref ranges } => match c {
+                Some('/') => false,
+                Some(c) => ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != negated,
+                None => negated,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    unit: Unit,
+    /// Whether the unit may match zero or more times, instead of exactly once.
+    repeat: bool,
+    /// Whether this token and the next can be skipped together when entered
+    /// from a previous token. Used to make `**/` optional.
+    skip: bool,
+}
+
+impl Token {
+    fn one(unit: Unit) -> Self {
+        Token { unit, repeat: false, skip: false }
+    }
+}
+
+/// Reads a single (possibly escaped) character of a class.
+fn parse_class_char(chars: &[char], i: usize) -> Option<(char, usize)> {
+    match *chars.get(i)? {
+        '\\' => chars.get(i + 1).map(|&c| (c, 2)),
+        c => Some((c, 1)),
+    }
+}
+
+/// Parses a class after the opening `[`, returning the unit and the number of
+/// characters read including the closing `]`.
+fn parse_class(chars: &[char]) -> Option<(Unit, usize)> {
+    let mut i = 0;
+    let negated = chars.first() == Some(&'!') || chars.first() == Some(&'^');
+    if negated {
+        i += 1;
+    }
+    let first = i;
+    let mut ranges = Vec::new();
+    loop {
+        if *chars.get(i)? == ']' && i != first {
+            return Some((Unit::Class { negated, ranges }, i + 1));
+        }
+        let (lo, len) = parse_class_char(chars, i)?;
+        i += len;
+        match chars.get(i + 1) {
+            Some(&next) if chars[i] == '-' && next != ']' => {
+                let (hi, len) = parse_class_char(chars, i + 1)?;
+                i += 1 + len;
+                ranges.push((lo, hi));
+            }
+            _ => ranges.push((lo, lo)),
+        }
+    }
+}
+
+fn parse(pattern: &str) -> Vec<Token> {
+    let chars = pattern.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => {
+                let c = chars.get(i + 1).cloned().unwrap_or('\\');
+                tokens.push(Token::one(Unit::Char(c)));
+                i += 2;
+            }
+            '?' => {
+                tokens.push(Token::one(Unit::NotSlash));
+                i += 1;
+            }
+            '*' => {
+                let stars = chars[i..].iter().take_while(|&&c| c == '*').count();
+                i += stars;
+                if stars == 1 {
+                    tokens.push(Token { unit: Unit::NotSlash, repeat: true, skip: false });
+                } else {
+                    let dirs = chars.get(i) == Some(&'/');
+                    tokens.push(Token { unit: Unit::Any, repeat: true, skip: dirs });
+                    if dirs {
+                        tokens.push(Token::one(Unit::Char('/')));
+                        i += 1;
+                    }
+                }
+            }
+            '[' => match parse_class(&chars[(i + 1)..]) {
+                Some((unit, len)) => {
+                    tokens.push(Token::one(unit));
+                    i += 1 + len;
+                }
+                None => {
+                    tokens.push(Token::one(Unit::Char('[')));
+                    i += 1;
+                }
+            },
+            c => {
+                tokens.push(Token::one(Unit::Char(c)));
+                i += 1;
+            }
+        }
+    }
+    tokens
+}
+
+/// A compiled shell-style wildcard pattern.
+///
+/// See the [module documentation](index.html) for the syntax and the
+/// matching rules. Passing a `Glob` by value to a searching function moves
+/// the compiled pattern into the searcher, while passing `&Glob` lets the
+/// pattern be reused.
+///
+/// This type is available only with the `std` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::glob::Glob;
+/// use pattern_3::ext::{find, rfind, split};
+///
+/// let log = Glob::new("*.log");
+/// assert_eq!(find("app.log.1", &log), Some(0));
+/// assert_eq!(rfind("a.log/b.log", &log), Some(6));
+///
+/// let sep = Glob::new("[,;]");
+/// assert_eq!(split("a,b;c", &sep).collect::<Vec<_>>(), vec!["a", "b", "c"]);
+///
+/// assert_eq!(find("1*2", Glob::new(r"\*")), Some(1));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Glob<'p> {
+    forward: Vec<Token>,
+    /// The tokens in reverse order, for matching backward.
+    backward: Vec<Token>,
+    /// The literal text before the first wildcard.
+    prefix: Option<TwoWaySearcher<'p, u8>>,
+    /// The literal text after the last wildcard.
+    suffix: Option<TwoWaySearcher<'p, u8>>,
+}
+
+impl<'p> Glob<'p> {
+    /// Compiles a pattern.
+    pub fn new(pattern: &'p str) -> Self {
+        let forward = parse(pattern);
+
+        let mut backward = forward.clone();
+        backward.reverse();
+        // `**/` becomes `/**` backward, where skipping starts from the `/`.
+        for i in 0..backward.len() {
+            if backward[i].skip {
+                backward[i].skip = false;
+                backward[i - 1].skip = true;
+            }
+        }
+
+        let prefix_len = pattern.find(|c| "*?[\\".contains(c)).unwrap_or(pattern.len());
+        let mut suffix_start = pattern.rfind(|c| "*?[]\\".contains(c)).map_or(0, |i| i + 1);
+        // The `/` of `**/` is optional.
+        if pattern[..suffix_start].ends_with("**") {
+            suffix_start = (suffix_start + 1).min(pattern.len());
+        }
+        let literal = |range: Range<usize>| if range.start == range.end {
+            None
+        } else {
+            Some(TwoWaySearcher::new(&pattern.as_bytes()[range]))
+        };
+
+        Glob {
+            forward,
+            backward,
+            prefix: literal(0..prefix_len),
+            suffix: literal(suffix_start..pattern.len()),
+        }
+    }
+}
+
+/// Decodes the character starting at `i`, not extending beyond `end`.
+fn decode(hay: &[u8], i: usize, end: usize) -> (Option<char>, usize) {
+    let width = match hay[i] {
+        0x00..=0x7f => 1,
+        0xc2..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf4 => 4,
+        _ => return (None, 1),
+    };
+    if i + width <= end {
+        if let Ok(s) = str::from_utf8(&hay[i..(i + width)]) {
+            return (s.chars().next(), width);
+        }
+    }
+    (None, 1)
+}
+
+/// Decodes the character ending at `i`, not extending before `start`.
+fn decode_back(hay: &[u8], start: usize, i: usize) -> (Option<char>, usize) {
+    for width in 1..5 {
+        if i < start + width {
+            break;
+        }
+        if hay[i - width] & 0xc0 != 0x80 {
+            return match decode(hay, i - width, i) {
+                (Some(c), w) if w == width => (Some(c), width),
+                _ => (None, 1),
+            };
+        }
+    }
+    (None, 1)
+}
+
+/// Enters state `s` of the automaton with the given key, following the
+/// transitions which consume nothing. A smaller key takes priority.
+fn add(tokens: &[Token], states: &mut [Option<usize>], s: usize, key: usize, entry: bool) {
+    if entry && s < tokens.len() && tokens[s].skip {
+        add(tokens, states, s + 2, key, true);
+    }
+    match states[s] {
+        Some(k) if k <= key => return,
+        _ => states[s] = Some(key),
+    }
+    if s < tokens.len() && tokens[s].repeat {
+        add(tokens, states, s + 1, key, true);
+    }
+}
+
+/// Searcher for a [`Glob`](struct.Glob.html).
+#[derive(Clone, Debug)]
+pub struct GlobSearcher<'g> {
+    glob: Cow<'g, Glob<'g>>,
+    prefix: Option<TwoWaySearcher<'g, u8>>,
+    suffix: Option<TwoWaySearcher<'g, u8>>,
+    /// For each state of the automaton, the key of the match attempt in it.
+    states: Vec<Option<usize>>,
+    next_states: Vec<Option<usize>>,
+    allow_empty_match_front: bool,
+    allow_empty_match_back: bool,
+}
+
+impl<'g> GlobSearcher<'g> {
+    fn new(glob: Cow<'g, Glob<'g>>) -> Self {
+        let len = glob.forward.len() + 1;
+        GlobSearcher {
+            prefix: glob.prefix,
+            suffix: glob.suffix,
+            glob,
+            states: vec![None; len],
+            next_states: vec![None; len],
+            allow_empty_match_front: true,
+            allow_empty_match_back: true,
+        }
+    }
+
+    /// Finds the leftmost-longest match in `range` (or the rightmost-longest
+    /// when going backward). Every position is a potential start unless the
+    /// search is `anchored` at the start (or end) of the range.
+    fn run(
+        &mut self,
+        hay: &[u8],
+        range: Range<usize>,
+        forward: bool,
+        anchored: bool,
+        reject_empty: bool,
+    ) -> Option<Range<usize>> {
+        let tokens: &[Token] = if forward { &self.glob.forward } else { &self.glob.backward };
+        let accept = tokens.len();
+        let states = &mut self.states;
+        let next_states = &mut self.next_states;
+        for s in states.iter_mut() {
+            *s = None;
+        }
+
+        // Attempts are keyed by where they start, so that the leftmost (or
+        // rightmost) attempt has the smallest key.
+        let key = |pos: usize| if forward { pos } else { !pos };
+        let origin = if forward { range.start } else { range.end };
+        let bound = if forward { range.end } else { range.start };
+        let mut pos = origin;
+        let mut best: Option<(usize, usize)> = None;
+
+        loop {
+            if best.is_none() && (!anchored || pos == origin) {
+                if !anchored && states.iter().all(Option::is_none) {
+                    let literal = if forward {
+                        self.prefix.as_mut().map(|p| p.next(hay, pos..range.end).map(|r| r.start))
+                    } else {
+                        self.suffix.as_mut().map(|s| s.next_back(hay, range.start..pos).map(|r| r.end))
+                    };
+                    match literal {
+                        Some(Some(found)) => pos = found,
+                        Some(None) => return None,
+                        None => {}
+                    }
+                }
+                add(tokens, states, 0, key(pos), true);
+            }
+
+            if let Some(k) = states[accept].take() {
+                let rejected = reject_empty && pos == origin && k == key(pos);
+                let better = match best {
+                    Some((best_key, _)) => k <= best_key,
+                    None => true,
+                };
+                if !rejected && better {
+                    best = Some((k, pos));
+                }
+            }
+
+            // Attempts which started after the best match can be dropped.
+            if let Some((best_key, _)) = best {
+                for s in states.iter_mut() {
+                    if let Some(k) = *s {
+                        if k > best_key {
+                            *s = None;
+                        }
+                    }
+                }
+            }
+
+            let idle = states[..accept].iter().all(Option::is_none);
+            if (idle && (best.is_some() || anchored)) || pos == bound {
+                break;
+            }
+
+            let (c, len) = if forward {
+                decode(hay, pos, range.end)
+            } else {
+                decode_back(hay, range.start, pos)
+            };
+            for (s, token) in tokens.iter().enumerate() {
+                if let Some(k) = states[s] {
+                    if token.unit.matches(c) {
+                        if token.repeat {
+                            add(tokens, next_states, s, k, false);
+                        } else {
+                            add(tokens, next_states, s + 1, k, true);
+                        }
+                    }
+                }
+            }
+            for s in states.iter_mut() {
+                *s = None;
+            }
+            mem::swap(states, next_states);
+            pos = if forward { pos + len } else { pos - len };
+        }
+
+        best.map(|(k, end)| if forward { k..end } else { end..!k })
+    }
+
+    fn search_bytes(&mut self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let reject_empty = !self.allow_empty_match_front;
+        let m = self.run(hay, range, true, false, reject_empty)?;
+        self.allow_empty_match_front = false;
+        Some(m)
+    }
+
+    fn rsearch_bytes(&mut self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let reject_empty = !self.allow_empty_match_back;
+        let m = self.run(hay, range, false, false, reject_empty)?;
+        self.allow_empty_match_back = false;
+        Some(m)
+    }
+
+    fn consume_bytes(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        self.run(hay, range, true, true, false).map(|m| m.end)
+    }
+
+    fn rconsume_bytes(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        self.run(hay, range, false, true, false).map(|m| m.start)
+    }
+}
+
+unsafe impl<'g> Searcher<[u8]> for GlobSearcher<'g> {
+    #[inline]
+    fn search(&mut self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        self.search_bytes(span)
+    }
+}
+
+unsafe impl<'g> ReverseSearcher<[u8]> for GlobSearcher<'g> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        self.rsearch_bytes(span)
+    }
+}
+
+unsafe impl<'g> Consumer<[u8]> for GlobSearcher<'g> {
+    #[inline]
+    fn consume(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        self.consume_bytes(span)
+    }
+}
+
+unsafe impl<'g> ReverseConsumer<[u8]> for GlobSearcher<'g> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        self.rconsume_bytes(span)
+    }
+}
+
+// In a string every decoded character is valid, so the matches always start
+// and end on character boundaries. Paths are plain bytes.
+macro_rules! impl_searcher {
+    ($ty:ty) => {
+        unsafe impl<'g> Searcher<$ty> for GlobSearcher<'g> {
+            #[inline]
+            fn search(&mut self, span: Span<&$ty>) -> Option<Range<usize>> {
+                self.search_bytes(span.as_bytes())
+            }
+        }
+
+        unsafe impl<'g> ReverseSearcher<$ty> for GlobSearcher<'g> {
+            #[inline]
+            fn rsearch(&mut self, span: Span<&$ty>) -> Option<Range<usize>> {
+                self.rsearch_bytes(span.as_bytes())
+            }
+        }
+
+        unsafe impl<'g> Consumer<$ty> for GlobSearcher<'g> {
+            #[inline]
+            fn consume(&mut self, span: Span<&$ty>) -> Option<usize> {
+                self.consume_bytes(span.as_bytes())
+            }
+        }
+
+        unsafe impl<'g> ReverseConsumer<$ty> for GlobSearcher<'g> {
+            #[inline]
+            fn rconsume(&mut self, span: Span<&$ty>) -> Option<usize> {
+                self.rconsume_bytes(span.as_bytes())
+            }
+        }
+    }
+}
+
+impl_searcher!(str);
+#[cfg(unix)]
+impl_searcher!(Path);
+
+macro_rules! impl_needle {
+    ($($(#[$attr:meta])* $ty:ty),+) => {
+        $(
+            $(#[$attr])*
+            impl<'h, 'p> Needle<$ty> for Glob<'p> {
+                type Searcher = GlobSearcher<'p>;
+                type Consumer = GlobSearcher<'p>;
+
+                #[inline]
+                fn into_searcher(self) -> Self::Searcher {
+                    GlobSearcher::new(Cow::Owned(self))
+                }
+
+                #[inline]
+                fn into_consumer(self) -> Self::Consumer {
+                    GlobSearcher::new(Cow::Owned(self))
+                }
+            }
+
+            $(#[$attr])*
+            impl<'h, 'g, 'p: 'g> Needle<$ty> for &'g Glob<'p> {
+                type Searcher = GlobSearcher<'g>;
+                type Consumer = GlobSearcher<'g>;
+
+                #[inline]
+                fn into_searcher(self) -> Self::Searcher {
+                    GlobSearcher::new(Cow::Borrowed(self))
+                }
+
+                #[inline]
+                fn into_consumer(self) -> Self::Consumer {
+                    GlobSearcher::new(Cow::Borrowed(self))
+                }
+            }
+        )+
+    };
+}
+
+impl_needle!(&'h str, &'h [u8], #[cfg(unix)] &'h Path);
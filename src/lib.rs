@@ -23,14 +23,52 @@
 extern crate core as std;
 
 extern crate memchr;
+#[cfg(feature = "unicode-normalization")]
+extern crate unicode_normalization;
+#[cfg(all(feature = "std", feature = "regex"))]
+extern crate regex;
 
 pub mod haystack;
 pub mod needle;
 mod slices;
 mod strings;
 mod omgwtf8;
+mod compiled;
+#[cfg(all(feature = "std", unix))]
+mod paths;
 pub mod ext;
+pub mod class;
+pub mod combinators;
+pub mod anchors;
+#[cfg(feature = "std")]
+pub mod glob;
+#[cfg(feature = "std")]
+pub mod approx;
 
 pub use haystack::{Hay, Haystack, SharedHaystack, Span};
-pub use needle::{Needle, Searcher, ReverseSearcher, DoubleEndedSearcher, Consumer, ReverseConsumer, DoubleEndedConsumer};
+pub use needle::{Needle, Searcher, ReverseSearcher, DoubleEndedSearcher, Consumer, ReverseConsumer, DoubleEndedConsumer, CapturingSearcher, MatchKind};
 pub use omgwtf8::Wtf8;
+pub use compiled::{Compile, Compiled};
+pub use slices::ascii::AsciiCaseInsensitive;
+pub use slices::constant_time::{ConstantTime, ConstantTimeConsumer};
+pub use slices::elem::Elem;
+pub use slices::key::{by_key, ByKey, ByKeySearcher, ByKeyConsumer};
+pub use slices::eq::{eq_by, EqBy, PartialEqSlice, EqBySearcher, EqByConsumer};
+pub use slices::predicates::{Predicates, PredicatesSearcher};
+pub use slices::sorted::{Sorted, SortedRange, SortedSearcher};
+pub use slices::set::ByteSet;
+pub use strings::case::CaseInsensitive;
+#[cfg(feature = "std")]
+pub use strings::set::CharSet;
+#[cfg(feature = "std")]
+pub use slices::aho_corasick::{AhoCorasick, AhoCorasickSearcher};
+#[cfg(feature = "std")]
+pub use slices::signature::{Signature, SignatureSearcher, ParseSignatureError};
+#[cfg(feature = "std")]
+pub use slices::trie::{Trie, TrieSearcher};
+#[cfg(feature = "std")]
+pub use slices::owned::{OwnedSliceSearcher, OwnedNaiveSearcher};
+#[cfg(all(feature = "std", feature = "unicode-normalization"))]
+pub use strings::normalization::NormalizationInsensitive;
+#[cfg(all(feature = "std", feature = "regex"))]
+pub use slices::regex::RegexSearcher;
@@ -471,6 +471,65 @@ pub unsafe trait DoubleEndedSearcher<A: Hay + ?Sized>: ReverseSearcher<A> {}
 /// ```
 pub unsafe trait DoubleEndedConsumer<A: Hay + ?Sized>: ReverseConsumer<A> {}
 
+/// A searcher or consumer which can report the sub-ranges of its most recent
+/// match, called *captures*.
+///
+/// It is used to support the following standard algorithms:
+///
+/// * [`captures`](::ext::captures)
+/// * [`captures_iter`](::ext::captures_iter)
+/// * [`replace_with_captures`](::ext::replace_with_captures)
+///
+/// Capture groups are introduced by wrapping a needle with
+/// [`capture`](::combinators::capture), and are collected through the other
+/// [combinators](::combinators) in the order they appear. Every searcher and
+/// consumer implements this trait: those which do not capture anything report
+/// zero groups, so they can be freely mixed with capturing ones.
+///
+/// # Safety
+///
+/// This trait is marked unsafe because the ranges written by
+/// `read_captures()` are required to lie on valid codeword boundaries in the
+/// haystack, like the ranges returned by a searcher.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::{Needle, Searcher, CapturingSearcher, Span};
+/// use pattern_3::combinators::{capture, NeedleExt};
+///
+/// let needle = capture(char::is_alphabetic).then('=').then(capture(char::is_numeric));
+/// let mut searcher = Needle::<&str>::into_searcher(needle);
+/// assert_eq!(searcher.search(Span::from("x, y=1")), Some(3..6));
+///
+/// let mut captures = vec![None; CapturingSearcher::<str>::captures_len(&searcher)];
+/// CapturingSearcher::<str>::read_captures(&searcher, &mut captures);
+/// assert_eq!(captures, vec![Some(3..4), Some(5..6)]);
+/// ```
+pub unsafe trait CapturingSearcher<A: Hay + ?Sized> {
+    /// Returns the number of capture groups.
+    fn captures_len(&self) -> usize;
+
+    /// Writes the range of every capture group of the most recent match into
+    /// `captures`, or `None` for groups which did not participate in the
+    /// match.
+    ///
+    /// The length of `captures` must be equal to `captures_len()`. The result
+    /// is unspecified if the most recent search did not find a match.
+    fn read_captures(&self, captures: &mut [Option<Range<A::Index>>]);
+}
+
+unsafe impl<A: Hay + ?Sized, T> CapturingSearcher<A> for T {
+    #[inline]
+    default fn captures_len(&self) -> usize {
+        0
+    }
+
+    #[inline]
+    default fn read_captures(&self, _: &mut [Option<Range<A::Index>>]) {}
+}
+
 /// A needle, a type which can be converted into a searcher.
 ///
 /// When using search algorithms like [`split()`](::ext::split), users will
@@ -576,3 +635,27 @@ unsafe impl<A: Hay + ?Sized> ReverseConsumer<A> for EmptySearcher {
 
 unsafe impl<A: Hay + ?Sized> DoubleEndedSearcher<A> for EmptySearcher {}
 unsafe impl<A: Hay + ?Sized> DoubleEndedConsumer<A> for EmptySearcher {}
+
+/// How to choose between several candidate matches starting at the same
+/// position.
+///
+/// Needles consisting of multiple alternatives (e.g. a set of substrings) may
+/// find several matches starting at the leftmost position. This enum decides
+/// which one of them would be reported.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MatchKind {
+    /// Prefers the alternative which is listed first, similar to the
+    /// alternation operator `|` in a backtracking regex engine.
+    LeftmostFirst,
+
+    /// Prefers the longest alternative. If multiple alternatives have the same
+    /// length, the one which is listed first is preferred.
+    LeftmostLongest,
+}
+
+impl Default for MatchKind {
+    #[inline]
+    fn default() -> Self {
+        MatchKind::LeftmostFirst
+    }
+}
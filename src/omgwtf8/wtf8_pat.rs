@@ -1,7 +1,9 @@
 use needle::*;
+use compiled::{Compile, Compiled};
 use haystack::{Haystack, Span};
 use std::ops::Range;
 use slices::slice::{TwoWaySearcher, SliceSearcher, NaiveSearcher};
+use slices::ascii::{AsciiCaseInsensitive, AsciiCaseless};
 #[cfg(test)]
 use ext::{match_ranges, rmatch_ranges, starts_with, ends_with};
 
@@ -332,6 +334,32 @@ impl<'p, H: Haystack<Target = Wtf8>> Needle<H> for &'p Wtf8 {
     }
 }
 
+impl<'p> Compile for &'p Wtf8 {
+    type Searcher = Wtf8Searcher<SliceSearcher<'p, u8>>;
+    type Consumer = Wtf8Searcher<NaiveSearcher<'p, u8>>;
+
+    fn compile_searcher(&self) -> Self::Searcher {
+        Needle::<&Wtf8>::into_searcher(*self)
+    }
+
+    fn compile_consumer(&self) -> Self::Consumer {
+        Needle::<&Wtf8>::into_consumer(*self)
+    }
+}
+
+impl<'p, H: Haystack<Target = Wtf8>> Needle<H> for &Compiled<&'p Wtf8> {
+    type Searcher = Wtf8Searcher<SliceSearcher<'p, u8>>;
+    type Consumer = Wtf8Searcher<NaiveSearcher<'p, u8>>;
+
+    fn into_searcher(self) -> Self::Searcher {
+        self.searcher()
+    }
+
+    fn into_consumer(self) -> Self::Consumer {
+        self.consumer()
+    }
+}
+
 // FIXME cannot impl `Needle<(_: Haystack<Target = Wtf8>)>` due to RFC 1672 being postponed.
 // (need to wait for chalk)
 impl<'h, 'p> Needle<&'h Wtf8> for &'p str {
@@ -346,3 +374,70 @@ impl<'h, 'p> Needle<&'h Wtf8> for &'p str {
         NaiveSearcher::new(self.as_bytes())
     }
 }
+
+impl<'p> Needle<&Wtf8> for &Compiled<&'p str> {
+    type Searcher = SliceSearcher<'p, u8>;
+    type Consumer = NaiveSearcher<'p, u8>;
+
+    fn into_searcher(self) -> Self::Searcher {
+        self.searcher()
+    }
+
+    fn into_consumer(self) -> Self::Consumer {
+        self.consumer()
+    }
+}
+
+// ASCII case folding never touches the surrogate bytes, so just like the `&str`
+// needle, a match never splits a surrogate pair.
+
+unsafe impl<'p> Searcher<Wtf8> for TwoWaySearcher<'p, AsciiCaseless> {
+    #[inline]
+    fn search(&mut self, span: Span<&Wtf8>) -> Option<Range<usize>> {
+        self.search(AsciiCaseless::from_span(span_as_inner(span)))
+    }
+}
+
+unsafe impl<'p> ReverseSearcher<Wtf8> for TwoWaySearcher<'p, AsciiCaseless> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&Wtf8>) -> Option<Range<usize>> {
+        self.rsearch(AsciiCaseless::from_span(span_as_inner(span)))
+    }
+}
+
+unsafe impl<'p> Consumer<Wtf8> for NaiveSearcher<'p, AsciiCaseless> {
+    #[inline]
+    fn consume(&mut self, span: Span<&Wtf8>) -> Option<usize> {
+        self.consume(AsciiCaseless::from_span(span_as_inner(span)))
+    }
+
+    #[inline]
+    fn trim_start(&mut self, hay: &Wtf8) -> usize {
+        self.trim_start(AsciiCaseless::from_bytes(hay.as_inner()))
+    }
+}
+
+unsafe impl<'p> ReverseConsumer<Wtf8> for NaiveSearcher<'p, AsciiCaseless> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&Wtf8>) -> Option<usize> {
+        self.rconsume(AsciiCaseless::from_span(span_as_inner(span)))
+    }
+
+    #[inline]
+    fn trim_end(&mut self, hay: &Wtf8) -> usize {
+        self.trim_end(AsciiCaseless::from_bytes(hay.as_inner()))
+    }
+}
+
+impl<'p> Needle<&Wtf8> for AsciiCaseInsensitive<'p> {
+    type Searcher = SliceSearcher<'p, AsciiCaseless>;
+    type Consumer = NaiveSearcher<'p, AsciiCaseless>;
+
+    fn into_searcher(self) -> Self::Searcher {
+        SliceSearcher::new(self.as_caseless())
+    }
+
+    fn into_consumer(self) -> Self::Consumer {
+        NaiveSearcher::new(self.as_caseless())
+    }
+}
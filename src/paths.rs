@@ -0,0 +1,56 @@
+//! `Path` as a hay.
+//!
+//! On Unix a path is an arbitrary sequence of bytes, so it is indexed by byte
+//! offsets like `[u8]`, and every offset is a valid split point.
+
+use haystack::{Hay, Span};
+use std::ffi::OsStr;
+use std::ops::Range;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+unsafe impl Hay for Path {
+    type Index = usize;
+
+    #[inline]
+    fn empty<'a>() -> &'a Self {
+        Path::new("")
+    }
+
+    #[inline]
+    fn start_index(&self) -> usize {
+        0
+    }
+
+    #[inline]
+    fn end_index(&self) -> usize {
+        self.as_os_str().len()
+    }
+
+    #[inline]
+    unsafe fn slice_unchecked(&self, range: Range<usize>) -> &Self {
+        let bytes = self.as_os_str().as_bytes().get_unchecked(range);
+        Path::new(OsStr::from_bytes(bytes))
+    }
+
+    #[inline]
+    unsafe fn next_index(&self, index: usize) -> usize {
+        index + 1
+    }
+
+    #[inline]
+    unsafe fn prev_index(&self, index: usize) -> usize {
+        index - 1
+    }
+}
+
+impl<'h> Span<&'h Path> {
+    /// Reinterprets the path span as a byte-array span.
+    #[inline]
+    pub fn as_bytes(self) -> Span<&'h [u8]> {
+        let (haystack, range) = self.into_parts();
+        unsafe {
+            Span::from_parts(haystack.as_os_str().as_bytes(), range)
+        }
+    }
+}
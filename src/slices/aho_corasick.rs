@@ -0,0 +1,666 @@
+use needle::*;
+use haystack::{Haystack, Span};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Range;
+
+//------------------------------------------------------------------------------
+// Automaton
+//------------------------------------------------------------------------------
+
+const ROOT: u32 = 0;
+const NONE: u32 = !0;
+
+#[derive(Clone, Debug)]
+struct State<T> {
+    /// Goto transitions, sorted by the element.
+    transitions: Vec<(T, u32)>,
+    /// Failure link, the state of the longest proper suffix of this state.
+    fail: u32,
+    /// Dictionary link, the nearest state along the failure links which
+    /// reports a pattern. The root is never a dictionary link.
+    dict: u32,
+    /// Length of the path from the root to this state.
+    depth: usize,
+    /// Index of the first pattern which ends at this state.
+    output: u32,
+}
+
+impl<T> State<T> {
+    #[inline]
+    fn new(depth: usize) -> Self {
+        State {
+            transitions: Vec::new(),
+            fail: ROOT,
+            dict: NONE,
+            depth,
+            output: NONE,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Candidate {
+    pattern: u32,
+    start: usize,
+    end: usize,
+}
+
+impl Candidate {
+    // Checks whether `self` should be preferred over another candidate of the
+    // same anchor position, according to the match kind.
+    #[inline]
+    fn beats(&self, other: &Candidate, kind: MatchKind) -> bool {
+        let (len, other_len) = (self.end - self.start, other.end - other.start);
+        match kind {
+            MatchKind::LeftmostFirst => self.pattern < other.pattern,
+            MatchKind::LeftmostLongest => {
+                len > other_len || (len == other_len && self.pattern < other.pattern)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Automaton<T> {
+    states: Vec<State<T>>,
+}
+
+impl<T: Ord + Clone> Automaton<T> {
+    fn new<'a, I, J>(patterns: I) -> Self
+    where
+        T: 'a,
+        I: IntoIterator<Item = (u32, J)>,
+        J: IntoIterator<Item = &'a T>,
+    {
+        let mut states: Vec<State<T>> = vec![State::new(0)];
+
+        // build the trie.
+        for (index, pattern) in patterns {
+            let mut state = ROOT;
+            for elem in pattern {
+                let s = state as usize;
+                state = match states[s].transitions.binary_search_by(|t| t.0.cmp(elem)) {
+                    Ok(i) => states[s].transitions[i].1,
+                    Err(i) => {
+                        let next = states.len() as u32;
+                        let depth = states[s].depth + 1;
+                        states[s].transitions.insert(i, (elem.clone(), next));
+                        states.push(State::new(depth));
+                        next
+                    }
+                };
+            }
+            let output = &mut states[state as usize].output;
+            if *output == NONE || index < *output {
+                *output = index;
+            }
+        }
+
+        // compute the failure and dictionary links in breadth-first order.
+        let mut automaton = Automaton { states };
+        let mut queue = VecDeque::new();
+        queue.push_back(ROOT);
+        while let Some(parent) = queue.pop_front() {
+            let p = parent as usize;
+            for i in 0..automaton.states[p].transitions.len() {
+                let child = automaton.states[p].transitions[i].1;
+                let fail = if parent == ROOT {
+                    ROOT
+                } else {
+                    let elem = &automaton.states[p].transitions[i].0;
+                    let mut state = automaton.states[p].fail;
+                    loop {
+                        if let Some(next) = automaton.goto(state, elem) {
+                            break next;
+                        }
+                        if state == ROOT {
+                            break ROOT;
+                        }
+                        state = automaton.states[state as usize].fail;
+                    }
+                };
+                let dict = if fail == ROOT {
+                    NONE
+                } else if automaton.states[fail as usize].output != NONE {
+                    fail
+                } else {
+                    automaton.states[fail as usize].dict
+                };
+                let c = &mut automaton.states[child as usize];
+                c.fail = fail;
+                c.dict = dict;
+                queue.push_back(child);
+            }
+        }
+        automaton
+    }
+
+    /// Rebuilds an automaton from the reversed patterns stored in this trie.
+    fn reverse(&self) -> Self {
+        let mut patterns = Vec::new();
+        let mut stack = vec![(ROOT, Vec::new())];
+        while let Some((state, path)) = stack.pop() {
+            let s = &self.states[state as usize];
+            if s.output != NONE {
+                let mut reversed = path.clone();
+                reversed.reverse();
+                patterns.push((s.output, reversed));
+            }
+            for &(ref elem, next) in &s.transitions {
+                let mut path = path.clone();
+                path.push(elem);
+                stack.push((next, path));
+            }
+        }
+        Automaton::new(patterns.into_iter().map(|(index, pattern)| (index, pattern.into_iter())))
+    }
+}
+
+impl<T: Ord> Automaton<T> {
+    #[inline]
+    fn goto(&self, state: u32, elem: &T) -> Option<u32> {
+        let transitions = &self.states[state as usize].transitions;
+        match transitions.binary_search_by(|t| t.0.cmp(elem)) {
+            Ok(i) => Some(transitions[i].1),
+            Err(_) => None,
+        }
+    }
+
+    #[inline]
+    fn next(&self, mut state: u32, elem: &T) -> u32 {
+        loop {
+            if let Some(next) = self.goto(state, elem) {
+                return next;
+            }
+            if state == ROOT {
+                return ROOT;
+            }
+            state = self.states[state as usize].fail;
+        }
+    }
+
+    /// Index of the first empty pattern, if any.
+    #[inline]
+    fn empty_pattern(&self) -> Option<u32> {
+        match self.states[ROOT as usize].output {
+            NONE => None,
+            p => Some(p),
+        }
+    }
+
+    /// Iterates all (pattern, length) pairs which are suffixes of the state.
+    #[inline]
+    fn outputs<'a>(&'a self, state: u32) -> Outputs<'a, T> {
+        let s = &self.states[state as usize];
+        Outputs {
+            automaton: self,
+            state: if s.output != NONE && state != ROOT { state } else { s.dict },
+        }
+    }
+
+    /// Finds the leftmost match in `hay[range]`, ignoring empty patterns.
+    fn find(&self, hay: &[T], range: Range<usize>, kind: MatchKind) -> Option<Candidate> {
+        let mut state = ROOT;
+        let mut best = None::<Candidate>;
+        for pos in range {
+            if let Some(b) = best {
+                // no pending partial match can start at or before the best.
+                if pos - self.states[state as usize].depth > b.start {
+                    break;
+                }
+            }
+            state = self.next(state, unsafe { hay.get_unchecked(pos) });
+            for (pattern, len) in self.outputs(state) {
+                let c = Candidate { pattern, start: pos + 1 - len, end: pos + 1 };
+                best = match best {
+                    Some(b) if c.start > b.start || (c.start == b.start && !c.beats(&b, kind)) => Some(b),
+                    _ => Some(c),
+                };
+            }
+        }
+        best
+    }
+
+    /// Finds the rightmost match in `hay[range]` using the reversed automaton,
+    /// ignoring empty patterns.
+    fn rfind(&self, hay: &[T], range: Range<usize>, kind: MatchKind) -> Option<Candidate> {
+        let mut state = ROOT;
+        let mut best = None::<Candidate>;
+        for pos in range.rev() {
+            if let Some(b) = best {
+                // no pending partial match can end at or after the best.
+                if pos + 1 + self.states[state as usize].depth < b.end {
+                    break;
+                }
+            }
+            state = self.next(state, unsafe { hay.get_unchecked(pos) });
+            for (pattern, len) in self.outputs(state) {
+                let c = Candidate { pattern, start: pos, end: pos + len };
+                best = match best {
+                    Some(b) if c.end < b.end || (c.end == b.end && !c.beats(&b, kind)) => Some(b),
+                    _ => Some(c),
+                };
+            }
+        }
+        best
+    }
+
+    /// Finds the preferred match starting exactly at `range.start`.
+    fn anchored(&self, hay: &[T], range: Range<usize>, kind: MatchKind) -> Option<Candidate> {
+        let start = range.start;
+        let mut best = self.empty_pattern().map(|pattern| Candidate { pattern, start, end: start });
+        let mut state = ROOT;
+        for pos in range {
+            state = match self.goto(state, unsafe { hay.get_unchecked(pos) }) {
+                Some(next) => next,
+                None => break,
+            };
+            let pattern = self.states[state as usize].output;
+            if pattern != NONE {
+                let c = Candidate { pattern, start, end: pos + 1 };
+                best = match best {
+                    Some(b) if !c.beats(&b, kind) => Some(b),
+                    _ => Some(c),
+                };
+            }
+        }
+        best
+    }
+
+    /// Finds the preferred match ending exactly at `range.end` using the
+    /// reversed automaton.
+    fn ranchored(&self, hay: &[T], range: Range<usize>, kind: MatchKind) -> Option<Candidate> {
+        let end = range.end;
+        let mut best = self.empty_pattern().map(|pattern| Candidate { pattern, start: end, end });
+        let mut state = ROOT;
+        for pos in range.rev() {
+            state = match self.goto(state, unsafe { hay.get_unchecked(pos) }) {
+                Some(next) => next,
+                None => break,
+            };
+            let pattern = self.states[state as usize].output;
+            if pattern != NONE {
+                let c = Candidate { pattern, start: pos, end };
+                best = match best {
+                    Some(b) if !c.beats(&b, kind) => Some(b),
+                    _ => Some(c),
+                };
+            }
+        }
+        best
+    }
+}
+
+struct Outputs<'a, T: 'a> {
+    automaton: &'a Automaton<T>,
+    state: u32,
+}
+
+impl<'a, T: 'a> Iterator for Outputs<'a, T> {
+    type Item = (u32, usize);
+
+    #[inline]
+    fn next(&mut self) -> Option<(u32, usize)> {
+        if self.state == NONE {
+            return None;
+        }
+        let s = &self.automaton.states[self.state as usize];
+        self.state = s.dict;
+        Some((s.output, s.depth))
+    }
+}
+
+//------------------------------------------------------------------------------
+// Aho-Corasick searcher
+//------------------------------------------------------------------------------
+
+/// Searcher of a set of substrings using the Aho–Corasick automaton.
+///
+/// This is the searcher and consumer of [`&AhoCorasick`](AhoCorasick) as well
+/// as the slice of substrings `&[&str]` and `&[&[T]]`.
+///
+/// Besides implementing the searcher and consumer traits, this type also
+/// remembers which pattern was found by the most recent search,
+/// which can be retrieved using
+/// [`.pattern_index()`](AhoCorasickSearcher::pattern_index).
+#[derive(Clone, Debug)]
+pub struct AhoCorasickSearcher<'a, T: Clone + 'a> {
+    kind: MatchKind,
+    forward: Cow<'a, Automaton<T>>,
+    // built lazily on the first reverse operation, unless borrowed.
+    backward: Option<Cow<'a, Automaton<T>>>,
+    allow_empty_match_front: bool,
+    allow_empty_match_back: bool,
+    pattern_index: Option<usize>,
+}
+
+impl<'a, T: Ord + Clone + 'a> AhoCorasickSearcher<'a, T> {
+    pub(crate) fn new<'b, I>(patterns: I, kind: MatchKind) -> Self
+    where
+        T: 'b,
+        I: IntoIterator<Item = &'b [T]>,
+    {
+        let patterns = patterns.into_iter().enumerate().map(|(i, p)| (i as u32, p));
+        Self::with_automata(Cow::Owned(Automaton::new(patterns)), None, kind)
+    }
+
+    fn with_automata(forward: Cow<'a, Automaton<T>>, backward: Option<Cow<'a, Automaton<T>>>, kind: MatchKind) -> Self {
+        AhoCorasickSearcher {
+            kind,
+            forward,
+            backward,
+            allow_empty_match_front: true,
+            allow_empty_match_back: true,
+            pattern_index: None,
+        }
+    }
+
+    #[inline]
+    fn backward(&mut self) -> &Automaton<T> {
+        if self.backward.is_none() {
+            self.backward = Some(Cow::Owned(self.forward.reverse()));
+        }
+        self.backward.as_ref().unwrap()
+    }
+
+    #[inline]
+    fn report(&mut self, candidate: Option<Candidate>) -> Option<Range<usize>> {
+        self.pattern_index = candidate.map(|c| c.pattern as usize);
+        candidate.map(|c| c.start..c.end)
+    }
+
+    /// Searches the leftmost match. `next_index` is used to step over a
+    /// codeword when an empty match is rejected.
+    pub(crate) fn search_in<F>(&mut self, hay: &[T], range: Range<usize>, next_index: F) -> Option<Range<usize>>
+    where
+        F: Fn(usize) -> usize,
+    {
+        let candidate = if self.forward.empty_pattern().is_none() {
+            self.forward.find(hay, range, self.kind)
+        } else {
+            // every position matches the empty pattern, so the leftmost match
+            // always starts from the beginning of the range.
+            let mut start = range.start;
+            loop {
+                let c = self.forward.anchored(hay, start..range.end, self.kind);
+                match c {
+                    Some(c) if c.start == c.end && c.start == range.start && !self.allow_empty_match_front => {
+                        if start == range.end {
+                            break None;
+                        }
+                        start = next_index(start);
+                    }
+                    c => break c,
+                }
+            }
+        };
+        if candidate.is_some() {
+            self.allow_empty_match_front = false;
+        }
+        self.report(candidate)
+    }
+
+    /// Searches the rightmost match. `prev_index` is used to step over a
+    /// codeword when an empty match is rejected.
+    pub(crate) fn rsearch_in<F>(&mut self, hay: &[T], range: Range<usize>, prev_index: F) -> Option<Range<usize>>
+    where
+        F: Fn(usize) -> usize,
+    {
+        let kind = self.kind;
+        let allow_empty_match = self.allow_empty_match_back;
+        let candidate = {
+            let backward = self.backward();
+            if backward.empty_pattern().is_none() {
+                backward.rfind(hay, range, kind)
+            } else {
+                let mut end = range.end;
+                loop {
+                    let c = backward.ranchored(hay, range.start..end, kind);
+                    match c {
+                        Some(c) if c.start == c.end && c.end == range.end && !allow_empty_match => {
+                            if end == range.start {
+                                break None;
+                            }
+                            end = prev_index(end);
+                        }
+                        c => break c,
+                    }
+                }
+            }
+        };
+        if candidate.is_some() {
+            self.allow_empty_match_back = false;
+        }
+        self.report(candidate)
+    }
+
+    pub(crate) fn consume_in(&mut self, hay: &[T], range: Range<usize>) -> Option<usize> {
+        let candidate = self.forward.anchored(hay, range, self.kind);
+        self.report(candidate).map(|r| r.end)
+    }
+
+    pub(crate) fn rconsume_in(&mut self, hay: &[T], range: Range<usize>) -> Option<usize> {
+        let kind = self.kind;
+        let candidate = self.backward().ranchored(hay, range, kind);
+        self.report(candidate).map(|r| r.start)
+    }
+}
+
+impl<'a, T: Clone + 'a> AhoCorasickSearcher<'a, T> {
+    /// Returns the index of the pattern found by the most recent call to
+    /// `search`, `rsearch`, `consume` or `rconsume`.
+    ///
+    /// Returns `None` if nothing is searched yet, or the most recent call
+    /// found nothing.
+    ///
+    /// If the same pattern appears multiple times in the list, the index of
+    /// the first occurrence is returned.
+    #[inline]
+    pub fn pattern_index(&self) -> Option<usize> {
+        self.pattern_index
+    }
+}
+
+unsafe impl<'a, T: Ord + Clone + 'a> Searcher<[T]> for AhoCorasickSearcher<'a, T> {
+    #[inline]
+    fn search(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        self.search_in(hay, range, |i| i + 1)
+    }
+}
+
+unsafe impl<'a, T: Ord + Clone + 'a> ReverseSearcher<[T]> for AhoCorasickSearcher<'a, T> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        self.rsearch_in(hay, range, |i| i - 1)
+    }
+}
+
+unsafe impl<'a, T: Ord + Clone + 'a> Consumer<[T]> for AhoCorasickSearcher<'a, T> {
+    #[inline]
+    fn consume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        self.consume_in(hay, range)
+    }
+}
+
+unsafe impl<'a, T: Ord + Clone + 'a> ReverseConsumer<[T]> for AhoCorasickSearcher<'a, T> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        self.rconsume_in(hay, range)
+    }
+}
+
+//------------------------------------------------------------------------------
+// Needle
+//------------------------------------------------------------------------------
+
+/// A needle which matches any substring from a list of patterns.
+///
+/// The patterns are compiled into an Aho–Corasick automaton by
+/// [`AhoCorasick::new`], so the time needed to search a haystack is
+/// independent of the number of patterns. The needle is `&AhoCorasick`, which
+/// borrows the automaton, so it can be built once and used for many searches.
+///
+/// When multiple patterns can be found at the leftmost position, the
+/// [`MatchKind`] decides which one is reported. The default is
+/// [`LeftmostFirst`](MatchKind::LeftmostFirst). A plain slice of substrings
+/// (`&[&str]` or `&[&[T]]`) can also be used directly as a leftmost-first
+/// needle, although the automaton is then built for every search.
+///
+/// When searching in reverse, the rightmost match is found instead, and the
+/// `MatchKind` decides between matches ending at the same position.
+///
+/// The type `AhoCorasick<T>` searches `[T]` haystacks, while
+/// `AhoCorasick<u8, str>` searches `str` haystacks and is created from
+/// `AsRef<str>` patterns.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::{AhoCorasick, MatchKind, Needle, Searcher, Span};
+/// use pattern_3::ext::{match_ranges, split};
+///
+/// let keywords = ["Sam", "Samwise"];
+///
+/// let first = AhoCorasick::new(&keywords);
+/// assert_eq!(
+///     match_ranges("Samwise and Sam", &first).collect::<Vec<_>>(),
+///     vec![(0..3, "Sam"), (12..15, "Sam")]
+/// );
+///
+/// let longest = AhoCorasick::new(&keywords).match_kind(MatchKind::LeftmostLongest);
+/// assert_eq!(
+///     match_ranges("Samwise and Sam", &longest).collect::<Vec<_>>(),
+///     vec![(0..7, "Samwise"), (12..15, "Sam")]
+/// );
+///
+/// // learn which pattern matched.
+/// let mut searcher = Needle::<&str>::into_searcher(&longest);
+/// assert_eq!(searcher.search(Span::from("Samwise and Sam")), Some(0..7));
+/// assert_eq!(searcher.pattern_index(), Some(1));
+///
+/// // slices of substrings are leftmost-first needles too.
+/// assert_eq!(
+///     split("a, b;c", &[", ", ";"][..]).collect::<Vec<_>>(),
+///     vec!["a", "b", "c"]
+/// );
+/// ```
+pub struct AhoCorasick<T, A: ?Sized = [T]> {
+    kind: MatchKind,
+    forward: Automaton<T>,
+    backward: Automaton<T>,
+    hay: PhantomData<fn(&A)>,
+}
+
+impl<T: Clone, A: ?Sized> Clone for AhoCorasick<T, A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        AhoCorasick {
+            kind: self.kind,
+            forward: self.forward.clone(),
+            backward: self.backward.clone(),
+            hay: PhantomData,
+        }
+    }
+}
+
+impl<T: fmt::Debug, A: ?Sized> fmt::Debug for AhoCorasick<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AhoCorasick")
+            .field("kind", &self.kind)
+            .field("forward", &self.forward)
+            .field("backward", &self.backward)
+            .finish()
+    }
+}
+
+impl<T: Ord + Clone, A: ?Sized + AsRef<[T]>> AhoCorasick<T, A> {
+    /// Builds a leftmost-first needle from a list of patterns.
+    pub fn new<P: AsRef<A>>(patterns: &[P]) -> Self {
+        let patterns = patterns
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i as u32, AsRef::<[T]>::as_ref(p.as_ref())));
+        let forward = Automaton::new(patterns);
+        let backward = forward.reverse();
+        AhoCorasick {
+            kind: MatchKind::LeftmostFirst,
+            forward,
+            backward,
+            hay: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone, A: ?Sized> AhoCorasick<T, A> {
+    /// Changes how to choose between patterns found at the same position.
+    #[inline]
+    pub fn match_kind(mut self, kind: MatchKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    #[inline]
+    pub(crate) fn searcher(&self) -> AhoCorasickSearcher<'_, T>
+    where
+        T: Ord,
+    {
+        AhoCorasickSearcher::with_automata(
+            Cow::Borrowed(&self.forward),
+            Some(Cow::Borrowed(&self.backward)),
+            self.kind,
+        )
+    }
+}
+
+impl<'c, T, H> Needle<H> for &'c AhoCorasick<T>
+where
+    T: Ord + Clone,
+    H: Haystack<Target = [T]>,
+{
+    type Searcher = AhoCorasickSearcher<'c, T>;
+    type Consumer = AhoCorasickSearcher<'c, T>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        self.searcher()
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        self.searcher()
+    }
+}
+
+macro_rules! impl_needle {
+    (<[$($gen:tt)*]> $ty:ty) => {
+        impl<$($gen)*> Needle<$ty> for &'p [&'p [T]]
+        where
+            T: Ord + Clone,
+        {
+            type Searcher = AhoCorasickSearcher<'p, T>;
+            type Consumer = AhoCorasickSearcher<'p, T>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                AhoCorasickSearcher::new(self.iter().cloned(), MatchKind::LeftmostFirst)
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                AhoCorasickSearcher::new(self.iter().cloned(), MatchKind::LeftmostFirst)
+            }
+        }
+    }
+}
+
+impl_needle!(<['p, 'h, T]> &'h [T]);
+impl_needle!(<['p, 'h, T]> &'h mut [T]);
+impl_needle!(<['p, T]> Vec<T>);
@@ -0,0 +1,172 @@
+use needle::*;
+use haystack::Span;
+use slices::slice::{FastSkipByteset, FastSkipOptimization, TwoWaySearcher, NaiveSearcher, SliceSearcher};
+use std::cmp::Ordering;
+use std::ops::Range;
+
+//------------------------------------------------------------------------------
+// Case-folded byte
+//------------------------------------------------------------------------------
+
+/// A byte which compares equal to other bytes under ASCII case folding.
+///
+/// A `[u8]` can be reinterpreted as `[AsciiCaseless]` for free, allowing the
+/// two-way and naive searchers to be reused for case-insensitive search.
+#[derive(Copy, Clone, Debug)]
+#[repr(transparent)]
+pub struct AsciiCaseless(u8);
+
+impl AsciiCaseless {
+    #[inline]
+    fn folded(self) -> u8 {
+        self.0.to_ascii_lowercase()
+    }
+
+    #[inline]
+    pub(crate) fn from_bytes(bytes: &[u8]) -> &[AsciiCaseless] {
+        unsafe { &*(bytes as *const [u8] as *const [AsciiCaseless]) }
+    }
+
+    #[inline]
+    pub(crate) fn from_span(span: Span<&[u8]>) -> Span<&[AsciiCaseless]> {
+        let (hay, range) = span.into_parts();
+        unsafe { Span::from_parts(Self::from_bytes(hay), range) }
+    }
+}
+
+impl PartialEq for AsciiCaseless {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+}
+
+impl Eq for AsciiCaseless {}
+
+impl PartialOrd for AsciiCaseless {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AsciiCaseless {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.folded().cmp(&other.folded())
+    }
+}
+
+impl FastSkipOptimization for AsciiCaseless {
+    #[inline]
+    fn byteset_mask(&self) -> FastSkipByteset {
+        let lower = self.0.to_ascii_lowercase();
+        let upper = self.0.to_ascii_uppercase();
+        (1 << (lower & 63)) | (1 << (upper & 63))
+    }
+}
+
+//------------------------------------------------------------------------------
+// Searchers for byte slices
+//------------------------------------------------------------------------------
+
+unsafe impl<'p> Searcher<[u8]> for TwoWaySearcher<'p, AsciiCaseless> {
+    #[inline]
+    fn search(&mut self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        self.search(AsciiCaseless::from_span(span))
+    }
+}
+
+unsafe impl<'p> ReverseSearcher<[u8]> for TwoWaySearcher<'p, AsciiCaseless> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        self.rsearch(AsciiCaseless::from_span(span))
+    }
+}
+
+unsafe impl<'p> Consumer<[u8]> for NaiveSearcher<'p, AsciiCaseless> {
+    #[inline]
+    fn consume(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        self.consume(AsciiCaseless::from_span(span))
+    }
+
+    #[inline]
+    fn trim_start(&mut self, hay: &[u8]) -> usize {
+        self.trim_start(AsciiCaseless::from_bytes(hay))
+    }
+}
+
+unsafe impl<'p> ReverseConsumer<[u8]> for NaiveSearcher<'p, AsciiCaseless> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        self.rconsume(AsciiCaseless::from_span(span))
+    }
+
+    #[inline]
+    fn trim_end(&mut self, hay: &[u8]) -> usize {
+        self.trim_end(AsciiCaseless::from_bytes(hay))
+    }
+}
+
+//------------------------------------------------------------------------------
+// Needle
+//------------------------------------------------------------------------------
+
+/// A needle which matches a substring ignoring ASCII case.
+///
+/// Only the ASCII letters `A`–`Z` and `a`–`z` are folded, all other
+/// characters must match exactly. Since the haystack is never modified, the
+/// indices returned by the standard algorithms correspond to the original
+/// haystack.
+///
+/// This needle can search in `str`, `[u8]` and `Wtf8` haystacks.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::AsciiCaseInsensitive;
+/// use pattern_3::ext::{match_ranges, starts_with, trim_start};
+///
+/// assert_eq!(
+///     match_ranges("Content-Type: text/html; CONTENT-type", AsciiCaseInsensitive("content-type"))
+///         .collect::<Vec<_>>(),
+///     vec![(0..12, "Content-Type"), (25..37, "CONTENT-type")]
+/// );
+///
+/// assert!(starts_with(&b"GET / HTTP/1.1"[..], AsciiCaseInsensitive("get ")));
+/// assert_eq!(trim_start("NaNanana!", AsciiCaseInsensitive("na")), "!");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct AsciiCaseInsensitive<'p>(pub &'p str);
+
+impl<'p> AsciiCaseInsensitive<'p> {
+    #[inline]
+    pub(crate) fn as_caseless(&self) -> &'p [AsciiCaseless] {
+        AsciiCaseless::from_bytes(self.0.as_bytes())
+    }
+}
+
+macro_rules! impl_needle {
+    (<[$($gen:tt)*]> $ty:ty) => {
+        impl<$($gen)*> Needle<$ty> for AsciiCaseInsensitive<'p> {
+            type Searcher = SliceSearcher<'p, AsciiCaseless>;
+            type Consumer = NaiveSearcher<'p, AsciiCaseless>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                SliceSearcher::new(self.as_caseless())
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                NaiveSearcher::new(self.as_caseless())
+            }
+        }
+    }
+}
+
+impl_needle!(<['p, 'h]> &'h [u8]);
+impl_needle!(<['p, 'h]> &'h mut [u8]);
+#[cfg(feature = "std")]
+impl_needle!(<['p]> Vec<u8>);
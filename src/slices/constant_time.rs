@@ -0,0 +1,109 @@
+use needle::*;
+use haystack::{Haystack, Span};
+use slices::slice::SliceSearcher;
+use std::ptr;
+
+/// Compares two slices of the same length, always looking at every byte.
+#[inline(never)]
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    debug_assert_eq!(a.len(), b.len());
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b) {
+        // Reading the accumulated difference back through a volatile pointer
+        // at every step prevents the optimizer from turning the loop into an
+        // early return once `diff` is known to be non-zero.
+        diff = unsafe { ptr::read_volatile(&(diff | (x ^ y))) };
+    }
+    diff == 0
+}
+
+/// A needle whose consumer compares in constant time.
+///
+/// The standard consumers of `str` and `[u8]` stop at the first differing
+/// byte, so the time taken by [`starts_with`](::ext::starts_with) or
+/// [`ends_with`](::ext::ends_with) reveals how long the common prefix is.
+/// When the needle is a secret (an API token, a MAC, a password hash), this
+/// lets an attacker guess it byte by byte.
+///
+/// The consumer of `ConstantTime` always compares the whole length of the
+/// needle before deciding whether it matches. Only the *length* of the needle
+/// is not hidden: a haystack shorter than the needle is rejected immediately.
+///
+/// Searching (e.g. `find` or `split`) uses the ordinary two-way searcher and
+/// is **not** constant time.
+///
+/// `ConstantTime(&[u8])` can be used on `[u8]` haystacks, and
+/// `ConstantTime(&str)` on `str` haystacks.
+///
+/// This is a best-effort guarantee: the comparison is written so that the
+/// compiler cannot stop at the first difference, but nothing prevents the
+/// hardware from taking data-dependent time.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::ConstantTime;
+/// use pattern_3::ext::{starts_with, ends_with, trim_start};
+///
+/// let header = "Bearer 3f1e9a77c0d2";
+/// assert!(starts_with(header, ConstantTime("Bearer 3f1e9a77c0d2")));
+/// assert!(!starts_with(header, ConstantTime("Bearer 3f1e9a77c0d3")));
+/// assert_eq!(trim_start(header, ConstantTime("Bearer ")), "3f1e9a77c0d2");
+///
+/// let message = &b"payload\x8c\x1a\x55\x07"[..];
+/// assert!(ends_with(message, ConstantTime(&b"\x8c\x1a\x55\x07"[..])));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct ConstantTime<N>(pub N);
+
+/// Consumer of [`ConstantTime`].
+#[derive(Copy, Clone, Debug)]
+pub struct ConstantTimeConsumer<'p>(pub(crate) &'p [u8]);
+
+unsafe impl<'p> Consumer<[u8]> for ConstantTimeConsumer<'p> {
+    #[inline]
+    fn consume(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let end = range.start + self.0.len();
+        if end > range.end {
+            return None;
+        }
+        if constant_time_eq(unsafe { hay.get_unchecked(range.start..end) }, self.0) {
+            Some(end)
+        } else {
+            None
+        }
+    }
+}
+
+unsafe impl<'p> ReverseConsumer<[u8]> for ConstantTimeConsumer<'p> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        if range.start + self.0.len() > range.end {
+            return None;
+        }
+        let start = range.end - self.0.len();
+        if constant_time_eq(unsafe { hay.get_unchecked(start..range.end) }, self.0) {
+            Some(start)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'p, H: Haystack<Target = [u8]>> Needle<H> for ConstantTime<&'p [u8]> {
+    type Searcher = SliceSearcher<'p, u8>;
+    type Consumer = ConstantTimeConsumer<'p>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        SliceSearcher::new(self.0)
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        ConstantTimeConsumer(self.0)
+    }
+}
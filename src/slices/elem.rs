@@ -0,0 +1,170 @@
+use needle::*;
+use haystack::Span;
+use memchr::{memchr, memrchr};
+use std::ops::Range;
+use std::slice;
+
+/// Locates an element in a slice, specialized to use `memchr` for bytes.
+trait ElemPosition: PartialEq + Sized {
+    fn position(&self, hay: &[Self]) -> Option<usize>;
+    fn rposition(&self, hay: &[Self]) -> Option<usize>;
+}
+
+impl<T: PartialEq> ElemPosition for T {
+    #[inline]
+    default fn position(&self, hay: &[Self]) -> Option<usize> {
+        hay.iter().position(|x| x == self)
+    }
+
+    #[inline]
+    default fn rposition(&self, hay: &[Self]) -> Option<usize> {
+        hay.iter().rposition(|x| x == self)
+    }
+}
+
+impl ElemPosition for u8 {
+    #[inline]
+    fn position(&self, hay: &[u8]) -> Option<usize> {
+        memchr(*self, hay)
+    }
+
+    #[inline]
+    fn rposition(&self, hay: &[u8]) -> Option<usize> {
+        memrchr(*self, hay)
+    }
+}
+
+impl ElemPosition for i8 {
+    #[inline]
+    fn position(&self, hay: &[i8]) -> Option<usize> {
+        let hay = unsafe { slice::from_raw_parts(hay.as_ptr() as *const u8, hay.len()) };
+        memchr(*self as u8, hay)
+    }
+
+    #[inline]
+    fn rposition(&self, hay: &[i8]) -> Option<usize> {
+        let hay = unsafe { slice::from_raw_parts(hay.as_ptr() as *const u8, hay.len()) };
+        memrchr(*self as u8, hay)
+    }
+}
+
+/// Searcher of [`Elem`].
+#[derive(Copy, Clone, Debug)]
+pub struct ElemEqSearcher<T> {
+    elem: T,
+}
+
+unsafe impl<T: PartialEq> Searcher<[T]> for ElemEqSearcher<T> {
+    #[inline]
+    fn search(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let start = range.start;
+        let pos = self.elem.position(&hay[range])? + start;
+        Some(pos..(pos + 1))
+    }
+}
+
+unsafe impl<T: PartialEq> ReverseSearcher<[T]> for ElemEqSearcher<T> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let start = range.start;
+        let pos = self.elem.rposition(&hay[range])? + start;
+        Some(pos..(pos + 1))
+    }
+}
+
+unsafe impl<T: PartialEq> Consumer<[T]> for ElemEqSearcher<T> {
+    #[inline]
+    fn consume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        if range.start == range.end {
+            return None;
+        }
+        if *unsafe { hay.get_unchecked(range.start) } == self.elem {
+            Some(range.start + 1)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn trim_start(&mut self, hay: &[T]) -> usize {
+        hay.iter().position(|x| *x != self.elem).unwrap_or(hay.len())
+    }
+}
+
+unsafe impl<T: PartialEq> ReverseConsumer<[T]> for ElemEqSearcher<T> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        if range.start == range.end {
+            return None;
+        }
+        let last = range.end - 1;
+        if *unsafe { hay.get_unchecked(last) } == self.elem {
+            Some(last)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn trim_end(&mut self, hay: &[T]) -> usize {
+        hay.iter().rposition(|x| *x != self.elem).map_or(0, |p| p + 1)
+    }
+}
+
+unsafe impl<T: PartialEq> DoubleEndedSearcher<[T]> for ElemEqSearcher<T> {}
+unsafe impl<T: PartialEq> DoubleEndedConsumer<[T]> for ElemEqSearcher<T> {}
+
+/// A needle which matches a single element equal to the given value.
+///
+/// This is equivalent to the closure `|x: &T| *x == value`, but searching in
+/// `[u8]` and `[i8]` is accelerated using `memchr`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::Elem;
+/// use pattern_3::ext::{find, rsplit, split, trim_end};
+///
+/// let hay = &b"key=value=more"[..];
+/// assert_eq!(find(hay, Elem(b'=')), Some(3));
+/// assert_eq!(
+///     split(hay, Elem(b'=')).collect::<Vec<_>>(),
+///     vec![&b"key"[..], b"value", b"more"]
+/// );
+/// assert_eq!(rsplit(&[1, 0, 2, 0, 3][..], Elem(0)).next(), Some(&[3][..]));
+/// assert_eq!(trim_end(&[1.5, 0.0, 0.0][..], Elem(0.0)), &[1.5]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Elem<T>(pub T);
+
+macro_rules! impl_needle {
+    (<[$($gen:tt)*]> $ty:ty) => {
+        impl<$($gen)*> Needle<$ty> for Elem<T>
+        where
+            T: PartialEq,
+        {
+            type Searcher = ElemEqSearcher<T>;
+            type Consumer = ElemEqSearcher<T>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                ElemEqSearcher { elem: self.0 }
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                ElemEqSearcher { elem: self.0 }
+            }
+        }
+    }
+}
+
+impl_needle!(<['h, T]> &'h [T]);
+impl_needle!(<['h, T]> &'h mut [T]);
+#[cfg(feature = "std")]
+impl_needle!(<[T]> Vec<T>);
@@ -0,0 +1,293 @@
+use needle::*;
+use haystack::{Haystack, Span};
+use slices::slice::{Elements, FastSkipByteset, SliceSearcher, TwoWaySearcher};
+use std::ops::Range;
+
+/// Treats `PartialEq<U>` as if a `FnMut(&T, &U) -> bool`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PartialEqFn;
+
+impl<'a, 'b, T: PartialEq<U>, U> FnOnce<(&'a T, &'b U)> for PartialEqFn {
+    type Output = bool;
+    #[inline]
+    extern "rust-call" fn call_once(self, args: (&'a T, &'b U)) -> bool {
+        self.call(args)
+    }
+}
+
+impl<'a, 'b, T: PartialEq<U>, U> FnMut<(&'a T, &'b U)> for PartialEqFn {
+    #[inline]
+    extern "rust-call" fn call_mut(&mut self, args: (&'a T, &'b U)) -> bool {
+        self.call(args)
+    }
+}
+
+impl<'a, 'b, T: PartialEq<U>, U> Fn<(&'a T, &'b U)> for PartialEqFn {
+    #[inline]
+    extern "rust-call" fn call(&self, (x, y): (&'a T, &'b U)) -> bool {
+        x == y
+    }
+}
+
+//------------------------------------------------------------------------------
+// Searcher
+//------------------------------------------------------------------------------
+
+/// The elements of a hay compared to the needle with an equality function.
+struct Compared<'h, 'f, T: 'h, F: 'f> {
+    hay: &'h [T],
+    eq: &'f mut F,
+}
+
+impl<'h, 'f, T, U, F> Elements<U> for Compared<'h, 'f, T, F>
+where
+    F: FnMut(&T, &U) -> bool,
+{
+    #[inline]
+    unsafe fn eq_at(&mut self, index: usize, item: &U) -> bool {
+        (self.eq)(self.hay.get_unchecked(index), item)
+    }
+
+    #[inline]
+    unsafe fn byteset_mask_at(&mut self, _: usize) -> FastSkipByteset {
+        !0
+    }
+}
+
+/// Searcher of [`EqBy`] and [`PartialEqSlice`].
+#[derive(Clone, Debug)]
+pub struct EqBySearcher<'p, U: 'p, F> {
+    eq: F,
+    searcher: SliceSearcher<'p, U>,
+}
+
+impl<'p, U: 'p, F> EqBySearcher<'p, U, F> {
+    #[inline]
+    fn new(needle: &'p [U], eq: F) -> Self {
+        // The equality may be neither symmetric nor transitive, so the needle
+        // cannot be compared with itself to skip ahead.
+        let searcher = if needle.is_empty() {
+            SliceSearcher::Empty(EmptySearcher::default())
+        } else {
+            SliceSearcher::TwoWay(TwoWaySearcher::naive(needle))
+        };
+        EqBySearcher { eq, searcher }
+    }
+}
+
+unsafe impl<'p, T, U, F> Searcher<[T]> for EqBySearcher<'p, U, F>
+where
+    F: FnMut(&T, &U) -> bool,
+    U: 'p,
+{
+    #[inline]
+    fn search(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        match self.searcher {
+            SliceSearcher::TwoWay(ref mut searcher) => {
+                let (hay, range) = span.into_parts();
+                searcher.next_in(Compared { hay, eq: &mut self.eq }, range)
+            }
+            SliceSearcher::Empty(ref mut searcher) => searcher.search(span),
+        }
+    }
+}
+
+unsafe impl<'p, T, U, F> ReverseSearcher<[T]> for EqBySearcher<'p, U, F>
+where
+    F: FnMut(&T, &U) -> bool,
+    U: 'p,
+{
+    #[inline]
+    fn rsearch(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        match self.searcher {
+            SliceSearcher::TwoWay(ref mut searcher) => {
+                let (hay, range) = span.into_parts();
+                searcher.next_back_in(Compared { hay, eq: &mut self.eq }, range)
+            }
+            SliceSearcher::Empty(ref mut searcher) => searcher.rsearch(span),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Consumer
+//------------------------------------------------------------------------------
+
+/// Consumer of [`EqBy`] and [`PartialEqSlice`].
+#[derive(Copy, Clone, Debug)]
+pub struct EqByConsumer<'p, U: 'p, F> {
+    eq: F,
+    needle: &'p [U],
+}
+
+impl<'p, U: 'p, F> EqByConsumer<'p, U, F> {
+    #[inline]
+    fn matches<T>(&mut self, hay: &[T]) -> bool
+    where
+        F: FnMut(&T, &U) -> bool,
+    {
+        let eq = &mut self.eq;
+        hay.iter().zip(self.needle).all(|(t, u)| eq(t, u))
+    }
+}
+
+unsafe impl<'p, T, U, F> Consumer<[T]> for EqByConsumer<'p, U, F>
+where
+    F: FnMut(&T, &U) -> bool,
+    U: 'p,
+{
+    #[inline]
+    fn consume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let end = range.start + self.needle.len();
+        if end > range.end {
+            return None;
+        }
+        if self.matches(unsafe { hay.get_unchecked(range.start..end) }) {
+            Some(end)
+        } else {
+            None
+        }
+    }
+}
+
+unsafe impl<'p, T, U, F> ReverseConsumer<[T]> for EqByConsumer<'p, U, F>
+where
+    F: FnMut(&T, &U) -> bool,
+    U: 'p,
+{
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        if range.start + self.needle.len() > range.end {
+            return None;
+        }
+        let start = range.end - self.needle.len();
+        if self.matches(unsafe { hay.get_unchecked(start..range.end) }) {
+            Some(start)
+        } else {
+            None
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Needles
+//------------------------------------------------------------------------------
+
+/// A needle which matches a subslice whose elements are equal to the needle
+/// according to a custom function.
+///
+/// Created by [`eq_by`].
+#[derive(Copy, Clone, Debug)]
+pub struct EqBy<'p, U: 'p, F> {
+    needle: &'p [U],
+    eq: F,
+}
+
+/// Creates a needle matching the subslices `s` of the same length as `needle`
+/// where `eq(&s[i], &needle[i])` is true for every `i`.
+///
+/// The equality function does not need to be symmetric, transitive or even
+/// deterministic, for instance it may compare floating point numbers with a
+/// tolerance. In exchange, the needle is compared at every position of the
+/// haystack, taking *O*(*mn*) time in the worst case.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::eq_by;
+/// use pattern_3::ext::{find, match_indices};
+///
+/// let samples = [0.0, 0.49, 1.02, 0.51, 0.98, 0.0];
+/// let pulse = [0.5, 1.0, 0.5];
+/// let close = |x: &f64, y: &f64| (x - y).abs() < 0.05;
+/// assert_eq!(find(&samples[..], eq_by(&pulse, close)), Some(1));
+///
+/// let words = ["Hello", "WORLD", "hello", "world"];
+/// let caseless = |x: &&str, y: &&str| x.eq_ignore_ascii_case(y);
+/// assert_eq!(
+///     match_indices(&words[..], eq_by(&["hello", "world"], caseless))
+///         .map(|(i, _)| i)
+///         .collect::<Vec<_>>(),
+///     vec![0, 2]
+/// );
+/// ```
+#[inline]
+pub fn eq_by<'p, T, U, F>(needle: &'p [U], eq: F) -> EqBy<'p, U, F>
+where
+    F: FnMut(&T, &U) -> bool,
+{
+    EqBy { needle, eq }
+}
+
+impl<'p, T, U, F, H> Needle<H> for EqBy<'p, U, F>
+where
+    H: Haystack<Target = [T]>,
+    F: FnMut(&T, &U) -> bool,
+    U: 'p,
+{
+    type Searcher = EqBySearcher<'p, U, F>;
+    type Consumer = EqByConsumer<'p, U, F>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        EqBySearcher::new(self.needle, self.eq)
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        EqByConsumer {
+            eq: self.eq,
+            needle: self.needle,
+        }
+    }
+}
+
+/// A needle which matches a subslice whose elements are equal to the needle,
+/// where the needle may have a different element type than the haystack.
+///
+/// The elements are compared using `T: PartialEq<U>`. Unlike a plain `&[T]`
+/// needle, the two-way algorithm is not used, since its shifts rely on the
+/// equality being an equivalence relation on a single type. The needle is
+/// compared at every position of the haystack instead.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::PartialEqSlice;
+/// use pattern_3::ext::{find, split};
+///
+/// let args = vec!["cc".to_owned(), "-o".to_owned(), "out".to_owned(), "--".to_owned(), "a.c".to_owned()];
+/// assert_eq!(find(&args[..], PartialEqSlice(&["-o"])), Some(1));
+///
+/// let parts = split(&args[..], PartialEqSlice(&["--"])).collect::<Vec<_>>();
+/// assert_eq!(parts[1], ["a.c"]);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct PartialEqSlice<'p, U: 'p>(pub &'p [U]);
+
+impl<'p, T, U, H> Needle<H> for PartialEqSlice<'p, U>
+where
+    H: Haystack<Target = [T]>,
+    T: PartialEq<U>,
+    U: 'p,
+{
+    type Searcher = EqBySearcher<'p, U, PartialEqFn>;
+    type Consumer = EqByConsumer<'p, U, PartialEqFn>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        EqBySearcher::new(self.0, PartialEqFn)
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        EqByConsumer {
+            eq: PartialEqFn,
+            needle: self.0,
+        }
+    }
+}
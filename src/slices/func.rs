@@ -6,6 +6,13 @@ pub struct ElemSearcher<F> {
     predicate: F,
 }
 
+impl<F> ElemSearcher<F> {
+    #[inline]
+    pub(crate) fn new(predicate: F) -> Self {
+        ElemSearcher { predicate }
+    }
+}
+
 macro_rules! impl_needle {
     (<[$($gen:tt)*]> $ty:ty) => {
         impl<$($gen)*> Needle<$ty> for F
@@ -0,0 +1,225 @@
+use needle::*;
+use haystack::{Haystack, Span};
+use slices::slice::{Elements, FastSkipByteset, FastSkipOptimization, SliceSearcher};
+use std::ops::Range;
+
+//------------------------------------------------------------------------------
+// Projected hay
+//------------------------------------------------------------------------------
+
+/// The keys of the elements of a hay, computed as the searcher reads them.
+struct Projected<'h, 'f, T: 'h, F: 'f> {
+    hay: &'h [T],
+    key: &'f mut F,
+}
+
+impl<'h, 'f, T, K, F> Elements<K> for Projected<'h, 'f, T, F>
+where
+    F: FnMut(&T) -> K,
+    K: PartialEq,
+{
+    #[inline]
+    unsafe fn eq_at(&mut self, index: usize, item: &K) -> bool {
+        *item == (self.key)(self.hay.get_unchecked(index))
+    }
+
+    #[inline]
+    unsafe fn byteset_mask_at(&mut self, index: usize) -> FastSkipByteset {
+        (self.key)(self.hay.get_unchecked(index)).byteset_mask()
+    }
+}
+
+//------------------------------------------------------------------------------
+// Searcher
+//------------------------------------------------------------------------------
+
+/// Searcher of [`ByKey`].
+#[derive(Clone, Debug)]
+pub struct ByKeySearcher<'p, K: 'p, F> {
+    key: F,
+    searcher: SliceSearcher<'p, K>,
+}
+
+unsafe impl<'p, T, K, F> Searcher<[T]> for ByKeySearcher<'p, K, F>
+where
+    F: FnMut(&T) -> K,
+    K: PartialEq + 'p,
+{
+    #[inline]
+    fn search(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        match self.searcher {
+            SliceSearcher::TwoWay(ref mut searcher) => {
+                let (hay, range) = span.into_parts();
+                searcher.next_in(Projected { hay, key: &mut self.key }, range)
+            }
+            SliceSearcher::Empty(ref mut searcher) => searcher.search(span),
+        }
+    }
+}
+
+unsafe impl<'p, T, K, F> ReverseSearcher<[T]> for ByKeySearcher<'p, K, F>
+where
+    F: FnMut(&T) -> K,
+    K: PartialEq + 'p,
+{
+    #[inline]
+    fn rsearch(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        match self.searcher {
+            SliceSearcher::TwoWay(ref mut searcher) => {
+                let (hay, range) = span.into_parts();
+                searcher.next_back_in(Projected { hay, key: &mut self.key }, range)
+            }
+            SliceSearcher::Empty(ref mut searcher) => searcher.rsearch(span),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Consumer
+//------------------------------------------------------------------------------
+
+/// Consumer of [`ByKey`].
+#[derive(Copy, Clone, Debug)]
+pub struct ByKeyConsumer<'p, K: 'p, F> {
+    key: F,
+    needle: &'p [K],
+}
+
+impl<'p, K: PartialEq + 'p, F> ByKeyConsumer<'p, K, F> {
+    #[inline]
+    fn matches<T>(&mut self, hay: &[T]) -> bool
+    where
+        F: FnMut(&T) -> K,
+    {
+        let key = &mut self.key;
+        hay.iter().zip(self.needle).all(|(t, k)| key(t) == *k)
+    }
+}
+
+unsafe impl<'p, T, K, F> Consumer<[T]> for ByKeyConsumer<'p, K, F>
+where
+    F: FnMut(&T) -> K,
+    K: PartialEq + 'p,
+{
+    #[inline]
+    fn consume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let end = range.start + self.needle.len();
+        if end > range.end {
+            return None;
+        }
+        if self.matches(unsafe { hay.get_unchecked(range.start..end) }) {
+            Some(end)
+        } else {
+            None
+        }
+    }
+}
+
+unsafe impl<'p, T, K, F> ReverseConsumer<[T]> for ByKeyConsumer<'p, K, F>
+where
+    F: FnMut(&T) -> K,
+    K: PartialEq + 'p,
+{
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        if range.start + self.needle.len() > range.end {
+            return None;
+        }
+        let start = range.end - self.needle.len();
+        if self.matches(unsafe { hay.get_unchecked(start..range.end) }) {
+            Some(start)
+        } else {
+            None
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Needle
+//------------------------------------------------------------------------------
+
+/// A needle which matches a sequence of keys derived from the elements of a
+/// slice.
+///
+/// Created by [`by_key`].
+#[derive(Copy, Clone, Debug)]
+pub struct ByKey<'p, K: 'p, F> {
+    key: F,
+    needle: &'p [K],
+}
+
+/// Creates a needle matching the subslices whose elements, mapped through
+/// `key`, are equal to `needle`.
+///
+/// The keys are computed while searching, so the haystack is never copied.
+/// The search uses the same two-way algorithm as a plain slice needle; keys
+/// which implement `Ord` get the fast critical factorization, other keys fall
+/// back to a naive search. The `key` function may be called several times for
+/// the same element.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::by_key;
+/// use pattern_3::ext::{find, split};
+///
+/// #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// enum Kind { Ident, Colon, Number, Comma }
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Token<'a> { kind: Kind, text: &'a str }
+///
+/// let tokens = [
+///     Token { kind: Kind::Ident, text: "x" },
+///     Token { kind: Kind::Colon, text: ":" },
+///     Token { kind: Kind::Number, text: "1" },
+///     Token { kind: Kind::Comma, text: "," },
+///     Token { kind: Kind::Ident, text: "y" },
+///     Token { kind: Kind::Colon, text: ":" },
+///     Token { kind: Kind::Number, text: "2" },
+/// ];
+///
+/// let field = [Kind::Ident, Kind::Colon, Kind::Number];
+/// assert_eq!(find(&tokens[..], by_key(|t: &Token| t.kind, &field)), Some(0));
+///
+/// let parts = split(&tokens[..], by_key(|t: &Token| t.kind, &[Kind::Comma]))
+///     .map(|part| part.iter().map(|t| t.text).collect::<String>())
+///     .collect::<Vec<_>>();
+/// assert_eq!(parts, vec!["x:1", "y:2"]);
+/// ```
+#[inline]
+pub fn by_key<'p, T, K, F>(key: F, needle: &'p [K]) -> ByKey<'p, K, F>
+where
+    F: FnMut(&T) -> K,
+{
+    ByKey { key, needle }
+}
+
+impl<'p, T, K, F, H> Needle<H> for ByKey<'p, K, F>
+where
+    H: Haystack<Target = [T]>,
+    F: FnMut(&T) -> K,
+    K: PartialEq + 'p,
+{
+    type Searcher = ByKeySearcher<'p, K, F>;
+    type Consumer = ByKeyConsumer<'p, K, F>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        ByKeySearcher {
+            key: self.key,
+            searcher: SliceSearcher::new(self.needle),
+        }
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        ByKeyConsumer {
+            key: self.key,
+            needle: self.needle,
+        }
+    }
+}
@@ -86,5 +86,24 @@ unsafe impl<T> Haystack for Vec<T> {
     }
 }
 
-mod func;
+pub(crate) mod func;
+pub(crate) mod elem;
+pub(crate) mod set;
+mod range;
 pub(crate) mod slice;
+pub(crate) mod ascii;
+pub(crate) mod constant_time;
+pub(crate) mod key;
+pub(crate) mod eq;
+pub(crate) mod predicates;
+pub(crate) mod sorted;
+#[cfg(feature = "std")]
+pub(crate) mod aho_corasick;
+#[cfg(feature = "std")]
+pub(crate) mod signature;
+#[cfg(feature = "std")]
+pub(crate) mod trie;
+#[cfg(feature = "std")]
+pub(crate) mod owned;
+#[cfg(all(feature = "std", feature = "regex"))]
+pub(crate) mod regex;
@@ -0,0 +1,177 @@
+use needle::*;
+use haystack::{Haystack, Span};
+use slices::slice::{NaiveSearcher, TwoWaySearcher, TwoWayState};
+use std::ops::{Deref, Range};
+
+//------------------------------------------------------------------------------
+// Searcher
+//------------------------------------------------------------------------------
+
+#[derive(Clone, Debug)]
+pub(crate) enum Kind {
+    TwoWay(TwoWayState),
+    Empty(EmptySearcher),
+}
+
+/// Searcher of a needle which owns its content, like `Vec<T>` or `String`.
+///
+/// This runs the same algorithm as the searcher of a borrowed slice or
+/// string, with the needle stored inside instead of borrowed. It has no
+/// lifetime parameter, so it can be kept in long-lived structures, and it is
+/// `Send` and `Sync` whenever the needle is.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::{Needle, OwnedSliceSearcher, Searcher, Span};
+///
+/// struct Filter {
+///     searcher: OwnedSliceSearcher<String>,
+/// }
+///
+/// impl Filter {
+///     fn new(word: &str) -> Self {
+///         Filter { searcher: Needle::<&str>::into_searcher(word.to_owned()) }
+///     }
+///
+///     fn matches(&mut self, line: &str) -> bool {
+///         self.searcher.search(Span::from(line)).is_some()
+///     }
+/// }
+///
+/// let mut filter = Filter::new("error");
+/// assert!(filter.matches("an error occurred"));
+/// assert!(!filter.matches("all good"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct OwnedSliceSearcher<N> {
+    // This searcher is only created from the owned needles in this crate,
+    // whose `Deref` always returns the same content. The state therefore
+    // stays valid for the dereferenced needle.
+    pub(crate) needle: N,
+    pub(crate) kind: Kind,
+}
+
+impl<N: Deref> OwnedSliceSearcher<N> {
+    #[inline]
+    pub(crate) fn new<T: PartialEq>(needle: N) -> Self
+    where
+        N::Target: AsRef<[T]>,
+    {
+        let kind = {
+            let slice = needle.as_ref();
+            if slice.is_empty() {
+                Kind::Empty(EmptySearcher::default())
+            } else {
+                Kind::TwoWay(TwoWaySearcher::new(slice).state())
+            }
+        };
+        OwnedSliceSearcher { needle, kind }
+    }
+}
+
+/// Runs the two-way searcher of `needle` from its state, saving the state
+/// afterwards.
+///
+/// `needle` must be the one the state was computed from.
+#[inline]
+pub(crate) unsafe fn run_two_way<T, R, F>(state: &mut TwoWayState, needle: &[T], f: F) -> R
+where
+    F: FnOnce(&mut TwoWaySearcher<T>) -> R,
+{
+    let mut searcher = TwoWaySearcher::from_state(*state, needle);
+    let result = f(&mut searcher);
+    *state = searcher.state();
+    result
+}
+
+unsafe impl<T, N> Searcher<[T]> for OwnedSliceSearcher<N>
+where
+    T: PartialEq,
+    N: Deref<Target = [T]>,
+{
+    #[inline]
+    fn search(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        match self.kind {
+            Kind::TwoWay(ref mut state) => {
+                let (hay, range) = span.into_parts();
+                unsafe { run_two_way(state, &self.needle, |s| s.next(hay, range)) }
+            }
+            Kind::Empty(ref mut searcher) => searcher.search(span),
+        }
+    }
+}
+
+unsafe impl<T, N> ReverseSearcher<[T]> for OwnedSliceSearcher<N>
+where
+    T: PartialEq,
+    N: Deref<Target = [T]>,
+{
+    #[inline]
+    fn rsearch(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        match self.kind {
+            Kind::TwoWay(ref mut state) => {
+                let (hay, range) = span.into_parts();
+                unsafe { run_two_way(state, &self.needle, |s| s.next_back(hay, range)) }
+            }
+            Kind::Empty(ref mut searcher) => searcher.rsearch(span),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Consumer
+//------------------------------------------------------------------------------
+
+/// Consumer of a needle which owns its content, like `Vec<T>` or `String`.
+#[derive(Clone, Debug)]
+pub struct OwnedNaiveSearcher<N>(pub(crate) N);
+
+unsafe impl<T, N> Consumer<[T]> for OwnedNaiveSearcher<N>
+where
+    T: PartialEq,
+    N: Deref<Target = [T]>,
+{
+    #[inline]
+    fn consume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        NaiveSearcher::new(&self.0).consume(span)
+    }
+}
+
+unsafe impl<T, N> ReverseConsumer<[T]> for OwnedNaiveSearcher<N>
+where
+    T: PartialEq,
+    N: Deref<Target = [T]>,
+{
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        NaiveSearcher::new(&self.0).rconsume(span)
+    }
+}
+
+//------------------------------------------------------------------------------
+// Needles
+//------------------------------------------------------------------------------
+
+macro_rules! impl_needle {
+    ($ty:ty) => {
+        impl<T: PartialEq, H: Haystack<Target = [T]>> Needle<H> for $ty {
+            type Searcher = OwnedSliceSearcher<Self>;
+            type Consumer = OwnedNaiveSearcher<Self>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                OwnedSliceSearcher::new(self)
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                OwnedNaiveSearcher(self)
+            }
+        }
+    }
+}
+
+impl_needle!(Vec<T>);
+impl_needle!(Box<[T]>);
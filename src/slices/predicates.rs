@@ -0,0 +1,189 @@
+use needle::*;
+use haystack::Span;
+use std::ops::Range;
+
+/// A needle which matches consecutive elements, each satisfying the predicate
+/// at the same position.
+///
+/// On a `[T]` haystack the predicates are `Fn(&T) -> bool`, and on a `str`
+/// haystack they are `Fn(char) -> bool` applied to consecutive characters.
+/// All predicates must have the same type, so different closures need to be
+/// coerced to function pointers or trait objects like `&dyn Fn(char) -> bool`.
+///
+/// Since the matches may overlap in arbitrary ways, the predicates are tested
+/// at every position of the haystack. A needle without any predicate matches
+/// the empty range everywhere, like an empty slice.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::Predicates;
+/// use pattern_3::ext::{match_ranges, rfind, starts_with};
+///
+/// let digit: fn(&u8) -> bool = u8::is_ascii_digit;
+/// let colon: fn(&u8) -> bool = |b| *b == b':';
+/// let time = Predicates(&[digit, digit, colon, digit, digit]);
+/// assert_eq!(
+///     match_ranges(&b"at 12:30 or 7:05, 19:45"[..], time)
+///         .map(|(r, _)| r)
+///         .collect::<Vec<_>>(),
+///     vec![3..8, 18..23]
+/// );
+///
+/// let upper = |c: char| c.is_uppercase();
+/// let lower = |c: char| c.is_lowercase();
+/// let word: &[&dyn Fn(char) -> bool] = &[&upper, &lower, &lower];
+/// assert!(starts_with("Été", Predicates(word)));
+/// assert_eq!(rfind("The Cat sat", Predicates(word)), Some(4));
+/// ```
+#[derive(Debug)]
+pub struct Predicates<'p, P: 'p>(pub &'p [P]);
+
+impl<'p, P: 'p> Clone for Predicates<'p, P> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'p, P: 'p> Copy for Predicates<'p, P> {}
+
+/// Searcher of [`Predicates`].
+#[derive(Debug)]
+pub struct PredicatesSearcher<'p, P: 'p> {
+    pub(crate) predicates: &'p [P],
+    pub(crate) empty: EmptySearcher,
+}
+
+impl<'p, P: 'p> Clone for PredicatesSearcher<'p, P> {
+    #[inline]
+    fn clone(&self) -> Self {
+        PredicatesSearcher {
+            predicates: self.predicates,
+            empty: self.empty.clone(),
+        }
+    }
+}
+
+impl<'p, P: 'p> PredicatesSearcher<'p, P> {
+    #[inline]
+    pub(crate) fn new(predicates: &'p [P]) -> Self {
+        PredicatesSearcher {
+            predicates,
+            empty: EmptySearcher::default(),
+        }
+    }
+
+    #[inline]
+    fn matches<T>(&self, window: &[T]) -> bool
+    where
+        P: Fn(&T) -> bool,
+    {
+        window.iter().zip(self.predicates).all(|(x, p)| p(x))
+    }
+}
+
+unsafe impl<'p, T, P> Searcher<[T]> for PredicatesSearcher<'p, P>
+where
+    P: Fn(&T) -> bool,
+{
+    #[inline]
+    fn search(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        let len = self.predicates.len();
+        if len == 0 {
+            return self.empty.search(span);
+        }
+        let (hay, range) = span.into_parts();
+        if range.end - range.start < len {
+            return None;
+        }
+        (range.start..=(range.end - len))
+            .find(|&i| self.matches(unsafe { hay.get_unchecked(i..(i + len)) }))
+            .map(|i| i..(i + len))
+    }
+}
+
+unsafe impl<'p, T, P> ReverseSearcher<[T]> for PredicatesSearcher<'p, P>
+where
+    P: Fn(&T) -> bool,
+{
+    #[inline]
+    fn rsearch(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        let len = self.predicates.len();
+        if len == 0 {
+            return self.empty.rsearch(span);
+        }
+        let (hay, range) = span.into_parts();
+        if range.end - range.start < len {
+            return None;
+        }
+        (range.start..=(range.end - len))
+            .rev()
+            .find(|&i| self.matches(unsafe { hay.get_unchecked(i..(i + len)) }))
+            .map(|i| i..(i + len))
+    }
+}
+
+unsafe impl<'p, T, P> Consumer<[T]> for PredicatesSearcher<'p, P>
+where
+    P: Fn(&T) -> bool,
+{
+    #[inline]
+    fn consume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let end = range.start + self.predicates.len();
+        if end <= range.end && self.matches(unsafe { hay.get_unchecked(range.start..end) }) {
+            Some(end)
+        } else {
+            None
+        }
+    }
+}
+
+unsafe impl<'p, T, P> ReverseConsumer<[T]> for PredicatesSearcher<'p, P>
+where
+    P: Fn(&T) -> bool,
+{
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let len = self.predicates.len();
+        if range.start + len > range.end {
+            return None;
+        }
+        let start = range.end - len;
+        if self.matches(unsafe { hay.get_unchecked(start..range.end) }) {
+            Some(start)
+        } else {
+            None
+        }
+    }
+}
+
+macro_rules! impl_needle {
+    (<[$($gen:tt)*]> $ty:ty) => {
+        impl<$($gen)*> Needle<$ty> for Predicates<'p, P>
+        where
+            P: Fn(&T) -> bool,
+        {
+            type Searcher = PredicatesSearcher<'p, P>;
+            type Consumer = PredicatesSearcher<'p, P>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                PredicatesSearcher::new(self.0)
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                PredicatesSearcher::new(self.0)
+            }
+        }
+    }
+}
+
+impl_needle!(<['p, 'h, T, P]> &'h [T]);
+impl_needle!(<['p, 'h, T, P]> &'h mut [T]);
+#[cfg(feature = "std")]
+impl_needle!(<['p, T, P]> Vec<T>);
@@ -0,0 +1,75 @@
+use needle::*;
+use slices::func::ElemSearcher;
+use std::ops::{Range, RangeBounds, RangeInclusive};
+
+/// Treats a range as if a `FnMut(&T) -> bool` checking whether the element is
+/// inside the range.
+#[derive(Clone, Debug)]
+pub struct RangeContains<R>(R);
+
+impl<'a, T: PartialOrd, R: RangeBounds<T>> FnOnce<(&'a T,)> for RangeContains<R> {
+    type Output = bool;
+    #[inline]
+    extern "rust-call" fn call_once(self, args: (&'a T,)) -> bool {
+        self.call(args)
+    }
+}
+
+impl<'a, T: PartialOrd, R: RangeBounds<T>> FnMut<(&'a T,)> for RangeContains<R> {
+    #[inline]
+    extern "rust-call" fn call_mut(&mut self, args: (&'a T,)) -> bool {
+        self.call(args)
+    }
+}
+
+impl<'a, T: PartialOrd, R: RangeBounds<T>> Fn<(&'a T,)> for RangeContains<R> {
+    #[inline]
+    extern "rust-call" fn call(&self, (x,): (&'a T,)) -> bool {
+        self.0.contains(x)
+    }
+}
+
+macro_rules! impl_needle {
+    (<[$($gen:tt)*]> $ty:ty) => {
+        impl<$($gen)*> Needle<$ty> for Range<T>
+        where
+            T: PartialOrd,
+        {
+            type Searcher = ElemSearcher<RangeContains<Self>>;
+            type Consumer = ElemSearcher<RangeContains<Self>>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                ElemSearcher::new(RangeContains(self))
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                ElemSearcher::new(RangeContains(self))
+            }
+        }
+
+        impl<$($gen)*> Needle<$ty> for RangeInclusive<T>
+        where
+            T: PartialOrd,
+        {
+            type Searcher = ElemSearcher<RangeContains<Self>>;
+            type Consumer = ElemSearcher<RangeContains<Self>>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                ElemSearcher::new(RangeContains(self))
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                ElemSearcher::new(RangeContains(self))
+            }
+        }
+    }
+}
+
+impl_needle!(<['h, T]> &'h [T]);
+impl_needle!(<['h, T]> &'h mut [T]);
+#[cfg(feature = "std")]
+impl_needle!(<[T]> Vec<T>);
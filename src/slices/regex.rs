@@ -0,0 +1,171 @@
+use needle::*;
+use haystack::{Haystack, Span};
+use regex::bytes;
+use std::ops::Range;
+
+/// Searcher of a regular expression.
+///
+/// The regular expression sees the whole hay before the searched range, so
+/// anchors like `^` and `\b` consider the content before the range. However,
+/// the hay is truncated at the end of the range, thus `$` also matches at the
+/// end of the range.
+///
+/// Regular expressions can only be executed forward. Searching backward is
+/// implemented by finding the last match in the range, which takes time
+/// proportional to the length of the range for each match found. Iterating
+/// over all matches backward, e.g. with [`rsplit`](::ext::rsplit) or
+/// [`rmatches`](::ext::rmatches), thus takes time quadratic in the length of
+/// the hay.
+///
+/// This type is available only with the `regex` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// extern crate regex;
+/// use pattern_3::ext::{split, rfind};
+/// use regex::Regex;
+///
+/// let regex = Regex::new(r"\s*;\s*").unwrap();
+/// assert_eq!(split("a; b ;c", &regex).collect::<Vec<_>>(), vec!["a", "b", "c"]);
+/// assert_eq!(rfind("a; b ;c", &regex), Some(4));
+/// ```
+#[derive(Clone, Debug)]
+pub struct RegexSearcher<'p, R: 'p> {
+    regex: &'p R,
+    allow_empty_match_front: bool,
+    allow_empty_match_back: bool,
+}
+
+impl<'p, R: 'p> RegexSearcher<'p, R> {
+    #[inline]
+    pub(crate) fn new(regex: &'p R) -> Self {
+        RegexSearcher {
+            regex,
+            allow_empty_match_front: true,
+            allow_empty_match_back: true,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn regex(&self) -> &'p R {
+        self.regex
+    }
+
+    /// Finds the leftmost match in `range`.
+    ///
+    /// `find_at(start)` should return the leftmost match starting at or after
+    /// `start` in the hay truncated to `range.end`.
+    pub(crate) fn search_in<F, N>(&mut self, range: Range<usize>, mut find_at: F, next_index: N) -> Option<Range<usize>>
+    where
+        F: FnMut(usize) -> Option<Range<usize>>,
+        N: FnOnce(usize) -> usize,
+    {
+        let mut m = find_at(range.start)?;
+        if m.end == range.start && !self.allow_empty_match_front {
+            if range.start == range.end {
+                return None;
+            }
+            m = find_at(next_index(range.start))?;
+        }
+        self.allow_empty_match_front = false;
+        Some(m)
+    }
+
+    /// Finds the last match in `range`, by iterating through all matches from
+    /// the start of `range`.
+    pub(crate) fn rsearch_in<F, N>(&mut self, range: Range<usize>, mut find_at: F, mut next_index: N) -> Option<Range<usize>>
+    where
+        F: FnMut(usize) -> Option<Range<usize>>,
+        N: FnMut(usize) -> usize,
+    {
+        let mut last: Option<Range<usize>> = None;
+        let mut start = range.start;
+        while let Some(m) = find_at(start) {
+            let is_empty = m.start == m.end;
+            // An empty match right after the previous match is skipped, just
+            // like the iterators of the `regex` crate.
+            let after_last = match last {
+                Some(ref last) => last.end == m.start,
+                None => false,
+            };
+            let rejected = is_empty && m.end == range.end && !self.allow_empty_match_back;
+            if !(is_empty && after_last) && !rejected {
+                last = Some(m.clone());
+            }
+            if !is_empty {
+                start = m.end;
+            } else if m.end == range.end {
+                break;
+            } else {
+                start = next_index(m.end);
+            }
+        }
+        if last.is_some() {
+            self.allow_empty_match_back = false;
+        }
+        last
+    }
+
+    /// Checks whether the leftmost match in `range` starts at `range.start`.
+    #[inline]
+    pub(crate) fn consume_in<F>(&mut self, range: Range<usize>, find_at: F) -> Option<usize>
+    where
+        F: FnOnce(usize) -> Option<Range<usize>>,
+    {
+        let m = find_at(range.start)?;
+        if m.start == range.start {
+            Some(m.end)
+        } else {
+            None
+        }
+    }
+}
+
+#[inline]
+fn find_at(regex: &bytes::Regex, hay: &[u8], start: usize) -> Option<Range<usize>> {
+    regex.find_at(hay, start).map(|m| m.start()..m.end())
+}
+
+unsafe impl<'p> Searcher<[u8]> for RegexSearcher<'p, bytes::Regex> {
+    #[inline]
+    fn search(&mut self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let (regex, hay) = (self.regex, &hay[..range.end]);
+        self.search_in(range, |start| find_at(regex, hay, start), |i| i + 1)
+    }
+}
+
+unsafe impl<'p> ReverseSearcher<[u8]> for RegexSearcher<'p, bytes::Regex> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let (regex, hay) = (self.regex, &hay[..range.end]);
+        self.rsearch_in(range, |start| find_at(regex, hay, start), |i| i + 1)
+    }
+}
+
+unsafe impl<'p> Consumer<[u8]> for RegexSearcher<'p, bytes::Regex> {
+    #[inline]
+    fn consume(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let (regex, hay) = (self.regex, &hay[..range.end]);
+        self.consume_in(range, |start| find_at(regex, hay, start))
+    }
+}
+
+impl<'p, H: Haystack<Target = [u8]>> Needle<H> for &'p bytes::Regex {
+    type Searcher = RegexSearcher<'p, bytes::Regex>;
+    type Consumer = RegexSearcher<'p, bytes::Regex>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        RegexSearcher::new(self)
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        RegexSearcher::new(self)
+    }
+}
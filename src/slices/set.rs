@@ -0,0 +1,248 @@
+use needle::*;
+use haystack::Span;
+use memchr::{memchr, memchr2, memchr3, memrchr, memrchr2, memrchr3};
+use std::ops::Range;
+
+//------------------------------------------------------------------------------
+// Byte set representation
+//------------------------------------------------------------------------------
+
+/// A set of bytes, stored in the representation most efficient to search for.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Bytes {
+    One(u8),
+    Two(u8, u8),
+    Three(u8, u8, u8),
+    /// A 256-bit bitmap, bit `b` is set when the byte `b` is a member.
+    Bitmap([u64; 4]),
+}
+
+impl Bytes {
+    pub(crate) fn new(bytes: &[u8]) -> Self {
+        let mut bitmap = [0u64; 4];
+        for &b in bytes {
+            bitmap[(b >> 6) as usize] |= 1 << (b & 63);
+        }
+        Bytes::from_bitmap(bitmap)
+    }
+
+    /// Creates a set from a 256-bit bitmap, where bit `b` is set when the byte
+    /// `b` is a member.
+    pub(crate) fn from_bitmap(bitmap: [u64; 4]) -> Self {
+        let mut members = [0u8; 3];
+        let mut count = 0;
+        for b in 0..=255u8 {
+            if bitmap[(b >> 6) as usize] & (1 << (b & 63)) != 0 {
+                if count == 3 {
+                    return Bytes::Bitmap(bitmap);
+                }
+                members[count] = b;
+                count += 1;
+            }
+        }
+        match count {
+            1 => Bytes::One(members[0]),
+            2 => Bytes::Two(members[0], members[1]),
+            3 => Bytes::Three(members[0], members[1], members[2]),
+            _ => Bytes::Bitmap(bitmap),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn contains(&self, b: u8) -> bool {
+        match *self {
+            Bytes::One(a) => b == a,
+            Bytes::Two(a1, a2) => b == a1 || b == a2,
+            Bytes::Three(a1, a2, a3) => b == a1 || b == a2 || b == a3,
+            Bytes::Bitmap(ref bitmap) => bitmap[(b >> 6) as usize] & (1 << (b & 63)) != 0,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn position(&self, hay: &[u8]) -> Option<usize> {
+        match *self {
+            Bytes::One(a) => memchr(a, hay),
+            Bytes::Two(a1, a2) => memchr2(a1, a2, hay),
+            Bytes::Three(a1, a2, a3) => memchr3(a1, a2, a3, hay),
+            Bytes::Bitmap(_) => hay.iter().position(|b| self.contains(*b)),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn rposition(&self, hay: &[u8]) -> Option<usize> {
+        match *self {
+            Bytes::One(a) => memrchr(a, hay),
+            Bytes::Two(a1, a2) => memrchr2(a1, a2, hay),
+            Bytes::Three(a1, a2, a3) => memrchr3(a1, a2, a3, hay),
+            Bytes::Bitmap(_) => hay.iter().rposition(|b| self.contains(*b)),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn search(&self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let start = range.start;
+        let pos = self.position(&hay[range])? + start;
+        Some(pos..(pos + 1))
+    }
+
+    #[inline]
+    pub(crate) fn rsearch(&self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let start = range.start;
+        let pos = self.rposition(&hay[range])? + start;
+        Some(pos..(pos + 1))
+    }
+
+    #[inline]
+    pub(crate) fn consume(&self, span: Span<&[u8]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        if range.start != range.end && self.contains(hay[range.start]) {
+            Some(range.start + 1)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub(crate) fn rconsume(&self, span: Span<&[u8]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        if range.start != range.end && self.contains(hay[range.end - 1]) {
+            Some(range.end - 1)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub(crate) fn trim_start(&self, hay: &[u8]) -> usize {
+        hay.iter().position(|b| !self.contains(*b)).unwrap_or(hay.len())
+    }
+
+    #[inline]
+    pub(crate) fn trim_end(&self, hay: &[u8]) -> usize {
+        hay.iter().rposition(|b| !self.contains(*b)).map_or(0, |p| p + 1)
+    }
+}
+
+//------------------------------------------------------------------------------
+// Byte set needle
+//------------------------------------------------------------------------------
+
+/// A needle which matches any single byte in a set.
+///
+/// The set is preprocessed on construction. Sets of one to three bytes are
+/// searched using `memchr`, `memchr2` and `memchr3`, while larger sets are
+/// looked up in a 256-bit bitmap.
+///
+/// An empty set never matches.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::ByteSet;
+/// use pattern_3::ext::{split, trim};
+///
+/// let whitespace = ByteSet::new(b" \t\n");
+/// assert_eq!(
+///     split(&b"a b\tc\nd"[..], whitespace).collect::<Vec<_>>(),
+///     vec![&b"a"[..], b"b", b"c", b"d"]
+/// );
+/// assert_eq!(trim(&b"\t x \n"[..], whitespace), b"x");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct ByteSet {
+    bytes: Option<Bytes>,
+}
+
+impl ByteSet {
+    /// Creates a set containing the given bytes.
+    ///
+    /// Duplicated bytes are ignored.
+    pub fn new(bytes: &[u8]) -> Self {
+        ByteSet {
+            bytes: if bytes.is_empty() { None } else { Some(Bytes::new(bytes)) },
+        }
+    }
+
+    /// Checks whether the byte is a member of this set.
+    #[inline]
+    pub fn contains(&self, b: u8) -> bool {
+        match self.bytes {
+            Some(bytes) => bytes.contains(b),
+            None => false,
+        }
+    }
+}
+
+/// Searcher of [`ByteSet`].
+#[derive(Copy, Clone, Debug)]
+pub struct ByteSetSearcher {
+    bytes: Option<Bytes>,
+}
+
+unsafe impl Searcher<[u8]> for ByteSetSearcher {
+    #[inline]
+    fn search(&mut self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        self.bytes?.search(span)
+    }
+}
+
+unsafe impl ReverseSearcher<[u8]> for ByteSetSearcher {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        self.bytes?.rsearch(span)
+    }
+}
+
+unsafe impl Consumer<[u8]> for ByteSetSearcher {
+    #[inline]
+    fn consume(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        self.bytes?.consume(span)
+    }
+
+    #[inline]
+    fn trim_start(&mut self, hay: &[u8]) -> usize {
+        self.bytes.map_or(0, |bytes| bytes.trim_start(hay))
+    }
+}
+
+unsafe impl ReverseConsumer<[u8]> for ByteSetSearcher {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        self.bytes?.rconsume(span)
+    }
+
+    #[inline]
+    fn trim_end(&mut self, hay: &[u8]) -> usize {
+        self.bytes.map_or(hay.len(), |bytes| bytes.trim_end(hay))
+    }
+}
+
+unsafe impl DoubleEndedSearcher<[u8]> for ByteSetSearcher {}
+unsafe impl DoubleEndedConsumer<[u8]> for ByteSetSearcher {}
+
+macro_rules! impl_needle {
+    (<[$($gen:tt)*]> $ty:ty) => {
+        impl<$($gen)*> Needle<$ty> for ByteSet {
+            type Searcher = ByteSetSearcher;
+            type Consumer = ByteSetSearcher;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                ByteSetSearcher { bytes: self.bytes }
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                ByteSetSearcher { bytes: self.bytes }
+            }
+        }
+    }
+}
+
+impl_needle!(<['h]> &'h [u8]);
+impl_needle!(<['h]> &'h mut [u8]);
+#[cfg(feature = "std")]
+impl_needle!(<[]> Vec<u8>);
@@ -0,0 +1,348 @@
+use needle::*;
+use haystack::{Haystack, Span};
+use slices::slice::TwoWaySearcher;
+use memchr::{memchr, memrchr};
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+//------------------------------------------------------------------------------
+// Parsing
+//------------------------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ParseErrorKind {
+    InvalidCharacter(usize),
+    HalfByte,
+}
+
+/// The error returned when a signature cannot be parsed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ParseSignatureError {
+    kind: ParseErrorKind,
+}
+
+impl ParseSignatureError {
+    /// Returns the byte offset of the invalid character in the parsed string,
+    /// or `None` if the string ends in the middle of a byte.
+    pub fn position(&self) -> Option<usize> {
+        match self.kind {
+            ParseErrorKind::InvalidCharacter(i) => Some(i),
+            ParseErrorKind::HalfByte => None,
+        }
+    }
+}
+
+impl fmt::Display for ParseSignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ParseErrorKind::InvalidCharacter(i) => write!(f, "invalid character in signature at {}", i),
+            ParseErrorKind::HalfByte => f.write_str("signature ends in the middle of a byte"),
+        }
+    }
+}
+
+impl Error for ParseSignatureError {}
+
+//------------------------------------------------------------------------------
+// Signature
+//------------------------------------------------------------------------------
+
+/// A needle which matches a sequence of bytes, some bits of which can be
+/// anything.
+///
+/// A signature is usually parsed from a string of hexadecimal digits, where a
+/// `?` stands for a wildcard nibble. Whitespace between the digits is
+/// ignored, so `4D 5A ?? 9?` matches the bytes `0x4D`, `0x5A`, any byte, and
+/// a byte whose high nibble is `9`.
+///
+/// The signature is searched by first locating its longest run of exact
+/// bytes, using `memchr` for a single byte or the two-way algorithm for more,
+/// and then comparing the remaining bytes around it. Since the matches of a
+/// signature may overlap, only the leftmost (or rightmost) match is found by
+/// a search.
+///
+/// This type is available only with the `std` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::Signature;
+/// use pattern_3::ext::{contains, match_ranges};
+///
+/// let pe = "4D 5A ?? ?? 90 00".parse::<Signature>().unwrap();
+/// let hay = &b"junk MZ\x01\x02\x90\x00 MZ\x90\x00"[..];
+/// assert!(contains(hay, &pe));
+/// assert_eq!(
+///     match_ranges(hay, &pe).map(|(r, _)| r).collect::<Vec<_>>(),
+///     vec![5..11]
+/// );
+///
+/// let call = Signature::new(&[Some(0xe8), None, None, None, None]);
+/// assert!(contains(&b"\x90\xe8\x10\x20\x30\x40"[..], &call));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Signature {
+    values: Vec<u8>,
+    /// The bits which must be equal to `values` for each byte.
+    masks: Vec<u8>,
+    /// The longest run of bytes without any wildcard bits.
+    literal: Range<usize>,
+}
+
+impl Signature {
+    fn from_masked(values: Vec<u8>, masks: Vec<u8>) -> Self {
+        let mut literal = 0..0;
+        let mut start = 0;
+        for (i, &mask) in masks.iter().enumerate() {
+            if mask != 0xff {
+                start = i + 1;
+            } else if i + 1 - start > literal.end - literal.start {
+                literal = start..(i + 1);
+            }
+        }
+        Signature { values, masks, literal }
+    }
+
+    /// Creates a signature from a sequence of bytes, where `None` matches any
+    /// byte.
+    pub fn new(bytes: &[Option<u8>]) -> Self {
+        let values = bytes.iter().map(|b| b.unwrap_or(0)).collect();
+        let masks = bytes.iter().map(|b| if b.is_some() { 0xff } else { 0 }).collect();
+        Self::from_masked(values, masks)
+    }
+
+    /// Returns the number of bytes matched by this signature.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Checks whether the signature has no bytes, and thus matches an empty
+    /// range everywhere.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Checks whether the signature matches the given bytes exactly.
+    pub fn matches(&self, bytes: &[u8]) -> bool {
+        bytes.len() == self.len() && bytes.iter()
+            .zip(&self.values)
+            .zip(&self.masks)
+            .all(|((b, v), m)| (b ^ v) & m == 0)
+    }
+}
+
+impl FromStr for Signature {
+    type Err = ParseSignatureError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut values = Vec::new();
+        let mut masks = Vec::new();
+        let mut high = None;
+        for (i, c) in s.char_indices() {
+            if c.is_ascii_whitespace() {
+                continue;
+            }
+            let nibble = match c {
+                '?' => (0, 0),
+                _ => match c.to_digit(16) {
+                    Some(d) => (d as u8, 0xf),
+                    None => return Err(ParseSignatureError { kind: ParseErrorKind::InvalidCharacter(i) }),
+                },
+            };
+            match high.take() {
+                None => high = Some(nibble),
+                Some((value, mask)) => {
+                    values.push(value << 4 | nibble.0);
+                    masks.push(mask << 4 | nibble.1);
+                }
+            }
+        }
+        if high.is_some() {
+            return Err(ParseSignatureError { kind: ParseErrorKind::HalfByte });
+        }
+        Ok(Self::from_masked(values, masks))
+    }
+}
+
+impl fmt::Display for Signature {
+    /// Formats the signature in the syntax accepted by `parse`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (&value, &mask)) in self.values.iter().zip(&self.masks).enumerate() {
+            if i != 0 {
+                f.write_str(" ")?;
+            }
+            for &shift in &[4, 0] {
+                if (mask >> shift) & 0xf == 0 {
+                    f.write_str("?")?;
+                } else {
+                    write!(f, "{:X}", (value >> shift) & 0xf)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+// Searcher
+//------------------------------------------------------------------------------
+
+#[derive(Copy, Clone, Debug)]
+enum Literal<'p> {
+    None,
+    Byte(u8),
+    Bytes(TwoWaySearcher<'p, u8>),
+}
+
+/// Searcher of [`Signature`].
+#[derive(Clone, Debug)]
+pub struct SignatureSearcher<'p> {
+    signature: &'p Signature,
+    literal: Literal<'p>,
+    allow_empty_match_front: bool,
+    allow_empty_match_back: bool,
+}
+
+impl<'p> SignatureSearcher<'p> {
+    fn new(signature: &'p Signature) -> Self {
+        let bytes = &signature.values[signature.literal.clone()];
+        let literal = match bytes.len() {
+            0 => Literal::None,
+            1 => Literal::Byte(bytes[0]),
+            _ => Literal::Bytes(TwoWaySearcher::new(bytes)),
+        };
+        SignatureSearcher {
+            signature,
+            literal,
+            allow_empty_match_front: true,
+            allow_empty_match_back: true,
+        }
+    }
+
+    /// Finds the first position in `starts` where the literal run of the
+    /// signature occurs.
+    fn next_candidate(&mut self, hay: &[u8], starts: Range<usize>) -> Option<usize> {
+        let offset = self.signature.literal.clone();
+        match self.literal {
+            Literal::None => Some(starts.start),
+            Literal::Byte(b) => {
+                let hay = &hay[(starts.start + offset.start)..(starts.end + offset.start)];
+                memchr(b, hay).map(|i| starts.start + i)
+            }
+            Literal::Bytes(ref mut searcher) => {
+                let range = (starts.start + offset.start)..(starts.end - 1 + offset.end);
+                searcher.next(hay, range).map(|r| r.start - offset.start)
+            }
+        }
+    }
+
+    /// Finds the last position in `starts` where the literal run of the
+    /// signature occurs.
+    fn next_back_candidate(&mut self, hay: &[u8], starts: Range<usize>) -> Option<usize> {
+        let offset = self.signature.literal.clone();
+        match self.literal {
+            Literal::None => Some(starts.end - 1),
+            Literal::Byte(b) => {
+                let hay = &hay[(starts.start + offset.start)..(starts.end + offset.start)];
+                memrchr(b, hay).map(|i| starts.start + i)
+            }
+            Literal::Bytes(ref mut searcher) => {
+                let range = (starts.start + offset.start)..(starts.end - 1 + offset.end);
+                searcher.next_back(hay, range).map(|r| r.start - offset.start)
+            }
+        }
+    }
+}
+
+unsafe impl<'p> Searcher<[u8]> for SignatureSearcher<'p> {
+    fn search(&mut self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let len = self.signature.len();
+        let mut start = range.start;
+        if len == 0 && !self.allow_empty_match_front {
+            if start == range.end {
+                return None;
+            }
+            start += 1;
+        }
+        while start + len <= range.end {
+            let candidate = self.next_candidate(hay, start..(range.end - len + 1))?;
+            let end = candidate + len;
+            if self.signature.matches(&hay[candidate..end]) {
+                self.allow_empty_match_front = false;
+                return Some(candidate..end);
+            }
+            start = candidate + 1;
+        }
+        None
+    }
+}
+
+unsafe impl<'p> ReverseSearcher<[u8]> for SignatureSearcher<'p> {
+    fn rsearch(&mut self, span: Span<&[u8]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let len = self.signature.len();
+        let mut end = range.end;
+        if len == 0 && !self.allow_empty_match_back {
+            if end == range.start {
+                return None;
+            }
+            end -= 1;
+        }
+        while range.start + len <= end {
+            let candidate = self.next_back_candidate(hay, range.start..(end - len + 1))?;
+            if self.signature.matches(&hay[candidate..(candidate + len)]) {
+                self.allow_empty_match_back = false;
+                return Some(candidate..(candidate + len));
+            }
+            end = candidate + len - 1;
+        }
+        None
+    }
+}
+
+unsafe impl<'p> Consumer<[u8]> for SignatureSearcher<'p> {
+    #[inline]
+    fn consume(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let end = range.start + self.signature.len();
+        if end <= range.end && self.signature.matches(&hay[range.start..end]) {
+            Some(end)
+        } else {
+            None
+        }
+    }
+}
+
+unsafe impl<'p> ReverseConsumer<[u8]> for SignatureSearcher<'p> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[u8]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let len = self.signature.len();
+        if range.start + len <= range.end && self.signature.matches(&hay[(range.end - len)..range.end]) {
+            Some(range.end - len)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'p, H: Haystack<Target = [u8]>> Needle<H> for &'p Signature {
+    type Searcher = SignatureSearcher<'p>;
+    type Consumer = SignatureSearcher<'p>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        SignatureSearcher::new(self)
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        SignatureSearcher::new(self)
+    }
+}
@@ -1,5 +1,6 @@
 use needle::*;
-use haystack::{Hay, Span};
+use compiled::{Compile, Compiled};
+use haystack::{Hay, Haystack, Span};
 use std::cmp::{Ordering, max, min};
 use std::usize;
 use std::ops::Range;
@@ -8,9 +9,9 @@ use std::ops::Range;
 // Two way searcher helpers
 //------------------------------------------------------------------------------
 
-type FastSkipByteset = u64;
+pub(crate) type FastSkipByteset = u64;
 
-trait FastSkipOptimization {
+pub(crate) trait FastSkipOptimization {
     fn byteset_mask(&self) -> FastSkipByteset;
 }
 
@@ -24,6 +25,30 @@ impl FastSkipOptimization for u8 {
     fn byteset_mask(&self) -> FastSkipByteset { 1 << (self & 63) }
 }
 
+/// The elements of a hay as seen by the two-way searcher.
+///
+/// This allows the searcher to run over keys derived from the hay without
+/// collecting them first.
+pub(crate) trait Elements<T> {
+    /// Checks whether the element at `index` is equal to `item`.
+    unsafe fn eq_at(&mut self, index: usize, item: &T) -> bool;
+
+    /// Returns the fast skip mask of the element at `index`.
+    unsafe fn byteset_mask_at(&mut self, index: usize) -> FastSkipByteset;
+}
+
+impl<T: PartialEq> Elements<T> for &[T] {
+    #[inline]
+    unsafe fn eq_at(&mut self, index: usize, item: &T) -> bool {
+        item == self.get_unchecked(index)
+    }
+
+    #[inline]
+    unsafe fn byteset_mask_at(&mut self, index: usize) -> FastSkipByteset {
+        self.get_unchecked(index).byteset_mask()
+    }
+}
+
 trait MaximalSuffix: Sized {
     // Compute the maximal suffix of `&[T]`.
     //
@@ -52,6 +77,10 @@ trait MaximalSuffix: Sized {
     //
     // For long period cases, the resulting period is not exact (it is too short).
     fn reverse_maximal_suffix(arr: &[Self], known_period: usize, order: Ordering) -> usize;
+
+    // Whether the maximal suffixes are computed from a total order. Without
+    // one, they do not give a critical factorization.
+    fn is_ordered() -> bool;
 }
 
 // fallback to naive search for non-Ord slices.
@@ -63,6 +92,10 @@ impl<T: PartialEq> MaximalSuffix for T {
     default fn reverse_maximal_suffix(_: &[Self], _: usize, _: Ordering) -> usize {
         0
     }
+
+    default fn is_ordered() -> bool {
+        false
+    }
 }
 
 impl<T: Ord> MaximalSuffix for T {
@@ -146,6 +179,10 @@ impl<T: Ord> MaximalSuffix for T {
         debug_assert!(period <= known_period);
         left
     }
+
+    fn is_ordered() -> bool {
+        true
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -197,12 +234,52 @@ impl<'p, T: 'p> Clone for TwoWaySearcher<'p, T> {
 
 impl<'p, T: 'p> Copy for TwoWaySearcher<'p, T> {}
 
-impl<'p, T> TwoWaySearcher<'p, T>
-where
-    T: PartialEq + 'p,
-{
+/// The state of a [`TwoWaySearcher`] without the borrowed needle, which lets
+/// a searcher owning its needle keep the preprocessing.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct TwoWayState {
+    crit_pos: usize,
+    crit_pos_back: usize,
+    period: usize,
+    byteset: FastSkipByteset,
+    memory: usize,
+    memory_back: usize,
+}
+
+impl<'p, T: 'p> TwoWaySearcher<'p, T> {
+    #[inline]
+    pub(crate) fn state(&self) -> TwoWayState {
+        TwoWayState {
+            crit_pos: self.crit_pos,
+            crit_pos_back: self.crit_pos_back,
+            period: self.period,
+            byteset: self.byteset,
+            memory: self.memory,
+            memory_back: self.memory_back,
+        }
+    }
+
+    /// Recreates the searcher from its state.
+    ///
+    /// The needle must be equal to the one the state was computed from, since
+    /// the indices in the state are not checked.
     #[inline]
-    fn do_next<P: Period>(&mut self, hay: &[T], range: Range<usize>) -> Option<Range<usize>> {
+    pub(crate) unsafe fn from_state(state: TwoWayState, needle: &'p [T]) -> Self {
+        TwoWaySearcher {
+            crit_pos: state.crit_pos,
+            crit_pos_back: state.crit_pos_back,
+            period: state.period,
+            byteset: state.byteset,
+            needle,
+            memory: state.memory,
+            memory_back: state.memory_back,
+        }
+    }
+}
+
+impl<'p, T: 'p> TwoWaySearcher<'p, T> {
+    #[inline]
+    fn do_next<P: Period, E: Elements<T>>(&mut self, mut hay: E, range: Range<usize>) -> Option<Range<usize>> {
         let needle = self.needle;
 
         let mut position = range.start;
@@ -212,13 +289,16 @@ where
             // are bounded by isize's range.
             let i = position + (needle.len() - 1);
             if i >= range.end {
+                // The next search may start anywhere, so forget the partial match.
+                if !P::IS_LONG_PERIOD {
+                    self.memory = 0;
+                }
                 return None;
             }
-            // let tail_item = &hay[i]; // using get_unchecked here would be slower
-            let tail_item = unsafe { hay.get_unchecked(i) };
+            let tail_mask = unsafe { hay.byteset_mask_at(i) };
 
             // Quickly skip by large portions unrelated to our substring
-            if !self.byteset_contains(tail_item) {
+            if !self.byteset_contains(tail_mask) {
                 position += needle.len();
                 if !P::IS_LONG_PERIOD {
                     self.memory = 0;
@@ -233,7 +313,7 @@ where
                 max(self.crit_pos, self.memory)
             };
             for i in start..needle.len() {
-                if unsafe { needle.get_unchecked(i) != hay.get_unchecked(position + i) } {
+                if unsafe { !hay.eq_at(position + i, needle.get_unchecked(i)) } {
                     position += i - self.crit_pos + 1;
                     if !P::IS_LONG_PERIOD {
                         self.memory = 0;
@@ -245,7 +325,7 @@ where
             // See if the left part of the needle matches
             let start = if P::IS_LONG_PERIOD { 0 } else { self.memory };
             for i in (start..self.crit_pos).rev() {
-                if unsafe { needle.get_unchecked(i) != hay.get_unchecked(position + i) } {
+                if unsafe { !hay.eq_at(position + i, needle.get_unchecked(i)) } {
                     position += self.period;
                     if !P::IS_LONG_PERIOD {
                         self.memory = needle.len() - self.period;
@@ -264,16 +344,24 @@ where
     }
 
     #[inline]
-    pub(crate) fn next(&mut self, hay: &[T], range: Range<usize>) -> Option<Range<usize>> {
+    pub(crate) fn next(&mut self, hay: &[T], range: Range<usize>) -> Option<Range<usize>>
+    where
+        T: PartialEq,
+    {
+        self.next_in(hay, range)
+    }
+
+    #[inline]
+    pub(crate) fn next_in<E: Elements<T>>(&mut self, hay: E, range: Range<usize>) -> Option<Range<usize>> {
         if self.memory != usize::MAX {
-            self.do_next::<ShortPeriod>(hay, range)
+            self.do_next::<ShortPeriod, E>(hay, range)
         } else {
-            self.do_next::<LongPeriod>(hay, range)
+            self.do_next::<LongPeriod, E>(hay, range)
         }
     }
 
     #[inline]
-    fn do_next_back<P: Period>(&mut self, hay: &[T], range: Range<usize>) -> Option<Range<usize>> {
+    fn do_next_back<P: Period, E: Elements<T>>(&mut self, mut hay: E, range: Range<usize>) -> Option<Range<usize>> {
         let needle = self.needle;
         let mut end = range.end;
         'search: loop {
@@ -282,12 +370,16 @@ where
             // but due to slice length limits it can never wrap all the way back
             // into the length of hay.
             if needle.len() + range.start > end {
+                // The next search may end anywhere, so forget the partial match.
+                if !P::IS_LONG_PERIOD {
+                    self.memory_back = needle.len();
+                }
                 return None;
             }
-            let front_item = unsafe { hay.get_unchecked(end.wrapping_sub(needle.len())) };
+            let front_mask = unsafe { hay.byteset_mask_at(end.wrapping_sub(needle.len())) };
 
             // Quickly skip by large portions unrelated to our substring
-            if !self.byteset_contains(front_item) {
+            if !self.byteset_contains(front_mask) {
                 end -= needle.len();
                 if !P::IS_LONG_PERIOD {
                     self.memory_back = needle.len();
@@ -302,7 +394,7 @@ where
                 min(self.crit_pos_back, self.memory_back)
             };
             for i in (0..crit).rev() {
-                if unsafe { needle.get_unchecked(i) != hay.get_unchecked(end - needle.len() + i) } {
+                if unsafe { !hay.eq_at(end - needle.len() + i, needle.get_unchecked(i)) } {
                     end -= self.crit_pos_back - i;
                     if !P::IS_LONG_PERIOD {
                         self.memory_back = needle.len();
@@ -314,7 +406,7 @@ where
             // See if the right part of the needle matches
             let needle_end = if P::IS_LONG_PERIOD { needle.len() } else { self.memory_back };
             for i in self.crit_pos_back..needle_end {
-                if unsafe { needle.get_unchecked(i) != hay.get_unchecked(end - needle.len() + i) } {
+                if unsafe { !hay.eq_at(end - needle.len() + i, needle.get_unchecked(i)) } {
                     end -= self.period;
                     if !P::IS_LONG_PERIOD {
                         self.memory_back = self.period;
@@ -332,16 +424,31 @@ where
     }
 
     #[inline]
-    pub(crate) fn next_back(&mut self, hay: &[T], range: Range<usize>) -> Option<Range<usize>> {
+    pub(crate) fn next_back(&mut self, hay: &[T], range: Range<usize>) -> Option<Range<usize>>
+    where
+        T: PartialEq,
+    {
+        self.next_back_in(hay, range)
+    }
+
+    #[inline]
+    pub(crate) fn next_back_in<E: Elements<T>>(&mut self, hay: E, range: Range<usize>) -> Option<Range<usize>> {
         if self.memory != usize::MAX {
-            self.do_next_back::<ShortPeriod>(hay, range)
+            self.do_next_back::<ShortPeriod, E>(hay, range)
         } else {
-            self.do_next_back::<LongPeriod>(hay, range)
+            self.do_next_back::<LongPeriod, E>(hay, range)
         }
     }
 
     #[inline]
-    pub(crate) fn new(needle: &'p [T]) -> Self {
+    pub(crate) fn new(needle: &'p [T]) -> Self
+    where
+        T: PartialEq,
+    {
+        if !T::is_ordered() {
+            return Self::naive(needle);
+        }
+
         let res_lt = T::maximal_suffix(needle, Ordering::Less);
         let res_gt = T::maximal_suffix(needle, Ordering::Greater);
         let (crit_pos, period) = max(res_lt, res_gt);
@@ -394,13 +501,33 @@ where
         }
     }
 
+    /// Creates a searcher which compares the needle at every position of the
+    /// hay, without relying on any property of the equality of the elements.
+    ///
+    /// The needle must not be empty.
+    #[inline]
+    pub(crate) fn naive(needle: &'p [T]) -> Self {
+        // Without a critical factorization, choose one where every mismatch
+        // shifts the window by exactly one element, which reduces the long
+        // period case to a naive search.
+        Self {
+            crit_pos: needle.len() - 1,
+            crit_pos_back: 1,
+            period: 1,
+            byteset: Self::byteset_create(needle),
+            needle,
+            memory: usize::MAX,
+            memory_back: usize::MAX,
+        }
+    }
+
     #[inline]
     fn byteset_create(needle: &[T]) -> FastSkipByteset {
         needle.iter().fold(0, |a, b| b.byteset_mask() | a)
     }
     #[inline]
-    fn byteset_contains(&self, item: &T) -> bool {
-        (self.byteset & item.byteset_mask()) != 0
+    fn byteset_contains(&self, mask: FastSkipByteset) -> bool {
+        (self.byteset & mask) != 0
     }
 }
 
@@ -587,3 +714,37 @@ impl_needle!(<['p, 'h, T]> &'h [T]);
 impl_needle!(<['p, 'h, T]> &'h mut [T]);
 #[cfg(feature = "std")]
 impl_needle!(<['p, T]> Vec<T>);
+
+impl<'p, T: PartialEq + 'p> Compile for &'p [T] {
+    type Searcher = SliceSearcher<'p, T>;
+    type Consumer = NaiveSearcher<'p, T>;
+
+    #[inline]
+    fn compile_searcher(&self) -> Self::Searcher {
+        SliceSearcher::new(self)
+    }
+
+    #[inline]
+    fn compile_consumer(&self) -> Self::Consumer {
+        NaiveSearcher::new(self)
+    }
+}
+
+impl<'p, T, H> Needle<H> for &Compiled<&'p [T]>
+where
+    T: PartialEq + 'p,
+    H: Haystack<Target = [T]>,
+{
+    type Searcher = SliceSearcher<'p, T>;
+    type Consumer = NaiveSearcher<'p, T>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        self.searcher()
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        self.consumer()
+    }
+}
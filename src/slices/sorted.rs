@@ -0,0 +1,174 @@
+use needle::*;
+use haystack::{Haystack, Span};
+use std::ops::{Bound, Range, RangeBounds};
+
+/// A needle which matches the run of elements equal to a key in a sorted
+/// slice.
+///
+/// The run is located by binary search, so searching takes *O*(log *n*)
+/// time instead of scanning the haystack. All equal elements are returned as
+/// a single match, and a key which does not occur matches nothing.
+///
+/// The haystack must be sorted in ascending order. If it is not, the result
+/// is unspecified (but still a valid range of the haystack).
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::Sorted;
+/// use pattern_3::ext::{find_range, split, matches};
+///
+/// let hay = &[1, 2, 2, 2, 3, 5, 8][..];
+/// assert_eq!(find_range(hay, Sorted(2)), Some(1..4));
+/// assert_eq!(find_range(hay, Sorted(4)), None);
+/// assert_eq!(split(hay, Sorted(3)).collect::<Vec<_>>(), vec![&[1, 2, 2, 2][..], &[5, 8]]);
+/// assert_eq!(matches(hay, Sorted(2)).count(), 1);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Sorted<T>(pub T);
+
+/// `Sorted(key)` is the range `key..=key`.
+impl<T> RangeBounds<T> for Sorted<T> {
+    #[inline]
+    fn start_bound(&self) -> Bound<&T> {
+        Bound::Included(&self.0)
+    }
+
+    #[inline]
+    fn end_bound(&self) -> Bound<&T> {
+        Bound::Included(&self.0)
+    }
+}
+
+/// A needle which matches the run of elements within a range in a sorted
+/// slice.
+///
+/// This is the same as [`Sorted`], except that all elements contained in the
+/// range are matched.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::SortedRange;
+/// use pattern_3::ext::{find_range, trim_start};
+///
+/// let hay = &[1, 2, 2, 3, 5, 8, 13][..];
+/// assert_eq!(find_range(hay, SortedRange(2..6)), Some(1..5));
+/// assert_eq!(find_range(hay, SortedRange(9..)), Some(6..7));
+/// assert_eq!(find_range(hay, SortedRange(6..8)), None);
+/// assert_eq!(trim_start(hay, SortedRange(..=2)), &[3, 5, 8, 13]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SortedRange<R>(pub R);
+
+/// Searcher of [`Sorted`] and [`SortedRange`].
+#[derive(Copy, Clone, Debug)]
+pub struct SortedSearcher<R> {
+    range: R,
+}
+
+impl<R> SortedSearcher<R> {
+    /// Finds the elements of `hay` contained in the range.
+    #[inline]
+    fn run<T: Ord>(&self, hay: &[T]) -> Option<Range<usize>>
+    where
+        R: RangeBounds<T>,
+    {
+        let start = match self.range.start_bound() {
+            Bound::Included(lo) => hay.partition_point(|x| x < lo),
+            Bound::Excluded(lo) => hay.partition_point(|x| x <= lo),
+            Bound::Unbounded => 0,
+        };
+        let end = match self.range.end_bound() {
+            Bound::Included(hi) => hay.partition_point(|x| x <= hi),
+            Bound::Excluded(hi) => hay.partition_point(|x| x < hi),
+            Bound::Unbounded => hay.len(),
+        };
+        if start < end {
+            Some(start..end)
+        } else {
+            None
+        }
+    }
+}
+
+unsafe impl<T: Ord, R: RangeBounds<T>> Searcher<[T]> for SortedSearcher<R> {
+    #[inline]
+    fn search(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let start = range.start;
+        let run = self.run(&hay[range])?;
+        Some((start + run.start)..(start + run.end))
+    }
+}
+
+unsafe impl<T: Ord, R: RangeBounds<T>> ReverseSearcher<[T]> for SortedSearcher<R> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        self.search(span)
+    }
+}
+
+unsafe impl<T: Ord, R: RangeBounds<T>> DoubleEndedSearcher<[T]> for SortedSearcher<R> {}
+
+unsafe impl<T: Ord, R: RangeBounds<T>> Consumer<[T]> for SortedSearcher<R> {
+    #[inline]
+    fn consume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let start = range.start;
+        let run = self.run(&hay[range])?;
+        if run.start == 0 {
+            Some(start + run.end)
+        } else {
+            None
+        }
+    }
+}
+
+unsafe impl<T: Ord, R: RangeBounds<T>> ReverseConsumer<[T]> for SortedSearcher<R> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let (start, len) = (range.start, range.end - range.start);
+        let run = self.run(&hay[range])?;
+        if run.end == len {
+            Some(start + run.start)
+        } else {
+            None
+        }
+    }
+}
+
+unsafe impl<T: Ord, R: RangeBounds<T>> DoubleEndedConsumer<[T]> for SortedSearcher<R> {}
+
+impl<T: Ord, H: Haystack<Target = [T]>> Needle<H> for Sorted<T> {
+    type Searcher = SortedSearcher<Self>;
+    type Consumer = SortedSearcher<Self>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        SortedSearcher { range: self }
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        SortedSearcher { range: self }
+    }
+}
+
+impl<T: Ord, R: RangeBounds<T>, H: Haystack<Target = [T]>> Needle<H> for SortedRange<R> {
+    type Searcher = SortedSearcher<R>;
+    type Consumer = SortedSearcher<R>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        SortedSearcher { range: self.0 }
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        SortedSearcher { range: self.0 }
+    }
+}
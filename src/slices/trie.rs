@@ -0,0 +1,366 @@
+use needle::*;
+use haystack::{Haystack, Span};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Range;
+
+//------------------------------------------------------------------------------
+// Prefix tree
+//------------------------------------------------------------------------------
+
+const ROOT: u32 = 0;
+const NONE: u32 = !0;
+
+#[derive(Clone, Debug)]
+struct Node<T> {
+    /// Children of this node, sorted by the element.
+    children: Vec<(T, u32)>,
+    /// Index of the first pattern which ends at this node.
+    output: u32,
+}
+
+#[derive(Clone, Debug)]
+struct Tree<T> {
+    nodes: Vec<Node<T>>,
+}
+
+impl<T: Ord + Clone> Tree<T> {
+    fn new<'a, I, J>(patterns: I) -> Self
+    where
+        T: 'a,
+        I: IntoIterator<Item = (u32, J)>,
+        J: IntoIterator<Item = &'a T>,
+    {
+        let mut nodes: Vec<Node<T>> = vec![Node { children: Vec::new(), output: NONE }];
+        for (index, pattern) in patterns {
+            let mut node = ROOT as usize;
+            for elem in pattern {
+                node = match nodes[node].children.binary_search_by(|c| c.0.cmp(elem)) {
+                    Ok(i) => nodes[node].children[i].1 as usize,
+                    Err(i) => {
+                        let next = nodes.len();
+                        nodes[node].children.insert(i, (elem.clone(), next as u32));
+                        nodes.push(Node { children: Vec::new(), output: NONE });
+                        next
+                    }
+                };
+            }
+            let output = &mut nodes[node].output;
+            if *output == NONE || index < *output {
+                *output = index;
+            }
+        }
+        Tree { nodes }
+    }
+
+    /// Builds the tree of the reversed patterns stored in this tree.
+    fn reverse(&self) -> Self {
+        let mut patterns = Vec::new();
+        let mut stack = vec![(ROOT, Vec::new())];
+        while let Some((node, path)) = stack.pop() {
+            let n = &self.nodes[node as usize];
+            if n.output != NONE {
+                patterns.push((n.output, path.clone()));
+            }
+            for &(ref elem, next) in &n.children {
+                let mut path = path.clone();
+                path.push(elem);
+                stack.push((next, path));
+            }
+        }
+        Tree::new(patterns.into_iter().map(|(index, pattern)| (index, pattern.into_iter().rev())))
+    }
+}
+
+impl<T: Ord> Tree<T> {
+    #[inline]
+    fn child(&self, node: usize, elem: &T) -> Option<usize> {
+        let children = &self.nodes[node].children;
+        children
+            .binary_search_by(|c| c.0.cmp(elem))
+            .ok()
+            .map(|i| children[i].1 as usize)
+    }
+
+    /// Finds the longest pattern which is a prefix of the elements, returning
+    /// the pattern index and its length.
+    #[inline]
+    fn longest<'a, I>(&self, elems: I) -> Option<(u32, usize)>
+    where
+        T: 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        let mut node = ROOT as usize;
+        let mut longest = None;
+        let mut len = 0;
+        let mut elems = elems.into_iter();
+        loop {
+            let output = self.nodes[node].output;
+            if output != NONE {
+                longest = Some((output, len));
+            }
+            node = match elems.next().and_then(|elem| self.child(node, elem)) {
+                Some(next) => next,
+                None => return longest,
+            };
+            len += 1;
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Searcher
+//------------------------------------------------------------------------------
+
+/// Searcher and consumer of [`&Trie`](Trie).
+///
+/// Besides implementing the searcher and consumer traits, this type also
+/// remembers which pattern was found by the most recent operation, which can
+/// be retrieved using [`.pattern_index()`](TrieSearcher::pattern_index).
+#[derive(Debug)]
+pub struct TrieSearcher<'t, T: 't> {
+    forward: &'t Tree<T>,
+    backward: &'t Tree<T>,
+    allow_empty_match_front: bool,
+    allow_empty_match_back: bool,
+    pattern_index: Option<usize>,
+}
+
+impl<'t, T: 't> Clone for TrieSearcher<'t, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'t, T: 't> Copy for TrieSearcher<'t, T> {}
+
+impl<'t, T: Ord + 't> TrieSearcher<'t, T> {
+    /// Consumes the longest pattern at the start of the range.
+    pub(crate) fn consume_in(&mut self, hay: &[T], range: Range<usize>) -> Option<usize> {
+        let start = range.start;
+        let found = self.forward.longest(&hay[range]);
+        self.pattern_index = found.map(|(index, _)| index as usize);
+        found.map(|(_, len)| start + len)
+    }
+
+    /// Consumes the longest pattern at the end of the range.
+    pub(crate) fn rconsume_in(&mut self, hay: &[T], range: Range<usize>) -> Option<usize> {
+        let end = range.end;
+        let found = self.backward.longest(hay[range].iter().rev());
+        self.pattern_index = found.map(|(index, _)| index as usize);
+        found.map(|(_, len)| end - len)
+    }
+
+    /// Searches the leftmost position where a pattern can be consumed.
+    /// `next_index` is used to step over a codeword.
+    pub(crate) fn search_in<F>(&mut self, hay: &[T], range: Range<usize>, next_index: F) -> Option<Range<usize>>
+    where
+        F: Fn(usize) -> usize,
+    {
+        let mut start = range.start;
+        loop {
+            if let Some(end) = self.consume_in(hay, start..range.end) {
+                if end != range.start || self.allow_empty_match_front {
+                    self.allow_empty_match_front = false;
+                    return Some(start..end);
+                }
+            }
+            if start == range.end {
+                self.pattern_index = None;
+                return None;
+            }
+            start = next_index(start);
+        }
+    }
+
+    /// Searches the rightmost position where a pattern can be consumed
+    /// backwards. `prev_index` is used to step over a codeword.
+    pub(crate) fn rsearch_in<F>(&mut self, hay: &[T], range: Range<usize>, prev_index: F) -> Option<Range<usize>>
+    where
+        F: Fn(usize) -> usize,
+    {
+        let mut end = range.end;
+        loop {
+            if let Some(start) = self.rconsume_in(hay, range.start..end) {
+                if start != range.end || self.allow_empty_match_back {
+                    self.allow_empty_match_back = false;
+                    return Some(start..end);
+                }
+            }
+            if end == range.start {
+                self.pattern_index = None;
+                return None;
+            }
+            end = prev_index(end);
+        }
+    }
+}
+
+impl<'t, T: 't> TrieSearcher<'t, T> {
+    /// Returns the index of the pattern found by the most recent call to
+    /// `search`, `rsearch`, `consume` or `rconsume`.
+    ///
+    /// Returns `None` if nothing is searched yet, or the most recent call
+    /// found nothing.
+    ///
+    /// If the same pattern appears multiple times in the list, the index of
+    /// the first occurrence is returned.
+    #[inline]
+    pub fn pattern_index(&self) -> Option<usize> {
+        self.pattern_index
+    }
+}
+
+unsafe impl<'t, T: Ord + 't> Searcher<[T]> for TrieSearcher<'t, T> {
+    #[inline]
+    fn search(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        self.search_in(hay, range, |i| i + 1)
+    }
+}
+
+unsafe impl<'t, T: Ord + 't> ReverseSearcher<[T]> for TrieSearcher<'t, T> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&[T]>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        self.rsearch_in(hay, range, |i| i - 1)
+    }
+}
+
+unsafe impl<'t, T: Ord + 't> Consumer<[T]> for TrieSearcher<'t, T> {
+    #[inline]
+    fn consume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        self.consume_in(hay, range)
+    }
+}
+
+unsafe impl<'t, T: Ord + 't> ReverseConsumer<[T]> for TrieSearcher<'t, T> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&[T]>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        self.rconsume_in(hay, range)
+    }
+}
+
+//------------------------------------------------------------------------------
+// Needle
+//------------------------------------------------------------------------------
+
+/// A needle which matches the longest of a list of patterns.
+///
+/// The patterns are stored in a prefix tree, built once by [`Trie::new`], so
+/// consuming a pattern takes time proportional to the length of the match,
+/// independent of the number of patterns. The needle is `&Trie`, which
+/// borrows the tree, so a single `Trie` can serve tokenizers and routers
+/// which repeatedly ask "which pattern starts here", using
+/// [`starts_with`](::ext::starts_with), [`trim_start`](::ext::trim_start) or
+/// the consumer directly. Consuming from the end uses a second tree of the
+/// reversed patterns, which is also built by `Trie::new`.
+///
+/// The searcher and consumer report which pattern was found via
+/// [`TrieSearcher::pattern_index`].
+///
+/// Searching tries to consume a pattern at every position, so the leftmost
+/// match is found, preferring the longest pattern there. To search long
+/// haystacks, [`AhoCorasick`](::AhoCorasick) with
+/// [`LeftmostLongest`](::MatchKind::LeftmostLongest) is usually faster.
+///
+/// The type `Trie<T>` is used on `[T]` haystacks with `AsRef<[T]>` patterns,
+/// and `Trie<u8, str>` on `str` haystacks with `AsRef<str>` patterns.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::{Trie, Needle, Consumer, Span};
+/// use pattern_3::ext::{split, starts_with, trim_start};
+///
+/// let operators = ["<", "<=", "<<", "<<=", "="];
+/// let trie = Trie::new(&operators);
+///
+/// let mut consumer = Needle::<&str>::into_consumer(&trie);
+/// assert_eq!(consumer.consume(Span::from("<<= 1")), Some(3));
+/// assert_eq!(consumer.pattern_index(), Some(3));
+/// assert_eq!(consumer.consume(Span::from("<x")), Some(1));
+/// assert_eq!(consumer.pattern_index(), Some(0));
+///
+/// assert!(starts_with("<=x", &trie));
+/// assert_eq!(trim_start("<<=<=x", &trie), "x");
+/// assert_eq!(split("a<=b<<c", &trie).collect::<Vec<_>>(), vec!["a", "b", "c"]);
+/// ```
+pub struct Trie<T, A: ?Sized = [T]> {
+    forward: Tree<T>,
+    backward: Tree<T>,
+    hay: PhantomData<fn(&A)>,
+}
+
+impl<T: Clone, A: ?Sized> Clone for Trie<T, A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Trie {
+            forward: self.forward.clone(),
+            backward: self.backward.clone(),
+            hay: PhantomData,
+        }
+    }
+}
+
+impl<T: fmt::Debug, A: ?Sized> fmt::Debug for Trie<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Trie")
+            .field("forward", &self.forward)
+            .field("backward", &self.backward)
+            .finish()
+    }
+}
+
+impl<T: Ord + Clone, A: ?Sized + AsRef<[T]>> Trie<T, A> {
+    /// Builds a needle from a list of patterns.
+    pub fn new<P: AsRef<A>>(patterns: &[P]) -> Self {
+        let patterns = patterns
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i as u32, AsRef::<[T]>::as_ref(p.as_ref())));
+        let forward = Tree::new(patterns);
+        let backward = forward.reverse();
+        Trie {
+            forward,
+            backward,
+            hay: PhantomData,
+        }
+    }
+}
+
+impl<T, A: ?Sized> Trie<T, A> {
+    #[inline]
+    pub(crate) fn searcher(&self) -> TrieSearcher<'_, T> {
+        TrieSearcher {
+            forward: &self.forward,
+            backward: &self.backward,
+            allow_empty_match_front: true,
+            allow_empty_match_back: true,
+            pattern_index: None,
+        }
+    }
+}
+
+impl<'t, T, H> Needle<H> for &'t Trie<T>
+where
+    T: Ord,
+    H: Haystack<Target = [T]>,
+{
+    type Searcher = TrieSearcher<'t, T>;
+    type Consumer = TrieSearcher<'t, T>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        self.searcher()
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        self.searcher()
+    }
+}
@@ -0,0 +1,78 @@
+use needle::*;
+use haystack::{Hay, Haystack, Span};
+use slices::aho_corasick::{AhoCorasick, AhoCorasickSearcher};
+use std::ops::Range;
+
+// The patterns are valid UTF-8, so every match found in a valid UTF-8 string
+// must start and end on character boundaries.
+
+unsafe impl<'a> Searcher<str> for AhoCorasickSearcher<'a, u8> {
+    #[inline]
+    fn search(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        self.search_in(hay.as_bytes(), range, |i| unsafe { hay.next_index(i) })
+    }
+}
+
+unsafe impl<'a> ReverseSearcher<str> for AhoCorasickSearcher<'a, u8> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        self.rsearch_in(hay.as_bytes(), range, |i| unsafe { hay.prev_index(i) })
+    }
+}
+
+unsafe impl<'a> Consumer<str> for AhoCorasickSearcher<'a, u8> {
+    #[inline]
+    fn consume(&mut self, span: Span<&str>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        self.consume_in(hay.as_bytes(), range)
+    }
+}
+
+unsafe impl<'a> ReverseConsumer<str> for AhoCorasickSearcher<'a, u8> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&str>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        self.rconsume_in(hay.as_bytes(), range)
+    }
+}
+
+impl<'c, H: Haystack<Target = str>> Needle<H> for &'c AhoCorasick<u8, str> {
+    type Searcher = AhoCorasickSearcher<'c, u8>;
+    type Consumer = AhoCorasickSearcher<'c, u8>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        self.searcher()
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        self.searcher()
+    }
+}
+
+macro_rules! impl_needle {
+    ($ty:ty) => {
+        impl<'h, 'p, 'q> Needle<$ty> for &'p [&'q str] {
+            type Searcher = AhoCorasickSearcher<'p, u8>;
+            type Consumer = AhoCorasickSearcher<'p, u8>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                let patterns = self.iter().map(|p| p.as_bytes());
+                AhoCorasickSearcher::new(patterns, MatchKind::LeftmostFirst)
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                let patterns = self.iter().map(|p| p.as_bytes());
+                AhoCorasickSearcher::new(patterns, MatchKind::LeftmostFirst)
+            }
+        }
+    }
+}
+
+impl_needle!(&'h str);
+impl_needle!(&'h mut str);
@@ -0,0 +1,68 @@
+use needle::*;
+use haystack::Span;
+use slices::ascii::{AsciiCaseInsensitive, AsciiCaseless};
+use slices::slice::{TwoWaySearcher, NaiveSearcher, SliceSearcher};
+use std::ops::Range;
+
+// ASCII case folding only maps ASCII bytes to ASCII bytes, so a match of a
+// valid UTF-8 needle always lies on character boundaries.
+
+unsafe impl<'p> Searcher<str> for TwoWaySearcher<'p, AsciiCaseless> {
+    #[inline]
+    fn search(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        self.search(AsciiCaseless::from_span(span.as_bytes()))
+    }
+}
+
+unsafe impl<'p> ReverseSearcher<str> for TwoWaySearcher<'p, AsciiCaseless> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        self.rsearch(AsciiCaseless::from_span(span.as_bytes()))
+    }
+}
+
+unsafe impl<'p> Consumer<str> for NaiveSearcher<'p, AsciiCaseless> {
+    #[inline]
+    fn consume(&mut self, span: Span<&str>) -> Option<usize> {
+        self.consume(AsciiCaseless::from_span(span.as_bytes()))
+    }
+
+    #[inline]
+    fn trim_start(&mut self, hay: &str) -> usize {
+        self.trim_start(AsciiCaseless::from_bytes(hay.as_bytes()))
+    }
+}
+
+unsafe impl<'p> ReverseConsumer<str> for NaiveSearcher<'p, AsciiCaseless> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&str>) -> Option<usize> {
+        self.rconsume(AsciiCaseless::from_span(span.as_bytes()))
+    }
+
+    #[inline]
+    fn trim_end(&mut self, hay: &str) -> usize {
+        self.trim_end(AsciiCaseless::from_bytes(hay.as_bytes()))
+    }
+}
+
+macro_rules! impl_needle {
+    ($ty:ty) => {
+        impl<'h, 'p> Needle<$ty> for AsciiCaseInsensitive<'p> {
+            type Searcher = SliceSearcher<'p, AsciiCaseless>;
+            type Consumer = NaiveSearcher<'p, AsciiCaseless>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                SliceSearcher::new(self.as_caseless())
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                NaiveSearcher::new(self.as_caseless())
+            }
+        }
+    }
+}
+
+impl_needle!(&'h str);
+impl_needle!(&'h mut str);
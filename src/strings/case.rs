@@ -0,0 +1,174 @@
+use needle::*;
+use haystack::{Haystack, Span};
+use std::ops::Range;
+use super::case_folding::CASE_FOLDING;
+
+/// Maps a character to the representative of its case-insensitive class.
+///
+/// This follows the simple case folding of Unicode (the `C` and `S` mappings
+/// of `CaseFolding.txt`), where every character is folded to exactly one
+/// character. Mappings which expand to multiple characters (e.g. `ß` to `ss`)
+/// and the Turkic mappings of `İ` and `ı` are not applied.
+#[inline]
+fn fold(c: char) -> char {
+    if c.is_ascii() {
+        return c.to_ascii_lowercase();
+    }
+    match CASE_FOLDING.binary_search_by_key(&c, |&(from, _)| from) {
+        Ok(i) => CASE_FOLDING[i].1,
+        Err(_) => c,
+    }
+}
+
+/// Checks whether `hay` starts with `needle` ignoring case, returns the length
+/// of the matched prefix of `hay`.
+#[inline]
+fn match_prefix(hay: &str, needle: &str) -> Option<usize> {
+    let mut chars = hay.chars();
+    for n in needle.chars() {
+        if fold(chars.next()?) != fold(n) {
+            return None;
+        }
+    }
+    Some(hay.len() - chars.as_str().len())
+}
+
+/// Checks whether `hay` ends with `needle` ignoring case, returns the start
+/// index of the matched suffix of `hay`.
+#[inline]
+fn match_suffix(hay: &str, needle: &str) -> Option<usize> {
+    let mut chars = hay.chars();
+    for n in needle.chars().rev() {
+        if fold(chars.next_back()?) != fold(n) {
+            return None;
+        }
+    }
+    Some(chars.as_str().len())
+}
+
+/// Searcher of [`CaseInsensitive`].
+#[derive(Clone, Debug)]
+pub struct CaseInsensitiveSearcher<'p> {
+    needle: &'p str,
+    empty: EmptySearcher,
+}
+
+impl<'p> CaseInsensitiveSearcher<'p> {
+    #[inline]
+    fn new(needle: &'p str) -> Self {
+        CaseInsensitiveSearcher {
+            needle,
+            empty: EmptySearcher::default(),
+        }
+    }
+}
+
+unsafe impl<'p> Searcher<str> for CaseInsensitiveSearcher<'p> {
+    #[inline]
+    fn search(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        let first = match self.needle.chars().next() {
+            Some(c) => fold(c),
+            None => return self.empty.search(span),
+        };
+        let (hay, range) = span.into_parts();
+        let st = range.start;
+        let h = &hay[range];
+        for (i, c) in h.char_indices() {
+            if fold(c) == first {
+                if let Some(len) = match_prefix(&h[i..], self.needle) {
+                    return Some((st + i)..(st + i + len));
+                }
+            }
+        }
+        None
+    }
+}
+
+unsafe impl<'p> ReverseSearcher<str> for CaseInsensitiveSearcher<'p> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        let last = match self.needle.chars().next_back() {
+            Some(c) => fold(c),
+            None => return self.empty.rsearch(span),
+        };
+        let (hay, range) = span.into_parts();
+        let st = range.start;
+        let h = &hay[range];
+        for (i, c) in h.char_indices().rev() {
+            if fold(c) == last {
+                let end = i + c.len_utf8();
+                if let Some(start) = match_suffix(&h[..end], self.needle) {
+                    return Some((st + start)..(st + end));
+                }
+            }
+        }
+        None
+    }
+}
+
+unsafe impl<'p> Consumer<str> for CaseInsensitiveSearcher<'p> {
+    #[inline]
+    fn consume(&mut self, span: Span<&str>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let len = match_prefix(&hay[range.clone()], self.needle)?;
+        Some(range.start + len)
+    }
+}
+
+unsafe impl<'p> ReverseConsumer<str> for CaseInsensitiveSearcher<'p> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&str>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let start = match_suffix(&hay[range.clone()], self.needle)?;
+        Some(range.start + start)
+    }
+}
+
+/// A needle which matches a substring ignoring case, using Unicode simple case
+/// folding.
+///
+/// Every character of the haystack and needle is folded to exactly one
+/// character before comparison. This allows matching case variants in all
+/// bicameral scripts, e.g. `"ΣΟΦΙΑ"` matches `"σοφια"` and `"σοφιας"` matches
+/// `"ΣΟΦΙΑΣ"`. Since the folding is per-character, expansions like `ß` to
+/// `ss` are not considered, although `ß` and `ẞ` match each other.
+///
+/// The matched range may have a different length from the needle (e.g. the
+/// Kelvin sign `K` is 3 bytes long, but matches the 1-byte `k`), and always
+/// refers to the original haystack.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::CaseInsensitive;
+/// use pattern_3::ext::{find, match_ranges, split, trim_start};
+///
+/// assert_eq!(
+///     match_ranges("Привет, ПРИВЕТ!", CaseInsensitive("привет")).collect::<Vec<_>>(),
+///     vec![(0..12, "Привет"), (14..26, "ПРИВЕТ")]
+/// );
+/// assert_eq!(find("STRAẞE", CaseInsensitive("straße")), Some(0));
+/// assert_eq!(
+///     split("aXbxc", CaseInsensitive("x")).collect::<Vec<_>>(),
+///     vec!["a", "b", "c"]
+/// );
+/// assert_eq!(trim_start("ΣσςΣa", CaseInsensitive("σ")), "a");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct CaseInsensitive<'p>(pub &'p str);
+
+impl<'p, H: Haystack<Target = str>> Needle<H> for CaseInsensitive<'p> {
+    type Searcher = CaseInsensitiveSearcher<'p>;
+    type Consumer = CaseInsensitiveSearcher<'p>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        CaseInsensitiveSearcher::new(self.0)
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        CaseInsensitiveSearcher::new(self.0)
+    }
+}
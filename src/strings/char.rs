@@ -27,7 +27,7 @@ impl CharSearcher {
     }
 
     #[inline]
-    fn new(c: char) -> Self {
+    pub(crate) fn new(c: char) -> Self {
         let mut utf8_encoded = [0u8; 4];
         let utf8_size = c.encode_utf8(&mut utf8_encoded).len();
         CharSearcher {
@@ -0,0 +1,36 @@
+use needle::*;
+use haystack::{Haystack, Span};
+use slices::constant_time::{ConstantTime, ConstantTimeConsumer};
+use slices::slice::SliceSearcher;
+
+// The consumer is only created from a `&str` needle for `str` haystacks, so
+// a match always ends on a character boundary.
+
+unsafe impl<'p> Consumer<str> for ConstantTimeConsumer<'p> {
+    #[inline]
+    fn consume(&mut self, span: Span<&str>) -> Option<usize> {
+        self.consume(span.as_bytes())
+    }
+}
+
+unsafe impl<'p> ReverseConsumer<str> for ConstantTimeConsumer<'p> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&str>) -> Option<usize> {
+        self.rconsume(span.as_bytes())
+    }
+}
+
+impl<'p, H: Haystack<Target = str>> Needle<H> for ConstantTime<&'p str> {
+    type Searcher = SliceSearcher<'p, u8>;
+    type Consumer = ConstantTimeConsumer<'p>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        SliceSearcher::new(self.0.as_bytes())
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        ConstantTimeConsumer(self.0.as_bytes())
+    }
+}
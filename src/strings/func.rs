@@ -31,6 +31,13 @@ pub struct MultiCharSearcher<F> {
     predicate: F,
 }
 
+impl<F> MultiCharSearcher<F> {
+    #[inline]
+    pub(crate) fn new(predicate: F) -> Self {
+        MultiCharSearcher { predicate }
+    }
+}
+
 unsafe impl<F: FnMut(char) -> bool> Searcher<str> for MultiCharSearcher<F> {
     #[inline]
     fn search(&mut self, span: Span<&str>) -> Option<Range<usize>> {
@@ -59,6 +59,24 @@ unsafe impl<'h> Haystack for &'h mut str {
     }
 }
 
-mod char;
-mod func;
+pub(crate) mod char;
+pub(crate) mod func;
 mod str;
+mod ascii;
+mod constant_time;
+mod predicates;
+pub(crate) mod case;
+mod case_folding;
+mod range;
+#[cfg(feature = "std")]
+pub(crate) mod set;
+#[cfg(feature = "std")]
+mod aho_corasick;
+#[cfg(feature = "std")]
+mod trie;
+#[cfg(feature = "std")]
+mod owned;
+#[cfg(all(feature = "std", feature = "unicode-normalization"))]
+pub(crate) mod normalization;
+#[cfg(all(feature = "std", feature = "regex"))]
+mod regex;
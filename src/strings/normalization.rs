@@ -0,0 +1,254 @@
+use needle::*;
+use haystack::{Haystack, Span};
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::{canonical_combining_class, decompose_canonical};
+use std::ops::Range;
+
+/// Checks whether the full canonical decomposition of `c` starts with a
+/// character of canonical combining class 0.
+///
+/// A combining sequence always begins before such a character, so it is never
+/// reordered with anything preceding it during normalization.
+#[inline]
+fn is_starter(c: char) -> bool {
+    if c < '\u{300}' {
+        return true;
+    }
+    let mut first = None;
+    decompose_canonical(c, |d| if first.is_none() { first = Some(d) });
+    match first {
+        Some(d) => canonical_combining_class(d) == 0,
+        None => true,
+    }
+}
+
+/// Checks whether `index` does not split a combining sequence of `hay`.
+#[inline]
+fn is_boundary(hay: &str, index: usize) -> bool {
+    match hay[index..].chars().next() {
+        Some(c) => index == 0 || is_starter(c),
+        None => true,
+    }
+}
+
+/// Returns the first combining sequence boundary after `index`.
+#[inline]
+fn next_boundary(hay: &str, index: usize) -> usize {
+    let mut chars = hay[index..].char_indices();
+    chars.next();
+    for (i, c) in chars {
+        if is_starter(c) {
+            return index + i;
+        }
+    }
+    hay.len()
+}
+
+/// Returns the last combining sequence boundary before `index`.
+#[inline]
+fn prev_boundary(hay: &str, index: usize) -> usize {
+    for (i, c) in hay[..index].char_indices().rev() {
+        if i == 0 || is_starter(c) {
+            return i;
+        }
+    }
+    0
+}
+
+/// Searcher of [`NormalizationInsensitive`].
+#[derive(Clone, Debug)]
+pub struct NormalizationInsensitiveSearcher {
+    /// The canonical decomposition (NFD) of the needle.
+    needle: Vec<char>,
+    /// Buffer holding the decomposition of a single combining sequence, used
+    /// when matching backwards.
+    buffer: Vec<char>,
+    consumed_start: bool,
+    consumed_end: bool,
+}
+
+impl NormalizationInsensitiveSearcher {
+    #[inline]
+    fn new(needle: &str) -> Self {
+        NormalizationInsensitiveSearcher {
+            needle: needle.nfd().collect(),
+            buffer: Vec::new(),
+            consumed_start: false,
+            consumed_end: false,
+        }
+    }
+
+    /// Matches the needle against the combining sequences starting at the
+    /// boundary `start`, returns the end of the match.
+    fn match_prefix(&self, hay: &str, start: usize, end: usize) -> Option<usize> {
+        let mut needle = self.needle.iter();
+        let mut pos = start;
+        while !needle.as_slice().is_empty() {
+            if pos == end {
+                return None;
+            }
+            let seq_end = next_boundary(hay, pos);
+            if seq_end > end {
+                return None;
+            }
+            for c in hay[pos..seq_end].nfd() {
+                if needle.next() != Some(&c) {
+                    return None;
+                }
+            }
+            pos = seq_end;
+        }
+        Some(pos)
+    }
+
+    /// Matches the needle against the combining sequences ending at the
+    /// boundary `end`, returns the start of the match.
+    fn match_suffix(&mut self, hay: &str, start: usize, end: usize) -> Option<usize> {
+        let mut remaining = self.needle.len();
+        let mut pos = end;
+        while remaining != 0 {
+            if pos == start {
+                return None;
+            }
+            let seq_start = prev_boundary(hay, pos);
+            if seq_start < start {
+                return None;
+            }
+            self.buffer.clear();
+            self.buffer.extend(hay[seq_start..pos].nfd());
+            let len = self.buffer.len();
+            if len > remaining || self.needle[(remaining - len)..remaining] != *self.buffer {
+                return None;
+            }
+            remaining -= len;
+            pos = seq_start;
+        }
+        Some(pos)
+    }
+}
+
+unsafe impl Searcher<str> for NormalizationInsensitiveSearcher {
+    fn search(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let mut pos = range.start;
+        if self.needle.is_empty() {
+            if self.consumed_start || !is_boundary(hay, pos) {
+                if pos == range.end {
+                    return None;
+                }
+                pos = next_boundary(hay, pos);
+            }
+            self.consumed_start = true;
+            return if pos <= range.end { Some(pos..pos) } else { None };
+        }
+        if !is_boundary(hay, pos) {
+            pos = next_boundary(hay, pos);
+        }
+        while pos < range.end {
+            if let Some(end) = self.match_prefix(hay, pos, range.end) {
+                return Some(pos..end);
+            }
+            pos = next_boundary(hay, pos);
+        }
+        None
+    }
+}
+
+unsafe impl ReverseSearcher<str> for NormalizationInsensitiveSearcher {
+    fn rsearch(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let mut pos = range.end;
+        if self.needle.is_empty() {
+            if self.consumed_end || !is_boundary(hay, pos) {
+                if pos == range.start {
+                    return None;
+                }
+                pos = prev_boundary(hay, pos);
+            }
+            self.consumed_end = true;
+            return if pos >= range.start { Some(pos..pos) } else { None };
+        }
+        if !is_boundary(hay, pos) {
+            pos = prev_boundary(hay, pos);
+        }
+        while pos > range.start {
+            if let Some(start) = self.match_suffix(hay, range.start, pos) {
+                return Some(start..pos);
+            }
+            pos = prev_boundary(hay, pos);
+        }
+        None
+    }
+}
+
+unsafe impl Consumer<str> for NormalizationInsensitiveSearcher {
+    #[inline]
+    fn consume(&mut self, span: Span<&str>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        if !is_boundary(hay, range.start) {
+            return None;
+        }
+        self.match_prefix(hay, range.start, range.end)
+    }
+}
+
+unsafe impl ReverseConsumer<str> for NormalizationInsensitiveSearcher {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&str>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        if !is_boundary(hay, range.end) {
+            return None;
+        }
+        self.match_suffix(hay, range.start, range.end)
+    }
+}
+
+/// A needle which matches a substring canonically equivalent to the needle.
+///
+/// Both the needle and the haystack are compared in their canonical
+/// decomposition (NFD), so a precomposed `"é"` (U+00E9) matches `"e\u{301}"`,
+/// and combining marks may appear in any canonically equivalent order.
+/// Neither side needs to be normalized beforehand, and the matched ranges
+/// refer to the original haystack.
+///
+/// A match always starts and ends on a boundary which does not split a
+/// combining sequence, so `"e"` does not match the beginning of `"e\u{301}"`.
+///
+/// This needle is only available with the `unicode-normalization` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::NormalizationInsensitive;
+/// use pattern_3::ext::{find, match_ranges};
+///
+/// let hay = "caf\u{e9} / cafe\u{301} / cafe";
+/// assert_eq!(
+///     match_ranges(hay, NormalizationInsensitive("café")).collect::<Vec<_>>(),
+///     vec![(0..5, "caf\u{e9}"), (8..14, "cafe\u{301}")]
+/// );
+///
+/// // Combining marks are compared in canonical order.
+/// assert_eq!(find("q\u{307}\u{323}", NormalizationInsensitive("q\u{323}\u{307}")), Some(0));
+///
+/// // A combining sequence is never split.
+/// assert_eq!(find("e\u{301}", NormalizationInsensitive("e")), None);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct NormalizationInsensitive<'p>(pub &'p str);
+
+impl<'p, H: Haystack<Target = str>> Needle<H> for NormalizationInsensitive<'p> {
+    type Searcher = NormalizationInsensitiveSearcher;
+    type Consumer = NormalizationInsensitiveSearcher;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        NormalizationInsensitiveSearcher::new(self.0)
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        NormalizationInsensitiveSearcher::new(self.0)
+    }
+}
@@ -0,0 +1,79 @@
+use needle::*;
+use haystack::{Haystack, Span};
+use slices::owned::{run_two_way, Kind, OwnedNaiveSearcher, OwnedSliceSearcher};
+use slices::slice::NaiveSearcher;
+use std::ops::{Deref, Range};
+use std::sync::Arc;
+
+unsafe impl<N: Deref<Target = str>> Searcher<str> for OwnedSliceSearcher<N> {
+    #[inline]
+    fn search(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        match self.kind {
+            Kind::TwoWay(ref mut state) => {
+                let (hay, range) = span.into_parts();
+                unsafe { run_two_way(state, self.needle.as_bytes(), |s| s.next(hay.as_bytes(), range)) }
+            }
+            Kind::Empty(ref mut searcher) => searcher.search(span),
+        }
+    }
+}
+
+unsafe impl<N: Deref<Target = str>> ReverseSearcher<str> for OwnedSliceSearcher<N> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        match self.kind {
+            Kind::TwoWay(ref mut state) => {
+                let (hay, range) = span.into_parts();
+                unsafe { run_two_way(state, self.needle.as_bytes(), |s| s.next_back(hay.as_bytes(), range)) }
+            }
+            Kind::Empty(ref mut searcher) => searcher.rsearch(span),
+        }
+    }
+}
+
+unsafe impl<N: Deref<Target = str>> Consumer<str> for OwnedNaiveSearcher<N> {
+    #[inline]
+    fn consume(&mut self, span: Span<&str>) -> Option<usize> {
+        NaiveSearcher::new(self.0.as_bytes()).consume(span.as_bytes())
+    }
+
+    #[inline]
+    fn trim_start(&mut self, hay: &str) -> usize {
+        NaiveSearcher::new(self.0.as_bytes()).trim_start(hay.as_bytes())
+    }
+}
+
+unsafe impl<N: Deref<Target = str>> ReverseConsumer<str> for OwnedNaiveSearcher<N> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&str>) -> Option<usize> {
+        NaiveSearcher::new(self.0.as_bytes()).rconsume(span.as_bytes())
+    }
+
+    #[inline]
+    fn trim_end(&mut self, hay: &str) -> usize {
+        NaiveSearcher::new(self.0.as_bytes()).trim_end(hay.as_bytes())
+    }
+}
+
+macro_rules! impl_needle {
+    ($ty:ty) => {
+        impl<H: Haystack<Target = str>> Needle<H> for $ty {
+            type Searcher = OwnedSliceSearcher<Self>;
+            type Consumer = OwnedNaiveSearcher<Self>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                OwnedSliceSearcher::new(self)
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                OwnedNaiveSearcher(self)
+            }
+        }
+    }
+}
+
+impl_needle!(String);
+impl_needle!(Box<str>);
+impl_needle!(Arc<str>);
@@ -0,0 +1,104 @@
+use needle::*;
+use haystack::Span;
+use slices::predicates::{Predicates, PredicatesSearcher};
+use std::ops::Range;
+
+/// Returns the length of the prefix of `s` matched by the predicates.
+#[inline]
+fn consume_chars<P: Fn(char) -> bool>(predicates: &[P], s: &str) -> Option<usize> {
+    let mut chars = s.chars();
+    for p in predicates {
+        match chars.next() {
+            Some(c) if p(c) => {}
+            _ => return None,
+        }
+    }
+    Some(s.len() - chars.as_str().len())
+}
+
+/// Returns the start of the suffix of `s` matched by the predicates.
+#[inline]
+fn rconsume_chars<P: Fn(char) -> bool>(predicates: &[P], s: &str) -> Option<usize> {
+    let mut chars = s.chars();
+    for p in predicates.iter().rev() {
+        match chars.next_back() {
+            Some(c) if p(c) => {}
+            _ => return None,
+        }
+    }
+    Some(chars.as_str().len())
+}
+
+unsafe impl<'p, P: Fn(char) -> bool> Searcher<str> for PredicatesSearcher<'p, P> {
+    #[inline]
+    fn search(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        if self.predicates.is_empty() {
+            return self.empty.search(span);
+        }
+        let (hay, range) = span.into_parts();
+        let st = range.start;
+        let h = &hay[range];
+        h.char_indices()
+            .find_map(|(i, _)| consume_chars(self.predicates, &h[i..]).map(|len| (st + i)..(st + i + len)))
+    }
+}
+
+unsafe impl<'p, P: Fn(char) -> bool> ReverseSearcher<str> for PredicatesSearcher<'p, P> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        if self.predicates.is_empty() {
+            return self.empty.rsearch(span);
+        }
+        let (hay, range) = span.into_parts();
+        let st = range.start;
+        let h = &hay[range];
+        let mut chars = h.chars();
+        loop {
+            let rest = chars.as_str();
+            if let Some(start) = rconsume_chars(self.predicates, rest) {
+                return Some((st + start)..(st + rest.len()));
+            }
+            chars.next_back()?;
+        }
+    }
+}
+
+unsafe impl<'p, P: Fn(char) -> bool> Consumer<str> for PredicatesSearcher<'p, P> {
+    #[inline]
+    fn consume(&mut self, span: Span<&str>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let start = range.start;
+        consume_chars(self.predicates, &hay[range]).map(|len| start + len)
+    }
+}
+
+unsafe impl<'p, P: Fn(char) -> bool> ReverseConsumer<str> for PredicatesSearcher<'p, P> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&str>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let start = range.start;
+        rconsume_chars(self.predicates, &hay[range]).map(|i| start + i)
+    }
+}
+
+macro_rules! impl_needle {
+    ($ty:ty) => {
+        impl<'p, 'h, P: Fn(char) -> bool> Needle<$ty> for Predicates<'p, P> {
+            type Searcher = PredicatesSearcher<'p, P>;
+            type Consumer = PredicatesSearcher<'p, P>;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                PredicatesSearcher::new(self.0)
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                PredicatesSearcher::new(self.0)
+            }
+        }
+    }
+}
+
+impl_needle!(&'h str);
+impl_needle!(&'h mut str);
@@ -0,0 +1,147 @@
+use needle::*;
+use haystack::Span;
+use slices::func::ElemSearcher;
+use strings::func::MultiCharSearcher;
+use std::ops::{Range, RangeInclusive};
+
+/// Searcher of a character range.
+///
+/// The range is stored as the half-open interval `start..end` of Unicode
+/// scalar values. If the range only covers ASCII characters, the haystack is
+/// scanned byte-wise.
+#[derive(Copy, Clone, Debug)]
+pub struct CharRangeSearcher {
+    start: u32,
+    end: u32,
+}
+
+impl CharRangeSearcher {
+    #[inline]
+    fn contains(self, c: u32) -> bool {
+        self.start <= c && c < self.end
+    }
+
+    #[inline]
+    fn is_ascii(self) -> bool {
+        self.end <= 0x80
+    }
+
+    #[inline]
+    fn chars(self) -> MultiCharSearcher<impl FnMut(char) -> bool> {
+        MultiCharSearcher::new(move |c| self.contains(c as u32))
+    }
+
+    #[inline]
+    fn bytes(self) -> ElemSearcher<impl FnMut(&u8) -> bool> {
+        ElemSearcher::new(move |b: &u8| self.contains(u32::from(*b)))
+    }
+}
+
+unsafe impl Searcher<str> for CharRangeSearcher {
+    #[inline]
+    fn search(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        if self.is_ascii() {
+            self.bytes().search(span.as_bytes())
+        } else {
+            self.chars().search(span)
+        }
+    }
+}
+
+unsafe impl ReverseSearcher<str> for CharRangeSearcher {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        if self.is_ascii() {
+            self.bytes().rsearch(span.as_bytes())
+        } else {
+            self.chars().rsearch(span)
+        }
+    }
+}
+
+unsafe impl Consumer<str> for CharRangeSearcher {
+    #[inline]
+    fn consume(&mut self, span: Span<&str>) -> Option<usize> {
+        if self.is_ascii() {
+            self.bytes().consume(span.as_bytes())
+        } else {
+            self.chars().consume(span)
+        }
+    }
+
+    #[inline]
+    fn trim_start(&mut self, hay: &str) -> usize {
+        if self.is_ascii() {
+            self.bytes().trim_start(hay.as_bytes())
+        } else {
+            self.chars().trim_start(hay)
+        }
+    }
+}
+
+unsafe impl ReverseConsumer<str> for CharRangeSearcher {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&str>) -> Option<usize> {
+        if self.is_ascii() {
+            self.bytes().rconsume(span.as_bytes())
+        } else {
+            self.chars().rconsume(span)
+        }
+    }
+
+    #[inline]
+    fn trim_end(&mut self, hay: &str) -> usize {
+        if self.is_ascii() {
+            self.bytes().trim_end(hay.as_bytes())
+        } else {
+            self.chars().trim_end(hay)
+        }
+    }
+}
+
+unsafe impl DoubleEndedSearcher<str> for CharRangeSearcher {}
+unsafe impl DoubleEndedConsumer<str> for CharRangeSearcher {}
+
+macro_rules! impl_needle {
+    ($ty:ty) => {
+        impl<'h> Needle<$ty> for Range<char> {
+            type Searcher = CharRangeSearcher;
+            type Consumer = CharRangeSearcher;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                CharRangeSearcher {
+                    start: self.start as u32,
+                    end: self.end as u32,
+                }
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                Needle::<$ty>::into_searcher(self)
+            }
+        }
+
+        impl<'h> Needle<$ty> for RangeInclusive<char> {
+            type Searcher = CharRangeSearcher;
+            type Consumer = CharRangeSearcher;
+
+            #[inline]
+            fn into_searcher(self) -> Self::Searcher {
+                let (start, end) = self.into_inner();
+                CharRangeSearcher {
+                    start: start as u32,
+                    end: end as u32 + 1,
+                }
+            }
+
+            #[inline]
+            fn into_consumer(self) -> Self::Consumer {
+                Needle::<$ty>::into_searcher(self)
+            }
+        }
+    }
+}
+
+impl_needle!(&'h str);
+impl_needle!(&'h mut str);
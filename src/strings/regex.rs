@@ -0,0 +1,55 @@
+use needle::*;
+use haystack::{Hay, Haystack, Span};
+use slices::regex::RegexSearcher;
+use regex::Regex;
+use std::ops::Range;
+
+// The regular expression always matches valid UTF-8, so every match found in
+// a string must start and end on character boundaries.
+
+#[inline]
+fn find_at(regex: &Regex, hay: &str, start: usize) -> Option<Range<usize>> {
+    regex.find_at(hay, start).map(|m| m.start()..m.end())
+}
+
+unsafe impl<'p> Searcher<str> for RegexSearcher<'p, Regex> {
+    #[inline]
+    fn search(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let (regex, truncated) = (self.regex(), &hay[..range.end]);
+        self.search_in(range, |start| find_at(regex, truncated, start), |i| unsafe { hay.next_index(i) })
+    }
+}
+
+unsafe impl<'p> ReverseSearcher<str> for RegexSearcher<'p, Regex> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        let (regex, truncated) = (self.regex(), &hay[..range.end]);
+        self.rsearch_in(range, |start| find_at(regex, truncated, start), |i| unsafe { hay.next_index(i) })
+    }
+}
+
+unsafe impl<'p> Consumer<str> for RegexSearcher<'p, Regex> {
+    #[inline]
+    fn consume(&mut self, span: Span<&str>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        let (regex, hay) = (self.regex(), &hay[..range.end]);
+        self.consume_in(range, |start| find_at(regex, hay, start))
+    }
+}
+
+impl<'p, H: Haystack<Target = str>> Needle<H> for &'p Regex {
+    type Searcher = RegexSearcher<'p, Regex>;
+    type Consumer = RegexSearcher<'p, Regex>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        RegexSearcher::new(self)
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        RegexSearcher::new(self)
+    }
+}
@@ -0,0 +1,200 @@
+use needle::*;
+use compiled::{Compile, Compiled};
+use haystack::{Haystack, Span};
+use slices::set::Bytes;
+use strings::func::MultiCharSearcher;
+use std::ops::Range;
+
+/// A needle which matches any single character in a set.
+///
+/// The set is preprocessed on construction. When all members are ASCII, the
+/// string is searched byte-wise: sets of one to three characters use `memchr`,
+/// `memchr2` and `memchr3`, while larger sets are looked up in a 256-bit
+/// bitmap. Non-ASCII members are kept in a sorted list and found by binary
+/// search.
+///
+/// Compared with the `&[char]` needle, this is preferred when the set is
+/// large or is reused for many searches.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate pattern_3;
+/// use pattern_3::CharSet;
+/// use pattern_3::ext::{split, trim_end};
+///
+/// let whitespace = CharSet::new(&[' ', '\t', '\n']);
+/// assert_eq!(
+///     split("a b\tc\nd", &whitespace).collect::<Vec<_>>(),
+///     vec!["a", "b", "c", "d"]
+/// );
+///
+/// let punctuation = CharSet::new(&['!', '?', '。', '！', '？']);
+/// assert_eq!(trim_end("你好！？", &punctuation), "你好");
+/// ```
+#[derive(Clone, Debug)]
+pub struct CharSet {
+    ascii: Option<Bytes>,
+    /// Sorted and deduplicated list of non-ASCII members.
+    others: Vec<char>,
+}
+
+impl CharSet {
+    /// Creates a set containing the given characters.
+    ///
+    /// Duplicated characters are ignored.
+    pub fn new(chars: &[char]) -> Self {
+        let ascii = chars.iter().filter(|c| c.is_ascii()).map(|c| *c as u8).collect::<Vec<_>>();
+        let mut others = chars.iter().filter(|c| !c.is_ascii()).cloned().collect::<Vec<_>>();
+        others.sort_unstable();
+        others.dedup();
+        CharSet {
+            ascii: if ascii.is_empty() { None } else { Some(Bytes::new(&ascii)) },
+            others,
+        }
+    }
+
+    /// Checks whether the character is a member of this set.
+    #[inline]
+    pub fn contains(&self, c: char) -> bool {
+        if c.is_ascii() {
+            match self.ascii {
+                Some(ascii) => ascii.contains(c as u8),
+                None => false,
+            }
+        } else {
+            self.others.binary_search(&c).is_ok()
+        }
+    }
+}
+
+/// Searcher of [`CharSet`].
+#[derive(Copy, Clone, Debug)]
+pub struct CharSetSearcher<'p> {
+    set: &'p CharSet,
+}
+
+impl<'p> CharSetSearcher<'p> {
+    /// Returns the byte set if all members are ASCII, in which case the
+    /// haystack can be searched byte-wise.
+    #[inline]
+    fn ascii_only(&self) -> Option<Option<Bytes>> {
+        if self.set.others.is_empty() {
+            Some(self.set.ascii)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn chars(&self) -> MultiCharSearcher<impl Fn(char) -> bool + 'p> {
+        let set = self.set;
+        MultiCharSearcher::new(move |c| set.contains(c))
+    }
+}
+
+unsafe impl<'p> Searcher<str> for CharSetSearcher<'p> {
+    #[inline]
+    fn search(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        match self.ascii_only() {
+            Some(ascii) => ascii?.search(span.as_bytes()),
+            None => self.chars().search(span),
+        }
+    }
+}
+
+unsafe impl<'p> ReverseSearcher<str> for CharSetSearcher<'p> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        match self.ascii_only() {
+            Some(ascii) => ascii?.rsearch(span.as_bytes()),
+            None => self.chars().rsearch(span),
+        }
+    }
+}
+
+unsafe impl<'p> Consumer<str> for CharSetSearcher<'p> {
+    #[inline]
+    fn consume(&mut self, span: Span<&str>) -> Option<usize> {
+        match self.ascii_only() {
+            Some(ascii) => ascii?.consume(span.as_bytes()),
+            None => self.chars().consume(span),
+        }
+    }
+
+    #[inline]
+    fn trim_start(&mut self, hay: &str) -> usize {
+        match self.ascii_only() {
+            Some(Some(ascii)) => ascii.trim_start(hay.as_bytes()),
+            Some(None) => 0,
+            None => self.chars().trim_start(hay),
+        }
+    }
+}
+
+unsafe impl<'p> ReverseConsumer<str> for CharSetSearcher<'p> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&str>) -> Option<usize> {
+        match self.ascii_only() {
+            Some(ascii) => ascii?.rconsume(span.as_bytes()),
+            None => self.chars().rconsume(span),
+        }
+    }
+
+    #[inline]
+    fn trim_end(&mut self, hay: &str) -> usize {
+        match self.ascii_only() {
+            Some(Some(ascii)) => ascii.trim_end(hay.as_bytes()),
+            Some(None) => hay.len(),
+            None => self.chars().trim_end(hay),
+        }
+    }
+}
+
+unsafe impl<'p> DoubleEndedSearcher<str> for CharSetSearcher<'p> {}
+unsafe impl<'p> DoubleEndedConsumer<str> for CharSetSearcher<'p> {}
+
+impl<'p, H: Haystack<Target = str>> Needle<H> for &'p CharSet {
+    type Searcher = CharSetSearcher<'p>;
+    type Consumer = CharSetSearcher<'p>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        CharSetSearcher { set: self }
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        CharSetSearcher { set: self }
+    }
+}
+
+impl<'p> Compile for &'p CharSet {
+    type Searcher = CharSetSearcher<'p>;
+    type Consumer = CharSetSearcher<'p>;
+
+    #[inline]
+    fn compile_searcher(&self) -> Self::Searcher {
+        CharSetSearcher { set: self }
+    }
+
+    #[inline]
+    fn compile_consumer(&self) -> Self::Consumer {
+        CharSetSearcher { set: self }
+    }
+}
+
+impl<'p, H: Haystack<Target = str>> Needle<H> for &Compiled<&'p CharSet> {
+    type Searcher = CharSetSearcher<'p>;
+    type Consumer = CharSetSearcher<'p>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        self.searcher()
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        self.consumer()
+    }
+}
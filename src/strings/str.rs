@@ -1,4 +1,5 @@
 use needle::*;
+use compiled::{Compile, Compiled};
 use haystack::{Span, Haystack};
 use slices::slice::{TwoWaySearcher, NaiveSearcher, SliceSearcher};
 use std::ops::Range;
@@ -66,3 +67,33 @@ impl_needle!(<['p]> for &'p str);
 #[cfg(feature = "std")]
 impl_needle!(<['p]> for &'p String);
 impl_needle!(<['q, 'p]> for &'q &'p str);
+
+impl<'p> Compile for &'p str {
+    type Searcher = SliceSearcher<'p, u8>;
+    type Consumer = NaiveSearcher<'p, u8>;
+
+    #[inline]
+    fn compile_searcher(&self) -> Self::Searcher {
+        SliceSearcher::new(self.as_bytes())
+    }
+
+    #[inline]
+    fn compile_consumer(&self) -> Self::Consumer {
+        NaiveSearcher::new(self.as_bytes())
+    }
+}
+
+impl<'p, H: Haystack<Target = str>> Needle<H> for &Compiled<&'p str> {
+    type Searcher = SliceSearcher<'p, u8>;
+    type Consumer = NaiveSearcher<'p, u8>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        self.searcher()
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        self.consumer()
+    }
+}
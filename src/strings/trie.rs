@@ -0,0 +1,54 @@
+use needle::*;
+use haystack::{Hay, Haystack, Span};
+use slices::trie::{Trie, TrieSearcher};
+use std::ops::Range;
+
+// The patterns are valid UTF-8, so every pattern consumed from a character
+// boundary of a valid UTF-8 string must end on a character boundary.
+
+unsafe impl<'t> Searcher<str> for TrieSearcher<'t, u8> {
+    #[inline]
+    fn search(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        self.search_in(hay.as_bytes(), range, |i| unsafe { hay.next_index(i) })
+    }
+}
+
+unsafe impl<'t> ReverseSearcher<str> for TrieSearcher<'t, u8> {
+    #[inline]
+    fn rsearch(&mut self, span: Span<&str>) -> Option<Range<usize>> {
+        let (hay, range) = span.into_parts();
+        self.rsearch_in(hay.as_bytes(), range, |i| unsafe { hay.prev_index(i) })
+    }
+}
+
+unsafe impl<'t> Consumer<str> for TrieSearcher<'t, u8> {
+    #[inline]
+    fn consume(&mut self, span: Span<&str>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        self.consume_in(hay.as_bytes(), range)
+    }
+}
+
+unsafe impl<'t> ReverseConsumer<str> for TrieSearcher<'t, u8> {
+    #[inline]
+    fn rconsume(&mut self, span: Span<&str>) -> Option<usize> {
+        let (hay, range) = span.into_parts();
+        self.rconsume_in(hay.as_bytes(), range)
+    }
+}
+
+impl<'t, H: Haystack<Target = str>> Needle<H> for &'t Trie<u8, str> {
+    type Searcher = TrieSearcher<'t, u8>;
+    type Consumer = TrieSearcher<'t, u8>;
+
+    #[inline]
+    fn into_searcher(self) -> Self::Searcher {
+        self.searcher()
+    }
+
+    #[inline]
+    fn into_consumer(self) -> Self::Consumer {
+        self.searcher()
+    }
+}
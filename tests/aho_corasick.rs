@@ -0,0 +1,206 @@
+extern crate pattern_3;
+
+mod common;
+
+use common::Lcg;
+use pattern_3::*;
+use pattern_3::ext::*;
+use std::ops::Range;
+
+fn bytes(rng: &mut Lcg, max_len: u64) -> Vec<u8> {
+    let len = rng.next(max_len) + 1;
+    rng.pick(b"abc", len as usize)
+}
+
+// Reference implementation: try every start position, pick the preferred
+// pattern there.
+fn naive_find(hay: &[u8], patterns: &[&[u8]], kind: MatchKind) -> Option<(usize, Range<usize>)> {
+    for start in 0..hay.len() {
+        let mut best: Option<(usize, usize)> = None;
+        for (i, p) in patterns.iter().enumerate() {
+            if hay[start..].starts_with(p) {
+                best = match (best, kind) {
+                    (None, _) => Some((i, p.len())),
+                    (Some((_, len)), MatchKind::LeftmostLongest) if p.len() > len => Some((i, p.len())),
+                    (b, _) => b,
+                };
+            }
+        }
+        if let Some((i, len)) = best {
+            return Some((i, start..(start + len)));
+        }
+    }
+    None
+}
+
+fn naive_rfind(hay: &[u8], patterns: &[&[u8]], kind: MatchKind) -> Option<(usize, Range<usize>)> {
+    for end in (1..=hay.len()).rev() {
+        let mut best: Option<(usize, usize)> = None;
+        for (i, p) in patterns.iter().enumerate() {
+            if hay[..end].ends_with(p) {
+                best = match (best, kind) {
+                    (None, _) => Some((i, p.len())),
+                    (Some((_, len)), MatchKind::LeftmostLongest) if p.len() > len => Some((i, p.len())),
+                    (b, _) => b,
+                };
+            }
+        }
+        if let Some((i, len)) = best {
+            return Some((i, (end - len)..end));
+        }
+    }
+    None
+}
+
+#[test]
+fn test_against_naive() {
+    let mut rng = Lcg(1);
+    for _ in 0..2000 {
+        let pattern_count = rng.next(5) + 1;
+        let patterns = (0..pattern_count).map(|_| bytes(&mut rng, 4)).collect::<Vec<_>>();
+        let patterns = patterns.iter().map(|p| &**p).collect::<Vec<_>>();
+        let hay = bytes(&mut rng, 30);
+
+        for &kind in &[MatchKind::LeftmostFirst, MatchKind::LeftmostLongest] {
+            let needle = AhoCorasick::new(&patterns).match_kind(kind);
+
+            let mut searcher = Needle::<&[u8]>::into_searcher(&needle);
+            let range = searcher.search(Span::from(&*hay));
+            let actual = range.map(|r| (searcher.pattern_index().unwrap(), r));
+            assert_eq!(actual, naive_find(&hay, &patterns, kind), "{:?} in {:?}", patterns, hay);
+
+            let mut searcher = Needle::<&[u8]>::into_searcher(&needle);
+            let range = searcher.rsearch(Span::from(&*hay));
+            let actual = range.map(|r| (searcher.pattern_index().unwrap(), r));
+            assert_eq!(actual, naive_rfind(&hay, &patterns, kind), "{:?} in {:?}", patterns, hay);
+        }
+    }
+}
+
+#[test]
+fn test_leftmost_first() {
+    let patterns = ["abcd", "b", "bcd", "abc"];
+    let needle = AhoCorasick::new(&patterns);
+    assert_eq!(
+        match_ranges("xabcdx abc bcd", &needle).collect::<Vec<_>>(),
+        vec![(1..5, "abcd"), (7..10, "abc"), (11..12, "b")]
+    );
+    assert_eq!(
+        rmatch_ranges("xabcdx abc bcd", &needle).collect::<Vec<_>>(),
+        vec![(11..14, "bcd"), (7..10, "abc"), (1..5, "abcd")]
+    );
+}
+
+#[test]
+fn test_leftmost_longest() {
+    let patterns = ["b", "abc", "abcd", "bcd"];
+    let needle = AhoCorasick::new(&patterns).match_kind(MatchKind::LeftmostLongest);
+    assert_eq!(
+        match_ranges("xabcdx abc bcd", &needle).collect::<Vec<_>>(),
+        vec![(1..5, "abcd"), (7..10, "abc"), (11..14, "bcd")]
+    );
+}
+
+#[test]
+fn test_pattern_index() {
+    let needle = AhoCorasick::new(&["foo", "bar", "foo"]);
+    let mut searcher = Needle::<&str>::into_searcher(&needle);
+    assert_eq!(searcher.pattern_index(), None);
+    assert_eq!(searcher.search(Span::from("a bar foo")), Some(2..5));
+    assert_eq!(searcher.pattern_index(), Some(1));
+    assert_eq!(searcher.rsearch(Span::from("a bar foo")), Some(6..9));
+    assert_eq!(searcher.pattern_index(), Some(0));
+    assert_eq!(searcher.search(Span::from("baz")), None);
+    assert_eq!(searcher.pattern_index(), None);
+
+    let mut consumer = Needle::<&str>::into_consumer(&needle);
+    assert_eq!(consumer.consume(Span::from("barfoo")), Some(3));
+    assert_eq!(consumer.pattern_index(), Some(1));
+    assert_eq!(consumer.rconsume(Span::from("barfoo")), Some(3));
+    assert_eq!(consumer.pattern_index(), Some(0));
+}
+
+#[test]
+fn test_unicode() {
+    let patterns: &[&str] = &["貓", "🐈", "cat"];
+    assert_eq!(
+        match_indices("a 🐈 is 貓, not a cat", patterns).collect::<Vec<_>>(),
+        vec![(2, "🐈"), (10, "貓"), (21, "cat")]
+    );
+    assert_eq!(
+        split("x貓y🐈z", patterns).collect::<Vec<_>>(),
+        vec!["x", "y", "z"]
+    );
+    assert_eq!(rfind("🐈貓cat", patterns), Some(7));
+}
+
+#[test]
+fn test_empty_pattern() {
+    let patterns: &[&str] = &["a", ""];
+    assert_eq!(
+        match_ranges("baé", patterns).collect::<Vec<_>>(),
+        vec![(0..0, ""), (1..2, "a"), (4..4, "")]
+    );
+    assert_eq!(
+        rmatch_ranges("baé", patterns).collect::<Vec<_>>(),
+        vec![(4..4, ""), (1..2, "a"), (0..0, "")]
+    );
+
+    let patterns: &[&str] = &["", "a"];
+    assert_eq!(
+        match_ranges("ba", patterns).collect::<Vec<_>>(),
+        vec![(0..0, ""), (1..1, ""), (2..2, "")]
+    );
+
+    let needle = AhoCorasick::new(&["", "a"]).match_kind(MatchKind::LeftmostLongest);
+    assert_eq!(
+        match_ranges("ba", &needle).collect::<Vec<_>>(),
+        vec![(0..0, ""), (1..2, "a")]
+    );
+
+    let patterns: &[&str] = &[];
+    assert_eq!(find("abc", patterns), None);
+}
+
+#[test]
+fn test_consumer() {
+    let patterns: &[&str] = &["ab", "a", "c"];
+    assert!(starts_with("abc", patterns));
+    assert!(!starts_with("bc", patterns));
+    assert!(ends_with("abc", patterns));
+    assert_eq!(trim_start("abacbab", patterns), "bab");
+    assert_eq!(trim_end("bacab", patterns), "b");
+
+    let needle = AhoCorasick::new(&["a", "ab"]);
+    assert_eq!(trim_start("abab", &needle), "bab");
+    let needle = needle.match_kind(MatchKind::LeftmostLongest);
+    assert_eq!(trim_start("abab", &needle), "");
+}
+
+#[test]
+fn test_slices() {
+    let haystack = &[1, 2, 3, 1, 2, 4, 5, 3][..];
+    let patterns: &[&[i32]] = &[&[1, 2, 4], &[3], &[2, 4, 5]];
+    assert_eq!(
+        match_indices(haystack, patterns).collect::<Vec<_>>(),
+        vec![(2, &[3][..]), (3, &[1, 2, 4][..]), (7, &[3][..])]
+    );
+    assert_eq!(
+        split(haystack.to_vec(), &AhoCorasick::new(&[vec![1, 2], vec![5]])).collect::<Vec<_>>(),
+        vec![vec![], vec![3], vec![4], vec![3]]
+    );
+}
+
+#[test]
+fn test_reuse() {
+    let keywords = ["fn", "let", "match", "if"].iter().map(|k| k.to_string()).collect::<Vec<_>>();
+    let needle = AhoCorasick::<u8, str>::new(&keywords);
+    let lines = ["let x = 1;", "x + y", "if x { y }", "match"];
+    let found = lines.iter().filter(|line| contains(**line, &needle)).count();
+    assert_eq!(found, 3);
+    assert_eq!(rfind(lines[2], &needle), Some(0));
+    assert!(starts_with(lines[3], &needle));
+
+    let copy = needle.clone().match_kind(MatchKind::LeftmostLongest);
+    assert_eq!(find("a match", &copy), Some(2));
+}
@@ -0,0 +1,146 @@
+extern crate pattern_3;
+extern crate regex;
+
+mod common;
+
+use common::Lcg;
+use pattern_3::*;
+use pattern_3::anchors::*;
+use pattern_3::combinators::NeedleExt;
+use pattern_3::ext::*;
+use regex::Regex;
+
+fn check<P>(hay: &str, needle: P, regex: &Regex)
+where
+    P: Needle<&'static str, Searcher = AnchorSearcher> + Needle<&'static [u8], Searcher = AnchorSearcher> + Copy,
+{
+    let hay: &'static str = Box::leak(hay.to_owned().into_boxed_str());
+    let expected = regex.find_iter(hay).map(|m| m.start()).collect::<Vec<_>>();
+
+    let actual = match_indices(hay, needle).map(|(i, _)| i).collect::<Vec<_>>();
+    assert_eq!(actual, expected, "{:?} {:?}", hay, regex);
+
+    let mut actual = rmatch_indices(hay, needle).map(|(i, _)| i).collect::<Vec<_>>();
+    actual.reverse();
+    assert_eq!(actual, expected, "{:?} {:?}", hay, regex);
+
+    let actual = match_indices(hay.as_bytes(), needle).map(|(i, _)| i).collect::<Vec<_>>();
+    assert_eq!(actual, expected, "{:?} {:?}", hay, regex);
+}
+
+#[test]
+fn test_against_regex() {
+    let start_of_hay = Regex::new(r"\A").unwrap();
+    let end_of_hay = Regex::new(r"\z").unwrap();
+    let start_of_line = Regex::new(r"(?m)^").unwrap();
+    let end_of_line = Regex::new(r"(?m)$").unwrap();
+    let word_boundary = Regex::new(r"\b").unwrap();
+    let ascii_word_boundary = Regex::new(r"(?-u:\b)").unwrap();
+
+    let mut rng = Lcg(31);
+    let alphabet = ["a", "Z", "0", "_", " ", "-", "\n", "é", "中", "\u{2028}"];
+    for _ in 0..300 {
+        let len = rng.next(12) as usize;
+        let hay = rng.pick(&alphabet, len).concat();
+        check(&hay, StartOfHay, &start_of_hay);
+        check(&hay, EndOfHay, &end_of_hay);
+        check(&hay, StartOfLine, &start_of_line);
+        check(&hay, EndOfLine, &end_of_line);
+        check(&hay, WordBoundary, &word_boundary);
+        check(&hay, AsciiWordBoundary, &ascii_word_boundary);
+    }
+}
+
+#[test]
+fn test_context_outside_range() {
+    let hay = "foo bar\nbaz";
+    let mut searcher = Needle::<&str>::into_searcher(StartOfHay);
+    assert_eq!(searcher.search(unsafe { Span::from_parts(hay, 4..11) }), None);
+
+    let mut searcher = Needle::<&str>::into_searcher(WordBoundary);
+    assert_eq!(searcher.search(unsafe { Span::from_parts(hay, 5..11) }), Some(7..7));
+    let mut searcher = Needle::<&str>::into_searcher(WordBoundary);
+    assert_eq!(searcher.search(unsafe { Span::from_parts(hay, 4..11) }), Some(4..4));
+
+    let mut searcher = Needle::<&str>::into_searcher(StartOfLine);
+    assert_eq!(searcher.search(unsafe { Span::from_parts(hay, 8..11) }), Some(8..8));
+    let mut searcher = Needle::<&str>::into_searcher(EndOfLine);
+    assert_eq!(searcher.rsearch(unsafe { Span::from_parts(hay, 0..7) }), Some(7..7));
+    assert_eq!(searcher.rsearch(unsafe { Span::from_parts(hay, 0..7) }), None);
+
+    let mut consumer = Needle::<&str>::into_consumer(EndOfHay);
+    assert_eq!(consumer.consume(unsafe { Span::from_parts(hay, 8..11) }), None);
+    assert_eq!(consumer.rconsume(unsafe { Span::from_parts(hay, 8..11) }), Some(11));
+}
+
+#[test]
+fn test_resume() {
+    // Only the position found last is skipped, not the start of every span.
+    let hay = "a b\nc";
+    let mut searcher = Needle::<&str>::into_searcher(WordBoundary);
+    assert_eq!(searcher.search(unsafe { Span::from_parts(hay, 0..5) }), Some(0..0));
+    assert_eq!(searcher.search(unsafe { Span::from_parts(hay, 0..5) }), Some(1..1));
+    assert_eq!(searcher.search(unsafe { Span::from_parts(hay, 2..5) }), Some(2..2));
+    assert_eq!(searcher.rsearch(unsafe { Span::from_parts(hay, 0..5) }), Some(5..5));
+    assert_eq!(searcher.rsearch(unsafe { Span::from_parts(hay, 0..3) }), Some(3..3));
+    assert_eq!(searcher.rsearch(unsafe { Span::from_parts(hay, 0..3) }), Some(2..2));
+
+    // The searcher steps over whole characters.
+    let mut searcher = Needle::<&str>::into_searcher(StartOfLine);
+    assert_eq!(searcher.rsearch(unsafe { Span::from_parts("é\né", 0..3) }), Some(3..3));
+    assert_eq!(searcher.rsearch(unsafe { Span::from_parts("é\né", 0..3) }), Some(0..0));
+}
+
+#[test]
+fn test_combined() {
+    let word = WordBoundary.then("cat").then(WordBoundary);
+    assert_eq!(
+        match_indices("cat concat cats (cat)", word).collect::<Vec<_>>(),
+        vec![(0, "cat"), (17, "cat")]
+    );
+    assert!(starts_with("cat!", word));
+    assert!(!starts_with("cats", word));
+
+    let comment = StartOfLine.then('#');
+    assert_eq!(
+        match_indices("# a\nb # c\n#d", comment).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![0, 10]
+    );
+
+    let mut res = String::new();
+    replace_with("one\ntwo", EndOfLine, |_| ";", |s| res.push_str(s));
+    assert_eq!(res, "one;\ntwo;");
+
+    assert_eq!(split("a1 b2", AsciiWordBoundary).collect::<Vec<_>>(), vec!["", "a1", " ", "b2", ""]);
+}
+
+#[test]
+fn test_bytes() {
+    let hay = &b"ab\xffcd \xc3\xa9"[..];
+    assert_eq!(
+        match_indices(hay, WordBoundary).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![0, 2, 3, 5, 6, 8]
+    );
+    assert_eq!(
+        match_indices(hay, AsciiWordBoundary).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![0, 2, 3, 5]
+    );
+}
+
+#[test]
+fn test_wtf8() {
+    let hay = unsafe { Wtf8::from_bytes_unchecked(b"\xed\xa0\x80ab\n\xf0\x90\x80\x80c") };
+    assert_eq!(
+        match_indices(hay, WordBoundary).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![3, 5, 6, 11]
+    );
+    assert_eq!(
+        match_indices(hay, StartOfLine).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![0, 6]
+    );
+
+    // The second half of a surrogate pair is not a word character.
+    let tail = unsafe { Wtf8::from_bytes_unchecked(b"\x90\x80\x80a") };
+    assert_eq!(find(tail, WordBoundary), Some(3));
+    assert_eq!(find(tail, EndOfHay), Some(4));
+}
@@ -0,0 +1,155 @@
+extern crate pattern_3;
+
+mod common;
+
+use common::Lcg;
+use pattern_3::*;
+use pattern_3::combinators::any_of;
+use pattern_3::ext::*;
+
+const PATTERNS: [&str; 3] = ["ab", "b", "bcb"];
+
+// Reference implementation: non-overlapping leftmost matches of `PATTERNS`.
+fn naive_matches(hay: &str, kind: MatchKind) -> Vec<(usize, usize)> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    while start < hay.len() {
+        let mut best: Option<(usize, usize)> = None;
+        for (i, p) in PATTERNS.iter().enumerate() {
+            if hay[start..].starts_with(p) {
+                best = match (best, kind) {
+                    (None, _) => Some((i, p.len())),
+                    (Some((_, len)), MatchKind::LeftmostLongest) if p.len() > len => Some((i, p.len())),
+                    (b, _) => b,
+                };
+            }
+        }
+        match best {
+            Some((i, len)) => {
+                result.push((i, start));
+                start += len;
+            }
+            None => start += 1,
+        }
+    }
+    result
+}
+
+#[test]
+fn test_against_naive() {
+    let mut rng = Lcg(7);
+    for _ in 0..500 {
+        let hay = (0..rng.next(30)).map(|_| ["a", "b", "c"][rng.next(3) as usize]).collect::<String>();
+        for &kind in &[MatchKind::LeftmostFirst, MatchKind::LeftmostLongest] {
+            let expected = naive_matches(&hay, kind);
+            let needle = any_of(("ab", 'b', "bcb")).match_kind(kind);
+
+            let mut searcher = Needle::<&str>::into_searcher(needle);
+            let mut actual = Vec::new();
+            let mut start = 0;
+            while let Some(r) = searcher.search(unsafe { Span::from_parts(&*hay, start..hay.len()) }) {
+                actual.push((searcher.branch().unwrap(), r.start));
+                start = r.end;
+            }
+            assert_eq!(actual, expected, "{:?} {:?}", hay, kind);
+
+            let expected = expected.into_iter().map(|(_, i)| i).collect::<Vec<_>>();
+            let actual = match_indices(&*hay, needle).map(|(i, _)| i).collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{:?} {:?}", hay, kind);
+
+            let needle = any_of((&b"ab"[..], |b: &u8| *b == b'b', &b"bcb"[..])).match_kind(kind);
+            let actual = match_indices(hay.clone().into_bytes(), needle).map(|(i, _)| i).collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{:?} {:?}", hay, kind);
+        }
+    }
+}
+
+#[test]
+fn test_heterogeneous() {
+    let needle = any_of(("-->", '\n', |c: char| c.is_ascii_digit()));
+    assert_eq!(
+        match_indices("a-->b\nc12", needle).collect::<Vec<_>>(),
+        vec![(1, "-->"), (5, "\n"), (7, "1"), (8, "2")]
+    );
+    assert_eq!(
+        rmatch_indices("a-->b\nc12", needle).collect::<Vec<_>>(),
+        vec![(8, "2"), (7, "1"), (5, "\n"), (1, "-->")]
+    );
+    assert_eq!(rfind("1-->", needle), Some(1));
+    assert_eq!(find("abc", needle), None);
+}
+
+#[test]
+fn test_branch() {
+    let needle = any_of(("foo", 'x', "oo"));
+    let mut searcher = Needle::<&str>::into_searcher(needle);
+    assert_eq!(searcher.branch(), None);
+    assert_eq!(searcher.search(Span::from("a foo")), Some(2..5));
+    assert_eq!(searcher.branch(), Some(0));
+    assert_eq!(searcher.rsearch(Span::from("xfoo")), Some(1..4));
+    assert_eq!(searcher.branch(), Some(0));
+    assert_eq!(searcher.rsearch(Span::from("xoo")), Some(1..3));
+    assert_eq!(searcher.branch(), Some(2));
+    assert_eq!(searcher.search(Span::from("box")), Some(2..3));
+    assert_eq!(searcher.branch(), Some(1));
+    assert_eq!(searcher.search(Span::from("bar")), None);
+    assert_eq!(searcher.branch(), None);
+
+    let mut consumer = Needle::<&str>::into_consumer(any_of(("f", "fo")));
+    assert_eq!(consumer.consume(Span::from("foo")), Some(1));
+    assert_eq!(consumer.branch(), Some(0));
+    let needle = any_of(("f", "fo")).match_kind(MatchKind::LeftmostLongest);
+    let mut consumer = Needle::<&str>::into_consumer(needle);
+    assert_eq!(consumer.consume(Span::from("foo")), Some(2));
+    assert_eq!(consumer.branch(), Some(1));
+}
+
+#[test]
+fn test_reverse_tie_break() {
+    let hay = "xabc";
+    assert_eq!(rfind_range(hay, any_of(("c", "bc", "abc"))), Some(3..4));
+    let needle = any_of(("c", "bc", "abc")).match_kind(MatchKind::LeftmostLongest);
+    assert_eq!(rfind_range(hay, needle), Some(1..4));
+    assert_eq!(
+        rsplit("a->b-c", any_of(("-", "->")).match_kind(MatchKind::LeftmostLongest)).collect::<Vec<_>>(),
+        vec!["c", "b", "a"]
+    );
+}
+
+#[test]
+fn test_empty_branch() {
+    assert_eq!(
+        match_ranges("ab", any_of(("", 'a'))).collect::<Vec<_>>(),
+        vec![(0..0, ""), (1..1, ""), (2..2, "")]
+    );
+    assert_eq!(
+        match_ranges("ba", any_of(('a', ""))).collect::<Vec<_>>(),
+        vec![(0..0, ""), (1..2, "a")]
+    );
+    assert_eq!(
+        rmatch_ranges("ab", any_of(('a', ""))).collect::<Vec<_>>(),
+        vec![(2..2, ""), (0..1, "a")]
+    );
+}
+
+#[test]
+fn test_consumer() {
+    let needle = any_of((' ', "\r\n", '\n'));
+    assert_eq!(trim_start(" \r\n x \n", needle), "x \n");
+    assert_eq!(trim_end(" \r\n x \n", needle), " \r\n x");
+    assert!(starts_with("\r\n", needle));
+    assert!(!starts_with("\r", needle));
+    assert!(ends_with("x ", needle));
+}
+
+#[test]
+fn test_reuse() {
+    let mut searcher = Needle::<&str>::into_searcher(any_of(('é', 'z')));
+    let mut hay = String::with_capacity(16);
+    for (s, expected) in [("xé_", Some(1..3)), ("éx_", Some(0..2)), ("abcd", None), ("ab_z", Some(3..4))] {
+        hay.clear();
+        hay.push_str(s);
+        assert_eq!(searcher.search(Span::from(&*hay)), expected.clone());
+        assert_eq!(searcher.rsearch(Span::from(&*hay)), expected);
+    }
+}
@@ -0,0 +1,208 @@
+extern crate pattern_3;
+
+mod common;
+
+use common::Lcg;
+use pattern_3::*;
+use pattern_3::approx::{match_distances, Approx, Metric};
+use pattern_3::ext::*;
+use std::cmp::min;
+use std::ops::Range;
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut column = (0..=a.len()).collect::<Vec<_>>();
+    for (j, y) in b.iter().enumerate() {
+        let mut diagonal = column[0];
+        column[0] = j + 1;
+        for (i, x) in a.iter().enumerate() {
+            let above = column[i + 1];
+            column[i + 1] = min(min(above, column[i]) + 1, diagonal + (x != y) as usize);
+            diagonal = above;
+        }
+    }
+    column[a.len()]
+}
+
+fn hamming(a: &[char], b: &[char]) -> usize {
+    a.iter().zip(b).filter(|(x, y)| x != y).count()
+}
+
+/// Straightforward implementation of the matching rules, on char indices.
+struct Naive<'a> {
+    needle: &'a [char],
+    hay: &'a [char],
+    k: usize,
+    metric: Metric,
+}
+
+impl<'a> Naive<'a> {
+    /// The best distance of a substring ending at `end` and starting at or
+    /// after `pos`, with the position where it starts.
+    fn best_ending_at(&self, pos: usize, end: usize) -> Option<(usize, usize)> {
+        let m = self.needle.len();
+        match self.metric {
+            Metric::Hamming => {
+                if end < pos + m {
+                    return None;
+                }
+                Some((hamming(self.needle, &self.hay[(end - m)..end]), end - m))
+            }
+            Metric::Levenshtein => {
+                // Longer substrings are always farther than `k`.
+                let lowest = end.saturating_sub(m + self.k + 1).max(pos);
+                (lowest..=end)
+                    .map(|s| (levenshtein(self.needle, &self.hay[s..end]), s))
+                    .min()
+            }
+        }
+    }
+
+    fn score(&self, pos: usize, end: usize) -> usize {
+        self.best_ending_at(pos, end).map_or(usize::MAX, |(d, _)| d)
+    }
+
+    fn search(&self, pos: usize, allow_empty: bool) -> Option<(Range<usize>, usize)> {
+        let first = (pos..=self.hay.len())
+            .filter(|&j| j != pos || allow_empty)
+            .find(|&j| self.score(pos, j) <= self.k)?;
+        let mut end = first;
+        while end < self.hay.len() && self.score(pos, end + 1) < self.score(pos, end) {
+            end += 1;
+        }
+        let (distance, start) = self.best_ending_at(pos, end).unwrap();
+        while end < self.hay.len() && self.score(pos, end + 1) == distance {
+            if self.best_ending_at(pos, end + 1).unwrap().1 != start {
+                break;
+            }
+            end += 1;
+        }
+        Some((start..end, distance))
+    }
+
+    fn consume(&self) -> Option<usize> {
+        let m = self.needle.len();
+        match self.metric {
+            Metric::Hamming => {
+                if self.hay.len() >= m && hamming(self.needle, &self.hay[..m]) <= self.k {
+                    Some(m)
+                } else {
+                    None
+                }
+            }
+            Metric::Levenshtein => (0..=self.hay.len())
+                .map(|j| (levenshtein(self.needle, &self.hay[..j]), !j))
+                .filter(|&(d, _)| d <= self.k)
+                .min()
+                .map(|(_, j)| !j),
+        }
+    }
+
+    fn all(&self) -> Vec<(Range<usize>, usize)> {
+        let mut result = Vec::new();
+        let mut pos = 0;
+        while let Some((range, distance)) = self.search(pos, result.is_empty()) {
+            pos = range.end;
+            result.push((range, distance));
+            if pos == self.hay.len() {
+                break;
+            }
+        }
+        result
+    }
+}
+
+fn approx<N>(needle: N, k: usize, metric: Metric) -> Approx<N> {
+    match metric {
+        Metric::Levenshtein => Approx::levenshtein(needle, k),
+        Metric::Hamming => Approx::hamming(needle, k),
+    }
+}
+
+fn check(needle: &[char], hay: &[char], k: usize, metric: Metric) {
+    let naive = Naive { needle, hay, k, metric };
+    let expected = naive.all();
+
+    let actual = match_distances(hay, approx(needle, k, metric))
+        .map(|(r, _, d)| (r, d))
+        .collect::<Vec<_>>();
+    assert_eq!(actual, expected, "{:?} {:?} {} {:?}", needle, hay, k, metric);
+
+    // The same matches in a string, with byte indices.
+    let needle_str = needle.iter().collect::<String>();
+    let hay_str = hay.iter().collect::<String>();
+    let offsets = hay_str.char_indices().map(|(i, _)| i).chain(Some(hay_str.len())).collect::<Vec<_>>();
+    let actual = match_distances(&*hay_str, approx(&*needle_str, k, metric))
+        .map(|(r, _, d)| (r, d))
+        .collect::<Vec<_>>();
+    let expected_str = expected.iter()
+        .map(|(r, d)| (offsets[r.start]..offsets[r.end], *d))
+        .collect::<Vec<_>>();
+    assert_eq!(actual, expected_str, "{:?} {:?} {} {:?}", needle, hay, k, metric);
+
+    assert_eq!(
+        Needle::<&[char]>::into_consumer(approx(needle, k, metric)).consume(Span::from(hay)),
+        naive.consume(),
+        "{:?} {:?} {} {:?}", needle, hay, k, metric
+    );
+}
+
+#[test]
+fn test_against_naive() {
+    let mut rng = Lcg(5);
+    let alphabet = ['a', 'b', 'c', 'é'];
+    for _ in 0..1500 {
+        let len = rng.next(6) as usize;
+        let needle = rng.pick(&alphabet, len);
+        let len = rng.next(14) as usize;
+        let hay = rng.pick(&alphabet, len);
+        let k = rng.next(3) as usize;
+        check(&needle, &hay, k, Metric::Levenshtein);
+        check(&needle, &hay, k, Metric::Hamming);
+    }
+}
+
+#[test]
+fn test_long_needles() {
+    let mut rng = Lcg(9);
+    let alphabet = ['a', 'b', 'c', 'd'];
+    for &len in &[63, 64, 65, 80] {
+        let needle = rng.pick(&alphabet, len);
+        let mut hay = rng.pick(&alphabet, 10);
+        hay.extend_from_slice(&needle);
+        hay.extend(rng.pick(&alphabet, 10));
+        hay[20] = 'x';
+        hay.remove(40);
+        check(&needle, &hay, 2, Metric::Levenshtein);
+        check(&needle, &hay, 1, Metric::Hamming);
+    }
+}
+
+#[test]
+fn test_text() {
+    let text = "Tne quick brown f0x jumped ovr the lazy dgo";
+    assert_eq!(find(text, Approx::levenshtein("The", 1)), Some(0));
+    assert_eq!(find_range(text, Approx::hamming("fox", 1)), Some(16..19));
+    assert_eq!(
+        match_distances(text, Approx::levenshtein("over", 1)).collect::<Vec<_>>(),
+        vec![(27..30, "ovr", 1)]
+    );
+    assert_eq!(
+        match_ranges(text, Approx::levenshtein("quick brwn", 1)).collect::<Vec<_>>(),
+        vec![(4..15, "quick brown")]
+    );
+    assert!(!contains(text, Approx::levenshtein("lion", 1)));
+    assert!(starts_with(text, Approx::levenshtein("The quack", 2)));
+    assert_eq!(trim_start(text, Approx::hamming("The ", 1)), "quick brown f0x jumped ovr the lazy dgo");
+}
+
+#[test]
+fn test_empty_needle() {
+    assert_eq!(
+        match_ranges("ab", Approx::levenshtein("", 0)).map(|(r, _)| r).collect::<Vec<_>>(),
+        vec![0..0, 1..1, 2..2]
+    );
+    assert_eq!(
+        match_ranges("ab", Approx::hamming("", 3)).map(|(r, _)| r).collect::<Vec<_>>(),
+        vec![0..0, 1..1, 2..2]
+    );
+}
@@ -0,0 +1,103 @@
+extern crate pattern_3;
+
+use pattern_3::{AsciiCaseInsensitive, Wtf8};
+use pattern_3::ext::*;
+
+#[test]
+fn test_str() {
+    let haystack = "The quick brown FOX jumps over the lazy Fox, fox!";
+    let needle = AsciiCaseInsensitive("fOx");
+    assert_eq!(
+        match_ranges(haystack, needle).collect::<Vec<_>>(),
+        vec![(16..19, "FOX"), (40..43, "Fox"), (45..48, "fox")]
+    );
+    assert_eq!(
+        rmatch_indices(haystack, needle).collect::<Vec<_>>(),
+        vec![(45, "fox"), (40, "Fox"), (16, "FOX")]
+    );
+    assert_eq!(find(haystack, AsciiCaseInsensitive("THE")), Some(0));
+    assert_eq!(rfind(haystack, AsciiCaseInsensitive("THE")), Some(31));
+    assert_eq!(find(haystack, AsciiCaseInsensitive("cat")), None);
+}
+
+#[test]
+fn test_non_letters_are_exact() {
+    // `@` (0x40) and `` ` `` (0x60) differ only by the case bit, but are not letters.
+    assert_eq!(find("a@b", AsciiCaseInsensitive("A`B")), None);
+    assert_eq!(find("[x]", AsciiCaseInsensitive("{X}")), None);
+    assert_eq!(find("x[X]", AsciiCaseInsensitive("[x]")), Some(1));
+}
+
+#[test]
+fn test_unicode_is_not_folded() {
+    let haystack = "ÉCOLE école École";
+    assert_eq!(
+        match_ranges(haystack, AsciiCaseInsensitive("cole")).collect::<Vec<_>>(),
+        vec![(2..6, "COLE"), (9..13, "cole"), (16..20, "cole")]
+    );
+    assert_eq!(
+        match_ranges(haystack, AsciiCaseInsensitive("école")).collect::<Vec<_>>(),
+        vec![(7..13, "école")]
+    );
+    assert_eq!(
+        split("aΣbσcΣ", AsciiCaseInsensitive("σ")).collect::<Vec<_>>(),
+        vec!["aΣb", "cΣ"]
+    );
+}
+
+#[test]
+fn test_bytes() {
+    let haystack = &b"GET /index.html HTTP/1.1\r\nhost: example.com\r\nHOST: x\r\n"[..];
+    assert_eq!(
+        match_indices(haystack, AsciiCaseInsensitive("Host:"))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>(),
+        vec![26, 45]
+    );
+    assert!(starts_with(haystack, AsciiCaseInsensitive("get")));
+    assert!(ends_with(haystack, AsciiCaseInsensitive(": X\r\n")));
+    assert!(contains(haystack.to_vec(), AsciiCaseInsensitive("EXAMPLE.COM")));
+}
+
+#[test]
+fn test_long_period() {
+    let needle = AsciiCaseInsensitive("abcDEFghiJKLmnoPQRstu");
+    let haystack = "xxABCdefGHIjklMNOpqrSTUxxabcdefghijklmnopqrstuvw";
+    assert_eq!(
+        match_indices(haystack, needle).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![2, 25]
+    );
+    assert_eq!(
+        rmatch_indices(haystack, needle).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![25, 2]
+    );
+}
+
+#[test]
+fn test_consumer() {
+    assert!(starts_with("HeLLo world", AsciiCaseInsensitive("hello")));
+    assert!(!starts_with("Help", AsciiCaseInsensitive("hello")));
+    assert!(ends_with("main.RS", AsciiCaseInsensitive(".rs")));
+    assert_eq!(trim_start("abABaBAbc", AsciiCaseInsensitive("ab")), "c");
+    assert_eq!(trim_end("cabABaBAb", AsciiCaseInsensitive("AB")), "c");
+    assert_eq!(trim_start("", AsciiCaseInsensitive("ab")), "");
+}
+
+#[test]
+fn test_empty_needle() {
+    assert_eq!(
+        match_indices("aB", AsciiCaseInsensitive("")).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+}
+
+#[test]
+fn test_wtf8() {
+    let haystack = unsafe { Wtf8::from_bytes_unchecked(b"Key\xed\xa0\x80=kEY") };
+    assert_eq!(
+        match_indices(haystack, AsciiCaseInsensitive("key")).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![0, 7]
+    );
+    assert!(starts_with(haystack, AsciiCaseInsensitive("KEY")));
+    assert_eq!(trim_end(haystack, AsciiCaseInsensitive("key")).len(), 7);
+}
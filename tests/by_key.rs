@@ -0,0 +1,116 @@
+extern crate pattern_3;
+
+mod common;
+
+use common::{naive_matches, Lcg};
+use pattern_3::*;
+use pattern_3::ext::*;
+use std::ops::Range;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Kind {
+    Ident,
+    Colon,
+    Number,
+    Comma,
+}
+
+#[derive(Debug, PartialEq)]
+struct Token {
+    kind: Kind,
+    text: &'static str,
+}
+
+fn tokenize(src: &'static str) -> Vec<Token> {
+    src.split(' ')
+        .map(|text| {
+            let kind = match text {
+                ":" => Kind::Colon,
+                "," => Kind::Comma,
+                _ if text.bytes().all(|b| b.is_ascii_digit()) => Kind::Number,
+                _ => Kind::Ident,
+            };
+            Token { kind, text }
+        })
+        .collect()
+}
+
+fn kind(t: &Token) -> Kind {
+    t.kind
+}
+
+#[test]
+fn test_tokens() {
+    let tokens = tokenize("a : 1 , b : c , d : 22 , 3");
+    let field = [Kind::Ident, Kind::Colon, Kind::Number];
+    assert_eq!(
+        match_ranges(&tokens[..], by_key(kind, &field)).map(|(r, _)| r).collect::<Vec<_>>(),
+        vec![0..3, 8..11]
+    );
+    assert_eq!(
+        rmatch_indices(&tokens[..], by_key(kind, &field)).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![8, 0]
+    );
+    assert_eq!(
+        split(&tokens[..], by_key(kind, &[Kind::Comma])).map(|s| s.len()).collect::<Vec<_>>(),
+        vec![3, 3, 3, 1]
+    );
+    assert!(starts_with(&tokens[..], by_key(kind, &field)));
+    assert!(ends_with(&tokens[..], by_key(kind, &[Kind::Comma, Kind::Number])));
+    assert_eq!(trim_start(&tokens[..], by_key(kind, &[Kind::Ident])).len(), tokens.len() - 1);
+    assert_eq!(find(&tokens[..], by_key(kind, &[])), Some(0));
+}
+
+#[test]
+fn test_mutable_haystacks() {
+    let mut tokens = tokenize("x : 1 , y : 2");
+    for t in split(&mut tokens[..], by_key(kind, &[Kind::Comma])) {
+        t[0].text = "_";
+    }
+    assert_eq!(tokens.iter().map(|t| t.text).collect::<Vec<_>>(), vec!["_", ":", "1", ",", "_", ":", "2"]);
+
+    let tokens = tokenize("x : 1 , y : 2");
+    let parts = split(tokens, by_key(kind, &[Kind::Comma])).collect::<Vec<_>>();
+    assert_eq!(parts[1][0].text, "y");
+}
+
+/// A key without a total order, which is searched naively.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Parity(bool);
+
+fn naive(hay: &[u32], needle: &[u32], key: fn(&u32) -> u32) -> Vec<Range<usize>> {
+    naive_matches(hay.len(), needle.len(), false, |i| {
+        hay[i..(i + needle.len())].iter().map(key).eq(needle.iter().cloned())
+    })
+}
+
+#[test]
+fn test_against_naive() {
+    let mut rng = Lcg(13);
+    for _ in 0..3000 {
+        let hay = (0..rng.next(20)).map(|_| rng.next(10) as u32).collect::<Vec<_>>();
+        let needle = (0..(1 + rng.next(4))).map(|_| rng.next(3) as u32).collect::<Vec<_>>();
+        let key: fn(&u32) -> u32 = |x| x % 3;
+
+        let expected = naive(&hay, &needle, key);
+        let actual = match_ranges(&*hay, by_key(key, &needle)).map(|(r, _)| r).collect::<Vec<_>>();
+        assert_eq!(actual, expected, "{:?} {:?}", hay, needle);
+
+        let last = (0..(hay.len() + 1).saturating_sub(needle.len()))
+            .rev()
+            .find(|&i| hay[i..(i + needle.len())].iter().map(key).eq(needle.iter().cloned()));
+        assert_eq!(rfind(&*hay, by_key(key, &needle)), last, "{:?} {:?}", hay, needle);
+
+        let parities = needle.iter().map(|&x| Parity(x % 2 == 0)).collect::<Vec<_>>();
+        let parity_key = |x: &u32| Parity(x % 2 == 0);
+        let expected = naive(&hay, &needle.iter().map(|x| x % 2).collect::<Vec<_>>(), |x| x % 2);
+        let actual = match_ranges(&*hay, by_key(parity_key, &parities)).map(|(r, _)| r).collect::<Vec<_>>();
+        assert_eq!(actual, expected, "{:?} {:?}", hay, needle);
+
+        let prefix = hay.len() >= needle.len() && hay[..needle.len()].iter().map(key).eq(needle.iter().cloned());
+        assert_eq!(starts_with(&*hay, by_key(key, &needle)), prefix);
+        let suffix = hay.len() >= needle.len()
+            && hay[(hay.len() - needle.len())..].iter().map(key).eq(needle.iter().cloned());
+        assert_eq!(ends_with(&*hay, by_key(key, &needle)), suffix);
+    }
+}
@@ -0,0 +1,88 @@
+extern crate pattern_3;
+
+use pattern_3::*;
+use pattern_3::combinators::{any_of, capture, repeat, NeedleExt};
+use pattern_3::ext::*;
+
+#[test]
+fn test_captures() {
+    let digits = repeat(|c: char| c.is_ascii_digit(), 1..);
+    let needle = capture(digits).then('-').then(capture(digits));
+    let caps = captures("tel: 555-1234", needle).unwrap();
+    assert_eq!(caps.len(), 3);
+    assert_eq!(caps.get(0), Some("555-1234"));
+    assert_eq!(caps.get(1), Some("555"));
+    assert_eq!(caps.range(2), Some(9..13));
+    assert_eq!(caps.get(3), None);
+    assert!(captures("tel: none", needle).is_none());
+}
+
+#[test]
+fn test_non_capturing() {
+    let caps = captures("abc", 'b').unwrap();
+    assert_eq!(caps.len(), 1);
+    assert_eq!(caps.range(0), Some(1..2));
+
+    let caps = captures(&[1, 2, 3][..], &[2, 3][..]).unwrap();
+    assert_eq!(caps.get(0), Some(&[2, 3][..]));
+
+    let mut searcher = Needle::<&str>::into_searcher("b".then('c'));
+    assert_eq!(searcher.search(Span::from("abc")), Some(1..3));
+    assert_eq!(CapturingSearcher::<str>::captures_len(&searcher), 0);
+}
+
+#[test]
+fn test_captures_iter() {
+    let word = repeat(char::is_alphabetic, 1..);
+    let needle = capture(word).then('=').then(capture(repeat(char::is_numeric, ..)));
+    let pairs = captures_iter("a=1, bc=, d=23", needle)
+        .map(|c| (c.get(1).unwrap(), c.get(2).unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(pairs, vec![("a", "1"), ("bc", ""), ("d", "23")]);
+}
+
+#[test]
+fn test_alternation() {
+    let needle = any_of((capture('x').then(capture('1')), capture('y')));
+    let groups = captures_iter("y x1", needle)
+        .map(|c| (1..c.len()).map(|i| c.range(i)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        groups,
+        vec![vec![None, None, Some(0..1)], vec![Some(2..3), Some(3..4), None]]
+    );
+}
+
+#[test]
+fn test_repetition() {
+    // Groups inside a repetition report the last repetition.
+    let needle = repeat(capture(|c: char| c.is_ascii_digit()).then(','), 1..);
+    let caps = captures("1,2,3,x", needle).unwrap();
+    assert_eq!(caps.get(0), Some("1,2,3,"));
+    assert_eq!(caps.get(1), Some("3"));
+
+    let needle = 'a'.then(repeat(capture('b'), ..)).then('c');
+    assert_eq!(captures("abbc", needle).unwrap().range(1), Some(2..3));
+    assert_eq!(captures("ac", needle).unwrap().range(1), None);
+}
+
+#[test]
+fn test_replace_with_captures() {
+    let needle = capture(repeat(char::is_alphabetic, 1..)).then(' ').then(capture(repeat(char::is_alphabetic, 1..)));
+    let mut res = String::new();
+    replace_with_captures("hello world, foo bar", needle, |c| c.get(2).unwrap(), |s| res.push_str(s));
+    assert_eq!(res, "world, bar");
+
+    let mut res = String::new();
+    replacen_with_captures("ab ab ab", capture('a').then('b'), |c| c.get(1).unwrap(), 2, |s| res.push_str(s));
+    assert_eq!(res, "a a ab");
+}
+
+#[test]
+fn test_replace_template() {
+    let word = repeat(char::is_alphabetic, 1..);
+    let needle = capture(word).then(", ").then(capture(word));
+    assert_eq!(replace_template("Doe, John; Roe, Jane", needle, "$2 $1"), "John Doe; Jane Roe");
+    assert_eq!(replace_template("a, b", needle, "${2}1 $3 $$ $x"), "b1  $ $x");
+    assert_eq!(replace_template("abc", "", "-"), "-a-b-c-");
+}
@@ -0,0 +1,116 @@
+extern crate pattern_3;
+
+use pattern_3::CaseInsensitive;
+use pattern_3::ext::*;
+
+#[test]
+fn test_ascii() {
+    let haystack = "Hello hello HELLO hElLo";
+    assert_eq!(
+        match_indices(haystack, CaseInsensitive("HeLLo")).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![0, 6, 12, 18]
+    );
+    assert_eq!(
+        rmatch_indices(haystack, CaseInsensitive("HeLLo")).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![18, 12, 6, 0]
+    );
+    assert_eq!(find(haystack, CaseInsensitive("world")), None);
+}
+
+#[test]
+fn test_greek() {
+    // final sigma, small sigma and capital sigma are all equivalent.
+    let haystack = "ΟΔΥΣΣΕΥΣ οδυσσευς Οδυσσευς";
+    assert_eq!(
+        match_ranges(haystack, CaseInsensitive("οδυσσεύς")).collect::<Vec<_>>(),
+        vec![]
+    );
+    assert_eq!(
+        match_ranges(haystack, CaseInsensitive("ΟΔΥΣΣΕΥΣ")).collect::<Vec<_>>(),
+        vec![(0..16, "ΟΔΥΣΣΕΥΣ"), (17..33, "οδυσσευς"), (34..50, "Οδυσσευς")]
+    );
+    assert!(contains("ΣΟΦΙΑ", CaseInsensitive("σοφια")));
+}
+
+#[test]
+fn test_cyrillic() {
+    let haystack = "Москва, МОСКВА и москва";
+    assert_eq!(
+        match_ranges(haystack, CaseInsensitive("мОСКВа")).collect::<Vec<_>>(),
+        vec![(0..12, "Москва"), (14..26, "МОСКВА"), (30..42, "москва")]
+    );
+    assert_eq!(
+        split(haystack, CaseInsensitive(", ")).collect::<Vec<_>>(),
+        vec!["Москва", "МОСКВА и москва"]
+    );
+}
+
+#[test]
+fn test_sharp_s() {
+    assert_eq!(find("STRAẞE", CaseInsensitive("Straße")), Some(0));
+    assert_eq!(find("Straße", CaseInsensitive("STRAẞE")), Some(0));
+    // multi-character expansions are not performed by simple case folding.
+    assert_eq!(find("STRASSE", CaseInsensitive("Straße")), None);
+}
+
+#[test]
+fn test_different_byte_length() {
+    // KELVIN SIGN (3 bytes) folds to `k` (1 byte).
+    let haystack = "\u{212a}elvin kelvin";
+    assert_eq!(
+        match_ranges(haystack, CaseInsensitive("KELVIN")).collect::<Vec<_>>(),
+        vec![(0..8, "\u{212a}elvin"), (9..15, "kelvin")]
+    );
+    assert_eq!(
+        rmatch_ranges(haystack, CaseInsensitive("KELVIN")).collect::<Vec<_>>(),
+        vec![(9..15, "kelvin"), (0..8, "\u{212a}elvin")]
+    );
+    // LATIN SMALL LETTER LONG S folds to `s`.
+    assert_eq!(find("ſtraſſe", CaseInsensitive("STRASSE")), Some(0));
+}
+
+#[test]
+fn test_dotted_and_dotless_i() {
+    // `ı` and `İ` only have Turkic or full folding mappings, so they fold to
+    // themselves.
+    assert_eq!(find("ı", CaseInsensitive("I")), None);
+    assert_eq!(find("ı", CaseInsensitive("i")), None);
+    assert_eq!(find("İ", CaseInsensitive("i")), None);
+    assert_eq!(find("İ", CaseInsensitive("I")), None);
+    assert_eq!(find("İ", CaseInsensitive("ı")), None);
+    assert_eq!(find("xıİ", CaseInsensitive("ı")), Some(1));
+    assert_eq!(find("xıİ", CaseInsensitive("İ")), Some(3));
+}
+
+#[test]
+fn test_special_mappings() {
+    assert_eq!(find("ſ", CaseInsensitive("S")), Some(0));
+    assert_eq!(find("S", CaseInsensitive("ſ")), Some(0));
+    assert_eq!(find("k", CaseInsensitive("\u{212a}")), Some(0));
+    assert_eq!(find("\u{212a}", CaseInsensitive("K")), Some(0));
+    assert_eq!(find("µ", CaseInsensitive("Μ")), Some(0));
+    // Cherokee folds to the upper case letters.
+    assert_eq!(find("\u{ab70}", CaseInsensitive("\u{13a0}")), Some(0));
+}
+
+#[test]
+fn test_consumer() {
+    assert!(starts_with("ÉCOLE normale", CaseInsensitive("école")));
+    assert!(!starts_with("ECOLE normale", CaseInsensitive("école")));
+    assert!(ends_with("fichier.TXT", CaseInsensitive(".txt")));
+    assert_eq!(trim_start("aAaAb", CaseInsensitive("a")), "b");
+    assert_eq!(trim_end("bÄäÄ", CaseInsensitive("ä")), "b");
+    assert_eq!(trim_start("", CaseInsensitive("a")), "");
+}
+
+#[test]
+fn test_empty_needle() {
+    assert_eq!(
+        match_indices("aé", CaseInsensitive("")).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![0, 1, 3]
+    );
+    assert_eq!(
+        rmatch_indices("aé", CaseInsensitive("")).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![3, 1, 0]
+    );
+}
@@ -0,0 +1,145 @@
+extern crate pattern_3;
+
+use pattern_3::{ByteSet, Elem, Needle};
+use pattern_3::class::*;
+use pattern_3::ext::*;
+
+#[test]
+fn test_char_union() {
+    let needle = union(' ', union('\t', '\n'));
+    assert_eq!(
+        split("a b\tc\nd", needle).collect::<Vec<_>>(),
+        vec!["a", "b", "c", "d"]
+    );
+    assert_eq!(
+        rmatch_indices("é é\n", needle).collect::<Vec<_>>(),
+        vec![(5, "\n"), (2, " ")]
+    );
+
+    let needle = union('é', union('0'..='9', char::is_uppercase));
+    assert_eq!(
+        match_indices("aé1Bü", needle.clone()).collect::<Vec<_>>(),
+        vec![(1, "é"), (3, "1"), (4, "B")]
+    );
+    assert_eq!(trim_end("x9Éé", needle), "x");
+}
+
+#[test]
+fn test_char_intersection_and_complement() {
+    let needle = intersection(char::is_alphabetic, complement('a'..='z'));
+    assert_eq!(
+        match_indices("aBcΔ1", needle).collect::<Vec<_>>(),
+        vec![(1, "B"), (3, "Δ")]
+    );
+
+    let needle = intersection('a'..='f', complement(&['b', 'd'][..]));
+    assert_eq!(
+        split("xbyazf", needle.clone()).collect::<Vec<_>>(),
+        vec!["xby", "z", ""]
+    );
+    assert_eq!(find("bdbd", needle), None);
+
+    let needle = complement(union(char::is_alphanumeric, '_'));
+    assert_eq!(
+        split("foo.bar_baz+qux", needle).collect::<Vec<_>>(),
+        vec!["foo", "bar_baz", "qux"]
+    );
+    assert_eq!(trim("--ü--", needle), "ü");
+    assert!(starts_with("é", complement('a'..='z')));
+    assert!(!ends_with("é", complement('é')));
+}
+
+#[test]
+fn test_empty_class() {
+    let needle = intersection('a'..='c', 'x'..='z');
+    assert_eq!(find("abcxyz", needle.clone()), None);
+    assert_eq!(trim("abc", needle), "abc");
+
+    let needle = complement(union(char::is_alphabetic, complement(char::is_alphabetic)));
+    assert_eq!(rfind("abc", needle), None);
+}
+
+#[test]
+fn test_elem_classes() {
+    let hay = &[1, 5, 10, 15, 20, 25][..];
+    assert_eq!(
+        matches(hay, union(Elem(1), 20..)).collect::<Vec<_>>(),
+        vec![&[1][..], &[20], &[25]]
+    );
+    assert_eq!(
+        find(hay, intersection(5..=20, |x: &i32| x % 2 == 0)),
+        Some(2)
+    );
+    assert_eq!(trim_start(hay, complement(10..)), &[10, 15, 20, 25]);
+    assert_eq!(trim_end(hay, complement(..20)), &[1, 5, 10, 15]);
+    assert_eq!(
+        split(b"a1b22c".to_vec(), complement(union(b'a'..=b'z', Elem(b'_')))).collect::<Vec<_>>(),
+        vec![b"a".to_vec(), b"b".to_vec(), vec![], b"c".to_vec()]
+    );
+}
+
+#[test]
+fn test_ascii_members() {
+    assert_eq!(union('a', 'b'..='c').ascii_members(), Some(0b111 << 97));
+    assert_eq!(union('a', 'é').ascii_members(), None);
+    assert_eq!(intersection('a'..='z', char::is_uppercase).ascii_members(), Some(0));
+    assert_eq!(intersection(char::is_numeric, ..='1').ascii_members(), Some(0b11 << 48));
+    assert_eq!(complement('a').ascii_members(), None);
+}
+
+#[test]
+fn test_char_searcher_selection() {
+    fn kind<N: Needle<&'static str>>(needle: N) -> String
+    where
+        N::Searcher: std::fmt::Debug,
+    {
+        let searcher = format!("{:?}", needle.into_searcher());
+        searcher[searcher.find("kind: ").unwrap()..].to_owned()
+    }
+
+    assert!(kind(union('a', 'b')).starts_with("kind: Ascii(Two(97, 98))"));
+    assert!(kind(union('a', &['b', 'c', 'd'][..])).starts_with("kind: Ascii(Bitmap("));
+    assert!(kind(union('é', 'é')).starts_with("kind: Char(CharSearcher {"));
+    let is_alphabetic = char::is_alphabetic as fn(char) -> bool;
+    assert!(kind(intersection('é', is_alphabetic)).starts_with("kind: Char("));
+    assert!(kind(intersection(is_alphabetic, 'é'..='é')).starts_with("kind: Char("));
+    assert!(kind(union('é', 'è')).starts_with("kind: Chars"));
+    assert!(kind(complement('é')).starts_with("kind: Chars"));
+
+    let needle = intersection('é', char::is_alphabetic);
+    assert_eq!(
+        match_indices("aéé", needle).collect::<Vec<_>>(),
+        vec![(1, "é"), (3, "é")]
+    );
+    assert_eq!(trim_end("aéé", needle), "a");
+    assert_eq!(find("e", needle), None);
+}
+
+#[test]
+fn test_byte_searcher_selection() {
+    fn bytes<N: Needle<&'static [u8]>>(needle: N) -> String
+    where
+        N::Searcher: std::fmt::Debug,
+    {
+        let searcher = format!("{:?}", needle.into_searcher());
+        searcher[searcher.rfind("bytes: ").unwrap()..].to_owned()
+    }
+
+    assert!(bytes(union(Elem(b'a'), Elem(b'a'))).starts_with("bytes: Some(One(97))"));
+    assert!(bytes(union(Elem(b'a'), Elem(b'b'))).starts_with("bytes: Some(Two(97, 98))"));
+    assert!(bytes(union(Elem(b'a'), b'b'..=b'c')).starts_with("bytes: Some(Three(97, 98, 99))"));
+    assert!(bytes(complement(b'a'..)).starts_with("bytes: Some(Bitmap("));
+    assert!(bytes(union(ByteSet::new(b"xyz"), Elem(b'\n'))).starts_with("bytes: Some(Bitmap("));
+    let low_bits_clear = (|b: &u8| b & 7 == 0) as fn(&u8) -> bool;
+    assert!(bytes(intersection(b'a'..=b'z', low_bits_clear)).starts_with("bytes: Some(Three(104, 112, 120))"));
+    assert!(bytes(complement(u8::is_ascii as fn(&u8) -> bool)).starts_with("bytes: None"));
+
+    let hay = &b"a\xffb\x00c"[..];
+    assert_eq!(
+        split(hay, complement(b'a'..=b'z')).collect::<Vec<_>>(),
+        vec![&b"a"[..], b"b", b"c"]
+    );
+    assert_eq!(rfind(hay, union(Elem(0), Elem(0xff))), Some(3));
+    assert_eq!(trim_start(hay, complement(Elem(0))), b"\x00c");
+    assert_eq!(trim_end(hay, union(b'b'..=b'c', ..=0)), b"a\xff");
+}
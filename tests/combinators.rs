@@ -0,0 +1,89 @@
+extern crate pattern_3;
+
+use pattern_3::combinators::*;
+use pattern_3::ext::*;
+use std::ops::Bound;
+
+#[test]
+fn test_then() {
+    let needle = "->".then(char::is_alphabetic);
+    assert_eq!(
+        match_ranges("a->b -> c->d", needle).collect::<Vec<_>>(),
+        vec![(1..4, "->b"), (9..12, "->d")]
+    );
+    assert_eq!(
+        rmatch_ranges("a->b -> c->d", needle).collect::<Vec<_>>(),
+        vec![(9..12, "->d"), (1..4, "->b")]
+    );
+    assert!(starts_with("->x", needle));
+    assert!(!starts_with("-> x", needle));
+    assert!(ends_with("é->ü", needle));
+    assert_eq!(trim_start("->a->b->", needle), "->");
+    assert_eq!(trim_end("->a->b->", needle), "->a->b->");
+
+    let needle = (&[1, 2][..]).then(|x: &i32| *x > 2);
+    assert_eq!(
+        split(&[0, 1, 2, 3, 1, 2, 1, 2, 5][..], needle).collect::<Vec<_>>(),
+        vec![&[0][..], &[1, 2], &[]]
+    );
+}
+
+#[test]
+fn test_repeat() {
+    let digits = repeat(|c: char| c.is_ascii_digit(), 1..);
+    assert_eq!(
+        matches("a1b22c333", digits).collect::<Vec<_>>(),
+        vec!["1", "22", "333"]
+    );
+    assert_eq!(
+        rmatches("a1b22c333", digits).collect::<Vec<_>>(),
+        vec!["333", "22", "1"]
+    );
+    assert_eq!(
+        split("10.0.0.255", digits).collect::<Vec<_>>(),
+        vec!["", ".", ".", ".", ""]
+    );
+
+    let needle = repeat("ab", 2..4);
+    assert_eq!(
+        match_ranges("ab ababababab", needle).collect::<Vec<_>>(),
+        vec![(3..9, "ababab"), (9..13, "abab")]
+    );
+    assert_eq!(
+        rmatch_ranges("ab ababababab", needle).collect::<Vec<_>>(),
+        vec![(7..13, "ababab"), (3..7, "abab")]
+    );
+    assert!(!starts_with("ab", needle));
+    assert!(starts_with("aab", repeat('a', ..=2)));
+    assert!(starts_with("aab", repeat('a', 2..=2).then('b')));
+    assert!(!starts_with("aaab", repeat('a', 2..=2).then('b')));
+}
+
+#[test]
+fn test_repeat_empty() {
+    assert_eq!(
+        match_ranges("baa", repeat('a', ..)).collect::<Vec<_>>(),
+        vec![(0..0, ""), (1..3, "aa")]
+    );
+    assert_eq!(
+        rmatch_ranges("aab", repeat('a', ..)).collect::<Vec<_>>(),
+        vec![(3..3, ""), (0..2, "aa")]
+    );
+    assert_eq!(find("aaa", repeat('a', ..0)), None);
+    assert_eq!(find("aaa", repeat('a', (Bound::Included(3), Bound::Included(2)))), None);
+    assert_eq!(find("xyz", repeat("", 5..)), Some(0));
+    assert_eq!(trim_start("xyz", repeat("", 5..)), "xyz");
+}
+
+#[test]
+fn test_nested() {
+    // key=value pairs with alphanumeric keys and values
+    let word = repeat(char::is_alphanumeric, 1..);
+    let pair = word.then('=').then(word);
+    assert_eq!(
+        matches("a=1, bad, b2=x y, =z", pair).collect::<Vec<_>>(),
+        vec!["a=1", "b2=x"]
+    );
+    let list = pair.then(repeat(", ".then(pair), ..));
+    assert_eq!(find_range("x: a=1, b=2, c", list), Some(3..11));
+}
@@ -0,0 +1,56 @@
+//! Helpers shared by the tests which compare needles against naive
+//! implementations on random inputs.
+
+#![allow(dead_code)]
+
+use std::ops::Range;
+
+/// A linear congruential generator, so that every run sees the same inputs.
+pub struct Lcg(pub u64);
+
+impl Lcg {
+    /// Returns a number in `0..n`.
+    pub fn next(&mut self, n: u64) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 33) % n
+    }
+
+    /// Returns `len` items picked from `alphabet`.
+    pub fn pick<T: Clone>(&mut self, alphabet: &[T], len: usize) -> Vec<T> {
+        (0..len).map(|_| alphabet[self.next(alphabet.len() as u64) as usize].clone()).collect()
+    }
+}
+
+/// Lists the non-overlapping matches of a non-empty needle of length `len` in
+/// a hay of length `hay_len`, from the end if `reverse` is set.
+///
+/// `matches_at(i)` checks whether the needle matches at `i..(i + len)`.
+pub fn naive_matches<F>(hay_len: usize, len: usize, reverse: bool, matches_at: F) -> Vec<Range<usize>>
+where
+    F: Fn(usize) -> bool,
+{
+    assert_ne!(len, 0);
+    let mut result = Vec::new();
+    if !reverse {
+        let mut i = 0;
+        while i + len <= hay_len {
+            if matches_at(i) {
+                result.push(i..(i + len));
+                i += len;
+            } else {
+                i += 1;
+            }
+        }
+    } else {
+        let mut end = hay_len;
+        while end >= len {
+            if matches_at(end - len) {
+                result.push((end - len)..end);
+                end -= len;
+            } else {
+                end -= 1;
+            }
+        }
+    }
+    result
+}
@@ -0,0 +1,110 @@
+extern crate pattern_3;
+
+use pattern_3::*;
+use pattern_3::ext::*;
+use std::sync::Arc;
+use std::thread;
+
+fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+#[test]
+fn test_str() {
+    let hays = ["a::b::c", "::", "", "a:b", "::a::::b::", "ä::ö"];
+    for needle in &["::", "", "a", "::::"] {
+        let compiled = Compiled::new(*needle);
+        for hay in &hays {
+            assert_eq!(split(*hay, &compiled).collect::<Vec<_>>(), split(*hay, *needle).collect::<Vec<_>>());
+            assert_eq!(rsplit(*hay, &compiled).collect::<Vec<_>>(), rsplit(*hay, *needle).collect::<Vec<_>>());
+            assert_eq!(match_ranges(*hay, &compiled).collect::<Vec<_>>(), match_ranges(*hay, *needle).collect::<Vec<_>>());
+            assert_eq!(starts_with(*hay, &compiled), starts_with(*hay, *needle));
+            assert_eq!(trim_end(*hay, &compiled), trim_end(*hay, *needle));
+        }
+    }
+}
+
+#[test]
+fn test_str_on_other_haystacks() {
+    let compiled = Compiled::new("ab");
+    let mut owned = "xabyab".to_owned();
+    assert_eq!(find(&mut *owned, &compiled), Some(1));
+    assert_eq!(rfind(Wtf8::from_str("xabyab"), &compiled), Some(4));
+    assert_eq!(trim_start(Wtf8::from_str("ababc"), &compiled), Wtf8::from_str("c"));
+}
+
+#[test]
+fn test_slice() {
+    let hay = &[1, 2, 1, 2, 1, 3, 1, 2][..];
+    for needle in &[&[1, 2][..], &[2, 1], &[], &[4], &[1, 2, 1]] {
+        let compiled = Compiled::new(*needle);
+        assert_eq!(match_ranges(hay, &compiled).collect::<Vec<_>>(), match_ranges(hay, *needle).collect::<Vec<_>>());
+        assert_eq!(rmatch_ranges(hay, &compiled).collect::<Vec<_>>(), rmatch_ranges(hay, *needle).collect::<Vec<_>>());
+        assert_eq!(ends_with(hay, &compiled), ends_with(hay, *needle));
+    }
+
+    let compiled = Compiled::new(&[0u8][..]);
+    assert_eq!(split(vec![1u8, 0, 2], &compiled).collect::<Vec<_>>(), vec![vec![1], vec![2]]);
+}
+
+#[test]
+fn test_wtf8() {
+    unsafe {
+        let needle = Wtf8::from_bytes_unchecked(b"\xed\xb0\x80");
+        let compiled = Compiled::new(needle);
+        let hays = [
+            Wtf8::from_bytes_unchecked(b"\x90\x80\x80aaa"),
+            Wtf8::from_bytes_unchecked(b"a\xed\xb0\x80b\xed\xb0\x80"),
+            Wtf8::from_str("\u{10000}"),
+            Wtf8::from_str("abc"),
+        ];
+        for hay in &hays {
+            assert_eq!(match_ranges(*hay, &compiled).collect::<Vec<_>>(), match_ranges(*hay, needle).collect::<Vec<_>>());
+            assert_eq!(rmatch_ranges(*hay, &compiled).collect::<Vec<_>>(), rmatch_ranges(*hay, needle).collect::<Vec<_>>());
+            assert_eq!(trim_start(*hay, &compiled), trim_start(*hay, needle));
+        }
+    }
+}
+
+#[test]
+fn test_char_set() {
+    let set = CharSet::new(&[' ', ',', '、']);
+    let compiled = Compiled::new(&set);
+    assert_eq!(split("a, b、c", &compiled).filter(|s| !s.is_empty()).collect::<Vec<_>>(), vec!["a", "b", "c"]);
+    assert_eq!(trim(" ,x, ", &compiled), "x");
+    assert_eq!(rfind("a、b", &compiled), Some(1));
+}
+
+#[test]
+fn test_reuse_in_struct() {
+    struct Tokenizer<'p> {
+        separator: Compiled<&'p str>,
+    }
+
+    let tokenizer = Tokenizer { separator: Compiled::new(", ") };
+    for (line, n) in &[("a, b", 2), ("a", 1), (", , ", 3)] {
+        assert_eq!(split(*line, &tokenizer.separator).count(), *n);
+    }
+    let cloned = tokenizer.separator.clone();
+    assert_eq!(find("x, y", &cloned), Some(1));
+}
+
+#[test]
+fn test_send_sync() {
+    let set = CharSet::new(&['a']);
+    assert_send_sync(&Compiled::new("abc"));
+    assert_send_sync(&Compiled::new(&[1u32, 2][..]));
+    assert_send_sync(&Compiled::new(Wtf8::from_str("abc")));
+    assert_send_sync(&Compiled::new(&set));
+
+    let compiled = Arc::new(Compiled::new("needle"));
+    let handles = (0..4)
+        .map(|i| {
+            let compiled = compiled.clone();
+            thread::spawn(move || {
+                let hay = format!("{}needle", "x".repeat(i));
+                find(&*hay, &*compiled)
+            })
+        })
+        .collect::<Vec<_>>();
+    let found = handles.into_iter().map(|h| h.join().unwrap()).collect::<Vec<_>>();
+    assert_eq!(found, vec![Some(0), Some(1), Some(2), Some(3)]);
+}
@@ -0,0 +1,60 @@
+extern crate pattern_3;
+
+use pattern_3::*;
+use pattern_3::ext::*;
+
+#[test]
+fn test_str() {
+    let token = "s3cr3t-t0k3n";
+    assert!(starts_with("s3cr3t-t0k3n", ConstantTime(token)));
+    assert!(starts_with("s3cr3t-t0k3n and more", ConstantTime(token)));
+    assert!(!starts_with("s3cr3t-t0k3m", ConstantTime(token)));
+    assert!(!starts_with("x3cr3t-t0k3n", ConstantTime(token)));
+    assert!(!starts_with("s3cr3t", ConstantTime(token)));
+    assert!(ends_with("token=s3cr3t-t0k3n", ConstantTime(token)));
+    assert!(!ends_with("token=s3cr3t-t0k3n ", ConstantTime(token)));
+    assert!(starts_with("anything", ConstantTime("")));
+
+    assert_eq!(trim_start("ababc", ConstantTime("ab")), "c");
+    assert_eq!(trim_end("cabab", ConstantTime("ab")), "c");
+    assert_eq!(trim_end("xéé", ConstantTime("é")), "x");
+}
+
+#[test]
+fn test_bytes() {
+    let mac = &b"\x00\xff\x10\x80"[..];
+    assert!(starts_with(&b"\x00\xff\x10\x80\x01"[..], ConstantTime(mac)));
+    assert!(!starts_with(&b"\x00\xff\x10\x81\x01"[..], ConstantTime(mac)));
+    assert!(ends_with(&b"data\x00\xff\x10\x80"[..], ConstantTime(mac)));
+    assert!(!ends_with(&b"data\x01\xff\x10\x80"[..], ConstantTime(mac)));
+    assert!(starts_with(vec![0, 255, 16, 128], ConstantTime(mac)));
+}
+
+#[test]
+fn test_same_results_as_slices() {
+    let needles = ["", "a", "ab", "ba", "aab", "é", "aé"];
+    let hays = ["", "a", "b", "ab", "aab", "abab", "éab", "aéé", "baab"];
+    for needle in &needles {
+        for hay in &hays {
+            let mut expected = Needle::<&str>::into_consumer(*needle);
+            let mut actual = Needle::<&str>::into_consumer(ConstantTime(*needle));
+            assert_eq!(actual.consume(Span::from(*hay)), expected.consume(Span::from(*hay)));
+            assert_eq!(actual.rconsume(Span::from(*hay)), expected.rconsume(Span::from(*hay)));
+
+            let (needle, hay) = (needle.as_bytes(), hay.as_bytes());
+            let mut expected = Needle::<&[u8]>::into_consumer(needle);
+            let mut actual = Needle::<&[u8]>::into_consumer(ConstantTime(needle));
+            assert_eq!(actual.consume(Span::from(hay)), expected.consume(Span::from(hay)));
+            assert_eq!(actual.rconsume(Span::from(hay)), expected.rconsume(Span::from(hay)));
+        }
+    }
+}
+
+#[test]
+fn test_search_is_unaffected() {
+    assert_eq!(
+        split("a--b--c", ConstantTime("--")).collect::<Vec<_>>(),
+        vec!["a", "b", "c"]
+    );
+    assert_eq!(rfind(&b"xyxy"[..], ConstantTime(&b"xy"[..])), Some(2));
+}
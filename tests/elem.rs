@@ -0,0 +1,57 @@
+extern crate pattern_3;
+
+use pattern_3::*;
+use pattern_3::ext::*;
+
+#[test]
+fn test_bytes() {
+    let hay = &b"a,b,,c"[..];
+    assert_eq!(
+        match_indices(hay, Elem(b',')).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![1, 3, 4]
+    );
+    assert_eq!(
+        rmatch_indices(hay, Elem(b',')).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![4, 3, 1]
+    );
+    assert_eq!(
+        rsplit(hay, Elem(b',')).collect::<Vec<_>>(),
+        vec![&b"c"[..], b"", b"b", b"a"]
+    );
+    assert_eq!(find(hay, Elem(b'x')), None);
+    assert_eq!(rfind(&b""[..], Elem(b'x')), None);
+}
+
+#[test]
+fn test_signed_bytes() {
+    let hay = &[1i8, -1, 2, -1, 3][..];
+    assert_eq!(find(hay, Elem(-1)), Some(1));
+    assert_eq!(rfind(hay, Elem(-1)), Some(3));
+    assert_eq!(split(hay, Elem(-1)).collect::<Vec<_>>(), vec![&[1][..], &[2], &[3]]);
+}
+
+#[test]
+fn test_generic() {
+    let hay = vec!["x", "y", "x", "z"];
+    assert_eq!(
+        split(&*hay, Elem("x")).collect::<Vec<_>>(),
+        vec![&[][..], &["y"][..], &["z"][..]]
+    );
+    assert_eq!(
+        split(hay, Elem("y")).collect::<Vec<_>>(),
+        vec![vec!["x"], vec!["x", "z"]]
+    );
+    assert_eq!(rfind(&[1.0, f64::NAN][..], Elem(f64::NAN)), None);
+}
+
+#[test]
+fn test_consumer() {
+    let hay = &[0u8, 0, 1, 0][..];
+    assert!(starts_with(hay, Elem(0)));
+    assert!(!starts_with(hay, Elem(1)));
+    assert_eq!(trim_start(hay, Elem(0)), &[1, 0]);
+    assert_eq!(trim_end(hay, Elem(0)), &[0, 0, 1]);
+    assert_eq!(trim(hay, Elem(0)), &[1]);
+    assert_eq!(trim(hay, Elem(2)), hay);
+    assert_eq!(trim(&[0u32; 3][..], Elem(0)), &[]);
+}
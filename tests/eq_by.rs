@@ -0,0 +1,72 @@
+extern crate pattern_3;
+
+mod common;
+
+use common::{naive_matches, Lcg};
+use pattern_3::*;
+use pattern_3::ext::*;
+use std::ops::Range;
+
+fn close(x: &f64, y: &f64) -> bool {
+    (x - y).abs() <= 1.0
+}
+
+fn naive(hay: &[f64], needle: &[f64], reverse: bool) -> Vec<Range<usize>> {
+    naive_matches(hay.len(), needle.len(), reverse, |i| {
+        hay[i..(i + needle.len())].iter().zip(needle).all(|(x, y)| close(x, y))
+    })
+}
+
+#[test]
+fn test_strings() {
+    let hay = "a b c b c d".split(' ').map(String::from).collect::<Vec<_>>();
+    assert_eq!(
+        match_indices(&hay[..], PartialEqSlice(&["b", "c"])).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![1, 3]
+    );
+    assert_eq!(rfind(&hay[..], PartialEqSlice(&["b"])), Some(3));
+    assert!(starts_with(&hay[..], PartialEqSlice(&["a", "b"])));
+    assert!(ends_with(&hay[..], PartialEqSlice(&["d"])));
+    assert!(!contains(&hay[..], PartialEqSlice(&["c", "c"])));
+    assert_eq!(find(&hay[..], PartialEqSlice::<&str>(&[])), Some(0));
+    assert_eq!(split(hay, PartialEqSlice(&["c"])).map(|s| s.len()).collect::<Vec<_>>(), vec![2, 1, 1]);
+}
+
+#[test]
+fn test_nan() {
+    let hay = [1.0, f64::NAN, 2.0, 1.0, 2.0];
+    assert_eq!(find(&hay[..], eq_by(&[f64::NAN, 2.0], |x: &f64, y: &f64| x.to_bits() == y.to_bits())), Some(1));
+    assert_eq!(find(&hay[..], PartialEqSlice(&[f64::NAN, 2.0])), None);
+    assert_eq!(find(&hay[..], PartialEqSlice(&[1.0, 2.0])), Some(3));
+}
+
+#[test]
+fn test_mutable_haystacks() {
+    let mut hay = vec![0.0, 1.1, 2.0, 0.0, 0.9, 2.1];
+    for s in match_ranges(&mut hay[..], eq_by(&[1.0, 2.0], |x: &f64, y: &f64| (x - y).abs() < 0.2)) {
+        s.1[0] = 1.0;
+    }
+    assert_eq!(hay, vec![0.0, 1.0, 2.0, 0.0, 1.0, 2.1]);
+}
+
+// With a tolerance, equality is not transitive, so the shifts of the two-way
+// algorithm would skip matches.
+#[test]
+fn test_against_naive() {
+    let mut rng = Lcg(17);
+    for _ in 0..3000 {
+        let hay = (0..rng.next(16)).map(|_| rng.next(5) as f64).collect::<Vec<_>>();
+        let needle = (0..(1 + rng.next(4))).map(|_| rng.next(5) as f64).collect::<Vec<_>>();
+
+        let actual = match_ranges(&*hay, eq_by(&needle, close)).map(|(r, _)| r).collect::<Vec<_>>();
+        assert_eq!(actual, naive(&hay, &needle, false), "{:?} {:?}", hay, needle);
+        let actual = rmatch_ranges(&*hay, eq_by(&needle, close)).map(|(r, _)| r).collect::<Vec<_>>();
+        assert_eq!(actual, naive(&hay, &needle, true), "{:?} {:?}", hay, needle);
+
+        let prefix = hay.len() >= needle.len() && hay.iter().zip(&needle).all(|(x, y)| close(x, y));
+        assert_eq!(starts_with(&*hay, eq_by(&needle, close)), prefix);
+        let suffix = hay.len() >= needle.len()
+            && hay[(hay.len() - needle.len())..].iter().zip(&needle).all(|(x, y)| close(x, y));
+        assert_eq!(ends_with(&*hay, eq_by(&needle, close)), suffix);
+    }
+}
@@ -0,0 +1,181 @@
+extern crate pattern_3;
+extern crate regex;
+
+mod common;
+
+use common::Lcg;
+use pattern_3::*;
+use pattern_3::glob::Glob;
+use pattern_3::ext::*;
+use regex::Regex;
+use std::ops::Range;
+use std::path::Path;
+
+// Pieces of a glob and the equivalent regular expressions.
+const PIECES: &[(&str, &str)] = &[
+    ("a", "a"),
+    ("b", "b"),
+    ("é", "é"),
+    ("/", "/"),
+    ("?", "[^/]"),
+    ("*", "[^/]*"),
+    ("**", "(?s:.*)"),
+    ("**/", "(?s:.*/)?"),
+    ("[ab]", "[ab]"),
+    ("[!a]", "[^a/]"),
+    ("[]-é]", "[\\]-é]"),
+    ("\\*", "\\*"),
+];
+
+fn boundaries(hay: &str) -> Vec<usize> {
+    hay.char_indices().map(|(i, _)| i).chain(Some(hay.len())).collect()
+}
+
+/// Finds the leftmost-longest match at or after `pos` by brute force.
+fn naive_search(hay: &str, regex: &Regex, pos: usize, allow_empty: bool) -> Option<Range<usize>> {
+    let bounds = boundaries(hay);
+    for &start in bounds.iter().filter(|&&i| i >= pos) {
+        for &end in bounds.iter().rev().filter(|&&i| i >= start) {
+            if start == end && start == pos && !allow_empty {
+                continue;
+            }
+            if regex.is_match(&hay[start..end]) {
+                return Some(start..end);
+            }
+        }
+    }
+    None
+}
+
+/// Finds the rightmost-longest match at or before `pos` by brute force.
+fn naive_rsearch(hay: &str, regex: &Regex, pos: usize, allow_empty: bool) -> Option<Range<usize>> {
+    let bounds = boundaries(hay);
+    for &end in bounds.iter().rev().filter(|&&i| i <= pos) {
+        for &start in bounds.iter().filter(|&&i| i <= end) {
+            if start == end && end == pos && !allow_empty {
+                continue;
+            }
+            if regex.is_match(&hay[start..end]) {
+                return Some(start..end);
+            }
+        }
+    }
+    None
+}
+
+#[test]
+fn test_against_naive() {
+    let mut rng = Lcg(37);
+    let alphabet = ["a", "b", "é", "/", "*", "]"];
+    for _ in 0..400 {
+        let mut glob = String::new();
+        let mut re = String::from("^");
+        for _ in 0..(1 + rng.next(4)) {
+            let (g, r) = PIECES[rng.next(PIECES.len() as u64) as usize];
+            // Avoid gluing stars together, or turning `**` into `**/`.
+            if glob.ends_with('*') && (g.starts_with('*') || g.starts_with('/')) {
+                continue;
+            }
+            glob.push_str(g);
+            re.push_str(r);
+        }
+        re.push('$');
+        let regex = Regex::new(&re).unwrap();
+        let compiled = Glob::new(&glob);
+
+        for _ in 0..5 {
+            let len = rng.next(10) as usize;
+            let hay = rng.pick(&alphabet, len).concat();
+
+            let mut expected = Vec::new();
+            let mut pos = 0;
+            while let Some(m) = naive_search(&hay, &regex, pos, expected.is_empty()) {
+                pos = m.end;
+                expected.push(m);
+                if pos == hay.len() {
+                    break;
+                }
+            }
+            let actual = match_ranges(&*hay, &compiled).map(|(r, _)| r).collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{:?} {:?}", glob, hay);
+            let actual = match_ranges(hay.as_bytes(), &compiled).map(|(r, _)| r).collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{:?} {:?}", glob, hay);
+
+            let mut expected = Vec::new();
+            let mut pos = hay.len();
+            while let Some(m) = naive_rsearch(&hay, &regex, pos, expected.is_empty()) {
+                pos = m.start;
+                expected.push(m);
+                if pos == 0 {
+                    break;
+                }
+            }
+            let actual = rmatch_ranges(&*hay, &compiled).map(|(r, _)| r).collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{:?} {:?}", glob, hay);
+
+            let bounds = boundaries(&hay);
+            let longest_prefix = bounds.iter().rev().find(|&&i| regex.is_match(&hay[..i])).cloned();
+            let mut consumer = Needle::<&str>::into_consumer(&compiled);
+            assert_eq!(consumer.consume(Span::from(&*hay)), longest_prefix, "{:?} {:?}", glob, hay);
+            let longest_suffix = bounds.iter().find(|&&i| regex.is_match(&hay[i..])).cloned();
+            assert_eq!(consumer.rconsume(Span::from(&*hay)), longest_suffix, "{:?} {:?}", glob, hay);
+        }
+    }
+}
+
+#[test]
+fn test_syntax() {
+    assert!(starts_with("a*b", Glob::new(r"a\*b")));
+    assert!(!starts_with("axb", Glob::new(r"a\*b")));
+    assert!(starts_with("[x", Glob::new("[x")));
+    assert!(starts_with("a\\", Glob::new("a\\")));
+    assert!(starts_with("]", Glob::new("[]]")));
+    assert!(starts_with("-", Glob::new("[a-]")));
+    assert!(starts_with("^", Glob::new(r"[\^]")));
+    assert!(!starts_with("b", Glob::new("[^a-c]")));
+    assert!(starts_with("d", Glob::new("[!a-c]")));
+    assert!(!starts_with("/", Glob::new("[!a-c]")));
+    assert_eq!(find("x/y", Glob::new("?")), Some(0));
+    assert_eq!(find("/y", Glob::new("?")), Some(1));
+}
+
+#[test]
+fn test_paths() {
+    let path = Path::new("src/slices/mod.rs");
+    assert!(starts_with(path, Glob::new("src/**")));
+    assert!(starts_with(path, Glob::new("src/**/mod.rs")));
+    assert!(starts_with(path, Glob::new("src/*/")));
+    assert!(!starts_with(path, Glob::new("src/*.rs")));
+    assert!(ends_with(path, Glob::new("**/*.rs")));
+    assert!(ends_with(Path::new("lib.rs"), Glob::new("**/*.rs")));
+    assert!(ends_with(path, Glob::new("slices/[a-m]*")));
+    assert_eq!(trim_start(path, Glob::new("src/")), Path::new("slices/mod.rs"));
+    assert_eq!(
+        split(Path::new("a/b.rs/c"), Glob::new("/*.rs/")).collect::<Vec<_>>(),
+        vec![Path::new("a"), Path::new("c")]
+    );
+}
+
+#[test]
+fn test_invalid_utf8() {
+    let hay = &b"a\xffb\xe4\xb8c"[..];
+    assert_eq!(match_ranges(hay, Glob::new("a?b")).collect::<Vec<_>>(), vec![(0..3, &b"a\xffb"[..])]);
+    assert_eq!(match_ranges(hay, Glob::new("b??c")).collect::<Vec<_>>(), vec![(2..6, &b"b\xe4\xb8c"[..])]);
+    assert_eq!(find(hay, Glob::new("[!a]")), Some(1));
+    assert_eq!(find(hay, Glob::new("[\u{0}-\u{10ffff}]")), Some(0));
+    assert_eq!(rfind(hay, Glob::new("b*")), Some(2));
+}
+
+#[test]
+fn test_reuse() {
+    let glob = Glob::new("*.log");
+    let files = ["a.log", "b.txt", "c.log.gz", "d.log"];
+    assert_eq!(
+        files.iter().filter(|f| ends_with(**f, &glob)).collect::<Vec<_>>(),
+        vec![&"a.log", &"d.log"]
+    );
+
+    let mut res = String::new();
+    replace_with("x.log/y.log", &glob, |_| "_", |s| res.push_str(s));
+    assert_eq!(res, "_/_");
+}
@@ -0,0 +1,86 @@
+extern crate pattern_3;
+
+use pattern_3::*;
+use pattern_3::anchors::WordBoundary;
+use pattern_3::combinators::{any_of, NeedleExt};
+use pattern_3::ext::*;
+
+#[test]
+fn test_preceded_by() {
+    let escaped = ':'.preceded_by('\\');
+    assert_eq!(match_indices(r"a\:b:c\:", escaped).collect::<Vec<_>>(), vec![(2, ":"), (7, ":")]);
+    assert_eq!(rmatch_indices(r"a\:b:c\:", escaped).collect::<Vec<_>>(), vec![(7, ":"), (2, ":")]);
+
+    let unescaped = ':'.not_preceded_by('\\');
+    assert_eq!(split(r":a\:b:c", unescaped).collect::<Vec<_>>(), vec!["", r"a\:b", "c"]);
+    assert_eq!(rsplit(r":a\:b:c", unescaped).collect::<Vec<_>>(), vec!["c", r"a\:b", ""]);
+
+    // A rejected match may overlap with an accepted one.
+    assert_eq!(find_range("xaaa", "aa".preceded_by('a')), Some(2..4));
+    assert_eq!(rfind_range("aaax", "aa".not_preceded_by('x')), Some(1..3));
+}
+
+#[test]
+fn test_empty_matches() {
+    // Rejecting an empty match must not skip an empty match right after it.
+    assert_eq!(find("a b", WordBoundary.preceded_by('a')), Some(1));
+    assert_eq!(rfind("a b", WordBoundary.followed_by('b')), Some(2));
+    assert_eq!(
+        match_indices("ab ba", WordBoundary.not_followed_by('b')).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![0, 2, 5]
+    );
+
+    // Nor after a non-empty match.
+    let suffixes = any_of(("abx", "bx", "x", ""));
+    assert_eq!(find_range("abx", suffixes.preceded_by('b')), Some(2..3));
+    assert_eq!(find_range("abx", suffixes.preceded_by('x')), Some(3..3));
+    let prefixes = any_of(("xab", "xa", "x", ""));
+    assert_eq!(rfind_range("xab", prefixes.followed_by('a')), Some(0..1));
+    assert_eq!(rfind_range("xab", prefixes.followed_by('x')), Some(0..0));
+}
+
+#[test]
+fn test_followed_by() {
+    let minus = '-'.followed_by(|c: char| c.is_ascii_digit());
+    let mut res = String::new();
+    replace_with("a - b, -1 - -2", minus, |_| "~", |s| res.push_str(s));
+    assert_eq!(res, "a - b, ~1 - ~2");
+
+    let dash = '-'.not_followed_by('-');
+    assert_eq!(matches("a--b-c-", dash).count(), 3);
+    assert_eq!(rfind("a--b-c-", dash), Some(6));
+    assert_eq!(find_range("aaax", "aa".followed_by('x')), Some(1..3));
+}
+
+#[test]
+fn test_context_outside_range() {
+    // The context is looked up in the whole hay, even after splitting.
+    let needle = 'b'.preceded_by('a');
+    assert_eq!(split("abab", needle).collect::<Vec<_>>(), vec!["a", "a", ""]);
+
+    let mut searcher = Needle::<&str>::into_searcher(needle);
+    assert_eq!(searcher.search(unsafe { Span::from_parts("abab", 1..4) }), Some(1..2));
+    let mut searcher = Needle::<&str>::into_searcher('a'.followed_by('b'));
+    assert_eq!(searcher.rsearch(unsafe { Span::from_parts("abab", 0..3) }), Some(2..3));
+}
+
+#[test]
+fn test_consumer() {
+    assert!(starts_with("-1", '-'.followed_by('1')));
+    assert!(!starts_with("-2", '-'.followed_by('1')));
+    assert!(starts_with("x", 'x'.preceded_by("")));
+    assert!(!starts_with("x", 'x'.preceded_by('y')));
+    assert!(starts_with("x", 'x'.not_preceded_by('y')));
+    assert!(ends_with("ab", 'b'.preceded_by('a')));
+    assert_eq!(trim_start("aaab", 'a'.followed_by('a')), "ab");
+    assert_eq!(trim_end("baaa", 'a'.preceded_by('a')), "ba");
+}
+
+#[test]
+fn test_slices() {
+    let hay = &[1, 0, 2, 0, 0, 3][..];
+    assert_eq!(
+        split(hay, Elem(0).not_preceded_by(Elem(0)).not_followed_by(Elem(0))).collect::<Vec<_>>(),
+        vec![&[1][..], &[2, 0, 0, 3]]
+    );
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "unicode-normalization")]
+
+extern crate pattern_3;
+
+use pattern_3::*;
+use pattern_3::ext::*;
+
+#[test]
+fn test_composed_and_decomposed() {
+    let hay = "r\u{e9}sum\u{e9} = re\u{301}sume\u{301}";
+    let needle = NormalizationInsensitive("sum\u{e9}");
+    assert_eq!(
+        match_ranges(hay, needle).collect::<Vec<_>>(),
+        vec![(3..8, "sum\u{e9}"), (15..21, "sume\u{301}")]
+    );
+    assert_eq!(
+        rmatch_ranges(hay, needle).collect::<Vec<_>>(),
+        vec![(15..21, "sume\u{301}"), (3..8, "sum\u{e9}")]
+    );
+
+    let needle = NormalizationInsensitive("e\u{301}");
+    assert_eq!(
+        match_indices(hay, needle).collect::<Vec<_>>(),
+        vec![(1, "\u{e9}"), (6, "\u{e9}"), (12, "e\u{301}"), (18, "e\u{301}")]
+    );
+}
+
+#[test]
+fn test_canonical_ordering() {
+    // U+1E0B U+0323 ≡ U+1E0D U+0307 ≡ d U+0323 U+0307
+    let needle = NormalizationInsensitive("d\u{323}\u{307}");
+    assert_eq!(find("\u{1e0b}\u{323}", needle), Some(0));
+    assert_eq!(find("\u{1e0d}\u{307}", needle), Some(0));
+    assert_eq!(find("d\u{307}\u{323}", needle), Some(0));
+    assert_eq!(find("d\u{307}", needle), None);
+
+    // Hangul syllables decompose into conjoining jamo.
+    assert_eq!(find("한국어", NormalizationInsensitive("\u{1100}\u{116e}\u{11a8}")), Some(3));
+}
+
+#[test]
+fn test_no_split_combining_sequence() {
+    let needle = NormalizationInsensitive("e");
+    assert_eq!(find("e\u{301}e", needle), Some(3));
+    assert_eq!(rfind("ee\u{301}", needle), Some(0));
+    assert_eq!(find("\u{e9}", needle), None);
+
+    // The matched range covers the whole combining sequence.
+    let needle = NormalizationInsensitive("a\u{301}");
+    assert_eq!(find("a\u{301}\u{302}", needle), None);
+    assert_eq!(
+        split("xa\u{301}ya\u{301}", needle).collect::<Vec<_>>(),
+        vec!["x", "y", ""]
+    );
+
+    // A defective combining sequence at the start of the haystack.
+    let needle = NormalizationInsensitive("\u{301}");
+    assert_eq!(match_indices("\u{301}e\u{301}", needle).collect::<Vec<_>>(), vec![(0, "\u{301}")]);
+}
+
+#[test]
+fn test_empty_needle() {
+    let needle = NormalizationInsensitive("");
+    assert_eq!(
+        match_indices("ae\u{301}b", needle).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![0, 1, 4, 5]
+    );
+    assert_eq!(
+        rmatch_indices("ae\u{301}b", needle).map(|(i, _)| i).collect::<Vec<_>>(),
+        vec![5, 4, 1, 0]
+    );
+}
+
+#[test]
+fn test_consumer() {
+    let needle = NormalizationInsensitive("\u{c5}");
+    assert!(starts_with("A\u{30a}ngstr\u{f6}m", needle));
+    assert!(!starts_with("A\u{30a}\u{301}", needle));
+    assert_eq!(trim_start("\u{c5}A\u{30a}\u{212b}x", needle), "x");
+    assert_eq!(trim_end("x\u{212b}A\u{30a}", needle), "x");
+    assert!(ends_with("ohm \u{2126}", NormalizationInsensitive("\u{3a9}")));
+}
@@ -0,0 +1,106 @@
+extern crate pattern_3;
+
+use pattern_3::*;
+use pattern_3::ext::*;
+use std::sync::Arc;
+use std::thread;
+
+fn assert_send_sync<T: Send + Sync + 'static>(_: &T) {}
+
+#[test]
+fn test_string_needles() {
+    let hay = "abcabcabc abc";
+    for needle in &["abc", "cab", "", "x", "abc abc", "abcabcabc abcd"] {
+        let expected = match_ranges(hay, *needle).collect::<Vec<_>>();
+        let rexpected = rmatch_ranges(hay, *needle).collect::<Vec<_>>();
+
+        assert_eq!(match_ranges(hay, needle.to_string()).collect::<Vec<_>>(), expected, "{:?}", needle);
+        assert_eq!(match_ranges(hay, Box::<str>::from(*needle)).collect::<Vec<_>>(), expected);
+        assert_eq!(match_ranges(hay, Arc::<str>::from(*needle)).collect::<Vec<_>>(), expected);
+        assert_eq!(rmatch_ranges(hay, needle.to_string()).collect::<Vec<_>>(), rexpected);
+        assert_eq!(rmatch_ranges(hay, Arc::<str>::from(*needle)).collect::<Vec<_>>(), rexpected);
+
+        assert_eq!(starts_with(hay, needle.to_string()), starts_with(hay, *needle));
+        assert_eq!(ends_with(hay, Box::<str>::from(*needle)), ends_with(hay, *needle));
+    }
+}
+
+#[test]
+fn test_string_haystacks() {
+    let mut hay = "ö,ü,ä".to_owned();
+    assert_eq!(split(&*hay, ",".to_owned()).collect::<Vec<_>>(), vec!["ö", "ü", "ä"]);
+    assert_eq!(trim_start(&*hay, "ö".to_owned()), ",ü,ä");
+    assert_eq!(trim_end(&*hay, Arc::<str>::from("ä")), "ö,ü,");
+    let mut replaced = String::new();
+    replace_with(&*hay, String::from("ü"), |_| "u", |s| replaced.push_str(s));
+    assert_eq!(replaced, "ö,u,ä");
+    assert_eq!(find(&mut *hay, Box::<str>::from(",ä")), Some(5));
+}
+
+#[test]
+fn test_vec_needles() {
+    let hay = &[1, 2, 1, 2, 1, 3, 1, 2][..];
+    for needle in &[&[1, 2][..], &[2, 1], &[], &[4], &[1, 2, 1]] {
+        let expected = match_ranges(hay, *needle).collect::<Vec<_>>();
+        let rexpected = rmatch_ranges(hay, *needle).collect::<Vec<_>>();
+
+        assert_eq!(match_ranges(hay, needle.to_vec()).collect::<Vec<_>>(), expected, "{:?}", needle);
+        assert_eq!(match_ranges(hay, needle.to_vec().into_boxed_slice()).collect::<Vec<_>>(), expected);
+        assert_eq!(rmatch_ranges(hay, needle.to_vec()).collect::<Vec<_>>(), rexpected);
+        assert_eq!(starts_with(hay, needle.to_vec()), starts_with(hay, *needle));
+        assert_eq!(ends_with(hay, needle.to_vec()), ends_with(hay, *needle));
+    }
+
+    let words = ["a".to_owned(), "--".to_owned(), "b".to_owned()];
+    assert_eq!(find(&words[..], vec!["--".to_owned()]), Some(1));
+    assert_eq!(split(vec![0u8, 1, 0, 1], vec![0u8, 1]).collect::<Vec<_>>(), vec![vec![], vec![], vec![]]);
+}
+
+#[test]
+fn test_searcher_keeps_state() {
+    // the two-way state is saved after every call, and must not leak into
+    // the search of another haystack.
+    let mut searcher = Needle::<&str>::into_searcher("aab".to_owned());
+    assert_eq!(searcher.search(Span::from("aaab")), Some(1..4));
+    assert_eq!(searcher.search(Span::from("aa")), None);
+    assert_eq!(searcher.search(Span::from("ab")), None);
+    assert_eq!(searcher.rsearch(Span::from("aabaab")), Some(3..6));
+    assert_eq!(searcher.search(Span::from("xaab")), Some(1..4));
+
+    let mut searcher = Needle::<&[u8]>::into_searcher(b"aab".to_vec());
+    assert_eq!(searcher.search(Span::from(&b"aaab"[..])), Some(1..4));
+    assert_eq!(searcher.rsearch(Span::from(&b"aabaab"[..])), Some(3..6));
+}
+
+#[test]
+fn test_long_lived_struct() {
+    struct Highlighter {
+        searchers: Vec<OwnedSliceSearcher<String>>,
+    }
+
+    let mut h = Highlighter {
+        searchers: ["fn", "let", ""].iter().map(|k| Needle::<&str>::into_searcher(k.to_string())).collect(),
+    };
+    let line = "let f = fn() {}";
+    let found = h.searchers.iter_mut().map(|s| s.search(Span::from(line))).collect::<Vec<_>>();
+    assert_eq!(found, vec![Some(8..10), Some(0..3), Some(0..0)]);
+}
+
+#[test]
+fn test_send_across_threads() {
+    let mut searcher = Needle::<&str>::into_searcher(Arc::<str>::from("needle"));
+    let consumer = Needle::<&str>::into_consumer("needle".to_owned());
+    let vec_searcher = Needle::<&[u32]>::into_searcher(vec![1u32, 2]);
+    assert_send_sync(&searcher);
+    assert_send_sync(&consumer);
+    assert_send_sync(&vec_searcher);
+
+    assert_eq!(searcher.search(Span::from("a needle")), Some(2..8));
+    let handle = thread::spawn(move || {
+        let mut consumer = consumer;
+        let a = searcher.search(Span::from("haystack with a needle")).map(|r| r.start);
+        let b = consumer.consume(Span::from("needles"));
+        (a, b)
+    });
+    assert_eq!(handle.join().unwrap(), (Some(16), Some(6)));
+}
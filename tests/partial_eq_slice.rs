@@ -61,4 +61,21 @@ fn test_negative_zero() {
     ]);
 }
 
+#[test]
+fn test_overlapping_prefix() {
+    let haystack = &[1.0, 1.0, 2.0, 1.0, 2.0, 2.0, 1.0][..];
+    let needle = &[1.0, 2.0][..];
+
+    assert_eq!(match_indices(haystack, needle).collect::<Vec<_>>(), vec![
+        (1, needle),
+        (3, needle),
+    ]);
+    assert_eq!(rmatch_indices(haystack, needle).collect::<Vec<_>>(), vec![
+        (3, needle),
+        (1, needle),
+    ]);
+    assert_eq!(find(haystack, &[2.0, 2.0, 1.0][..]), Some(4));
+    assert_eq!(rfind(haystack, &[1.0, 1.0][..]), Some(0));
+}
+
 fn main() {}
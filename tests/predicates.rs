@@ -0,0 +1,92 @@
+extern crate pattern_3;
+
+mod common;
+
+use common::Lcg;
+use pattern_3::*;
+use pattern_3::ext::*;
+
+#[test]
+fn test_bytes() {
+    let needle = Predicates(&[u8::is_ascii_digit, u8::is_ascii_digit, |b: &u8| *b == b':']);
+    let hay = &b"12:34:56 7:8 90:"[..];
+    assert_eq!(
+        match_ranges(hay, needle).map(|(r, _)| r).collect::<Vec<_>>(),
+        vec![0..3, 3..6, 13..16]
+    );
+    assert_eq!(
+        rmatch_ranges(hay, needle).map(|(r, _)| r).collect::<Vec<_>>(),
+        vec![13..16, 3..6, 0..3]
+    );
+    assert!(starts_with(hay, needle));
+    assert!(ends_with(hay, needle));
+    assert_eq!(trim_start(hay, needle), &b"56 7:8 90:"[..]);
+    assert_eq!(trim_end(&b"x12:"[..], needle), &b"x"[..]);
+    assert_eq!(split(hay, needle).collect::<Vec<_>>(), vec![&b""[..], b"", b"56 7:8 ", b""]);
+}
+
+#[test]
+fn test_str() {
+    let needle = Predicates(&[char::is_alphabetic, char::is_numeric, |c: char| c == '-']);
+    let hay = "é1- x2-yz3- 4-";
+    assert_eq!(
+        match_ranges(hay, needle).collect::<Vec<_>>(),
+        vec![(0..4, "é1-"), (5..8, "x2-"), (9..12, "z3-")]
+    );
+    assert_eq!(rfind(hay, needle), Some(9));
+    assert!(starts_with(hay, needle));
+    assert!(!ends_with(hay, needle));
+    assert_eq!(trim_end("xé1-", needle), "x");
+
+    let mut owned = String::from("a1-b2-");
+    let mut_hay: &mut str = &mut owned;
+    assert_eq!(split(mut_hay, needle).count(), 3);
+}
+
+#[test]
+fn test_empty() {
+    let none: &[fn(char) -> bool] = &[];
+    assert_eq!(
+        match_ranges("ab", Predicates(none)).map(|(r, _)| r).collect::<Vec<_>>(),
+        vec![0..0, 1..1, 2..2]
+    );
+    let none: &[fn(&u8) -> bool] = &[];
+    assert_eq!(
+        rmatch_ranges(&b"ab"[..], Predicates(none)).map(|(r, _)| r).collect::<Vec<_>>(),
+        vec![2..2, 1..1, 0..0]
+    );
+}
+
+#[test]
+fn test_against_slices() {
+    // A sequence of equality predicates behaves like the slice itself.
+    let mut rng = Lcg(19);
+    let alphabet = ['a', 'b', 'é'];
+    for _ in 0..2000 {
+        let hay = (0..rng.next(12)).map(|_| alphabet[rng.next(3) as usize]).collect::<String>();
+        let needle = (0..(1 + rng.next(3))).map(|_| alphabet[rng.next(3) as usize]).collect::<Vec<_>>();
+        let predicates = needle.iter().map(|&n| move |c: char| c == n).collect::<Vec<_>>();
+        let needle_str = needle.iter().collect::<String>();
+
+        assert_eq!(
+            match_ranges(&*hay, Predicates(&predicates)).collect::<Vec<_>>(),
+            match_ranges(&*hay, &*needle_str).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            rmatch_ranges(&*hay, Predicates(&predicates)).collect::<Vec<_>>(),
+            rmatch_ranges(&*hay, &*needle_str).collect::<Vec<_>>()
+        );
+        assert_eq!(starts_with(&*hay, Predicates(&predicates)), hay.starts_with(&*needle_str));
+        assert_eq!(ends_with(&*hay, Predicates(&predicates)), hay.ends_with(&*needle_str));
+
+        let bytes = needle_str.as_bytes().iter().map(|&n| move |b: &u8| *b == n).collect::<Vec<_>>();
+        assert_eq!(
+            match_ranges(hay.as_bytes(), Predicates(&bytes)).collect::<Vec<_>>(),
+            match_ranges(hay.as_bytes(), needle_str.as_bytes()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            rmatch_ranges(hay.as_bytes(), Predicates(&bytes)).collect::<Vec<_>>(),
+            rmatch_ranges(hay.as_bytes(), needle_str.as_bytes()).collect::<Vec<_>>()
+        );
+    }
+}
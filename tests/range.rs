@@ -0,0 +1,57 @@
+extern crate pattern_3;
+
+use pattern_3::ext::*;
+
+#[test]
+fn test_char_range_ascii() {
+    assert_eq!(trim_start("0042abc", '0'..='9'), "abc");
+    assert_eq!(trim_end("abc123", '0'..='9'), "abc");
+    assert_eq!(trim("12ab34", '0'..'3'), "ab34");
+    assert_eq!(
+        match_indices("a1b22c", '0'..='9').collect::<Vec<_>>(),
+        vec![(1, "1"), (3, "2"), (4, "2")]
+    );
+    assert_eq!(
+        rsplit("é1ü2ö", '0'..='9').collect::<Vec<_>>(),
+        vec!["ö", "ü", "é"]
+    );
+    assert!(starts_with("x", 'a'..='z'));
+    assert!(!starts_with("é", 'a'..='z'));
+    assert!(ends_with("X", 'A'..'\u{80}'));
+}
+
+#[test]
+fn test_char_range_unicode() {
+    let hiragana = '\u{3041}'..='\u{309f}';
+    assert_eq!(
+        split("東京はとても大きい", hiragana.clone()).filter(|s| !s.is_empty()).collect::<Vec<_>>(),
+        vec!["東京", "大"]
+    );
+    assert_eq!(rfind("東京はとても大きい", hiragana.clone()), Some(24));
+    assert_eq!(trim("ですDesu", hiragana), "Desu");
+    assert_eq!(find("a\u{10ffff}", 'b'..=char::MAX), Some(1));
+}
+
+#[test]
+fn test_char_range_empty() {
+    assert_eq!(find("abc", 'b'..'b'), None);
+    assert_eq!(find("abc", 'c'..'a'), None);
+    assert_eq!(trim("abc", '\0'..'\0'), "abc");
+}
+
+#[test]
+fn test_slice_range() {
+    let hay = &[5, 12, 19, 20, 3, 10][..];
+    assert_eq!(
+        matches(hay, 10..20).collect::<Vec<_>>(),
+        vec![&[12][..], &[19], &[10]]
+    );
+    assert_eq!(rfind(hay, 10..=20), Some(5));
+    assert_eq!(trim_start(hay, 0..=12), &[19, 20, 3, 10]);
+    assert_eq!(trim_end(hay, 3..11), &[5, 12, 19, 20]);
+    assert_eq!(
+        split(vec![1.5, -0.5, 2.5, 0.0], -1.0..=0.0).collect::<Vec<_>>(),
+        vec![vec![1.5], vec![2.5], vec![]]
+    );
+    assert_eq!(find(&["apple", "kiwi", "pear"][..], "b".."l"), Some(1));
+}
@@ -0,0 +1,138 @@
+#![cfg(feature = "regex")]
+
+extern crate pattern_3;
+extern crate regex;
+
+use pattern_3::*;
+use pattern_3::combinators::NeedleExt;
+use pattern_3::ext::*;
+use regex::{bytes, Regex, RegexBuilder};
+
+type Case = (&'static str, &'static str, &'static [(usize, usize)]);
+
+// Same cases as `tests/regex.rs`.
+const CASES: &[Case] = &[
+    (r"", "", &[(0, 0)]),
+    (r"", "ab", &[(0, 0), (1, 1), (2, 2)]),
+    (r"\d", "", &[]),
+    (r"\d", "5", &[(0, 1)]),
+    (r"\d", "a", &[]),
+    (r"\d", "56", &[(0, 1), (1, 2)]),
+    (r"\d", "5a6", &[(0, 1), (2, 3)]),
+    (r"\d", "a6", &[(1, 2)]),
+    (r"\d*", "a1b2", &[(0, 0), (1, 2), (3, 4)]),
+    (r"\d*", "a1bbb2", &[(0, 0), (1, 2), (3, 3), (4, 4), (5, 6)]),
+    (r".+?", "Ⅰ1Ⅱ2", &[(0, 3), (3, 4), (4, 7), (7, 8)]),
+    (r"^a", "aaa", &[(0, 1)]),
+    (r"\b", "hello::world", &[(0, 0), (5, 5), (7, 7), (12, 12)]),
+];
+
+#[test]
+fn test_match_ranges() {
+    for &(re, hay, expected) in CASES {
+        let regex = Regex::new(re).unwrap();
+        let actual = match_ranges(hay, &regex).map(|(r, _)| (r.start, r.end)).collect::<Vec<_>>();
+        assert_eq!(actual, expected, "{:?} {:?}", re, hay);
+
+        let mut actual = rmatch_ranges(hay, &regex).map(|(r, _)| (r.start, r.end)).collect::<Vec<_>>();
+        actual.reverse();
+        assert_eq!(actual, expected, "{:?} {:?}", re, hay);
+
+        let regex = bytes::Regex::new(re).unwrap();
+        let actual = match_ranges(hay.as_bytes(), &regex).map(|(r, _)| (r.start, r.end)).collect::<Vec<_>>();
+        assert_eq!(actual, expected, "{:?} {:?}", re, hay);
+    }
+}
+
+#[test]
+fn test_agrees_with_find_iter() {
+    let regexes = ["a|b*", r"\w+", r"(?m)^|$", r"\bb", "x?", "[^a]"];
+    let hays = ["", "ab", "a\nbb a", "bba ab", "xaxx", "ééb"];
+    for re in &regexes {
+        let regex = Regex::new(re).unwrap();
+        for hay in &hays {
+            let expected = regex.find_iter(hay).map(|m| (m.start(), m.end())).collect::<Vec<_>>();
+            let actual = match_ranges(*hay, &regex).map(|(r, _)| (r.start, r.end)).collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{:?} {:?}", re, hay);
+        }
+    }
+}
+
+#[test]
+fn test_split_and_replace() {
+    let regex = Regex::new(r"\s*,\s*").unwrap();
+    assert_eq!(split("a , b,c ,d", &regex).collect::<Vec<_>>(), vec!["a", "b", "c", "d"]);
+    assert_eq!(rsplitn("a , b,c ,d", 2, &regex).collect::<Vec<_>>(), vec!["d", "a , b,c"]);
+    assert_eq!(rfind("a , b,c ,d", &regex), Some(7));
+
+    let mut res = String::new();
+    replace_with("x1y22", &Regex::new(r"\d+").unwrap(), |_| "#", |s| res.push_str(s));
+    assert_eq!(res, "x#y#");
+
+    let regex = bytes::Regex::new(r"(?-u)\xff+").unwrap();
+    assert_eq!(split(&b"a\xffb\xff\xffc"[..], &regex).collect::<Vec<_>>(), vec![&b"a"[..], b"b", b"c"]);
+}
+
+#[test]
+fn test_consume_agrees_with_find_at() {
+    let regexes = ["a|b*", r"\w+", r"(?m)^b|$", r"\bb", r"\Ab", "x?", "[^a]"];
+    let hays = ["", "ab", "a\nbb a", "bba ab", "xaxx", "ééb"];
+    for re in &regexes {
+        let regex = Regex::new(re).unwrap();
+        let mut consumer = Needle::<&str>::into_consumer(&regex);
+        for hay in &hays {
+            for (i, _) in hay.char_indices().chain(Some((hay.len(), ' '))) {
+                let expected = regex.find_at(hay, i).filter(|m| m.start() == i).map(|m| m.end());
+                let actual = consumer.consume(unsafe { Span::from_parts(*hay, i..hay.len()) });
+                assert_eq!(actual, expected, "{:?} {:?} {}", re, hay, i);
+            }
+        }
+
+        let regex = bytes::Regex::new(re).unwrap();
+        let mut consumer = Needle::<&[u8]>::into_consumer(&regex);
+        for hay in &[&b"\xa9b"[..], b"\xc3\xa9b", b"a\xffb\n"] {
+            for i in 0..=hay.len() {
+                let expected = regex.find_at(hay, i).filter(|m| m.start() == i).map(|m| m.end());
+                let actual = consumer.consume(unsafe { Span::from_parts(*hay, i..hay.len()) });
+                assert_eq!(actual, expected, "{:?} {:?} {}", re, hay, i);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_builder_options() {
+    let regex = RegexBuilder::new("abc").case_insensitive(true).build().unwrap();
+    assert_eq!(find("ABCx", &regex), Some(0));
+    assert!(starts_with("ABCx", &regex));
+    assert!(!starts_with("xABC", &regex));
+    assert_eq!(trim_start("aBcAbCx", &regex), "x");
+
+    let regex = bytes::RegexBuilder::new("a.").dot_matches_new_line(true).build().unwrap();
+    assert!(starts_with(&b"a\nb"[..], &regex));
+    assert_eq!(trim_start(&b"a\na\nx"[..], &regex), b"x");
+}
+
+#[test]
+fn test_lookaround() {
+    let regex = Regex::new("b*").unwrap();
+    assert_eq!(find_range("abx", (&regex).preceded_by('x')), Some(3..3));
+    assert_eq!(find_range("abx", (&regex).preceded_by('a')), Some(1..2));
+    assert_eq!(rfind_range("abx", (&regex).followed_by('a')), Some(0..0));
+}
+
+#[test]
+fn test_consumer() {
+    let regex = Regex::new("a*").unwrap();
+    assert_eq!(trim_start("aaabbb", &regex), "bbb");
+    let regex = Regex::new("^a").unwrap();
+    assert_eq!(trim_start("aaabbb", &regex), "aabbb");
+    let regex = Regex::new("b|ab").unwrap();
+    assert!(starts_with("abc", &regex));
+    assert!(!starts_with("cab", &regex));
+
+    // The match must not extend beyond the searched range.
+    let regex = Regex::new(r"\d+").unwrap();
+    let mut consumer = Needle::<&str>::into_consumer(&regex);
+    assert_eq!(consumer.consume(unsafe { Span::from_parts("12345", 1..3) }), Some(3));
+}
@@ -0,0 +1,98 @@
+extern crate pattern_3;
+
+mod common;
+
+use common::Lcg;
+use pattern_3::*;
+use pattern_3::ext::*;
+
+#[test]
+fn test_byte_set_against_naive() {
+    let mut rng = Lcg(3);
+    for _ in 0..500 {
+        let set_len = rng.next(8);
+        let set = (0..set_len).map(|_| rng.next(12) as u8 * 23).collect::<Vec<_>>();
+        let hay = (0..rng.next(40)).map(|_| rng.next(12) as u8 * 23).collect::<Vec<_>>();
+        let needle = ByteSet::new(&set);
+
+        let expected = hay.iter().enumerate().filter(|(_, b)| set.contains(b)).map(|(i, _)| i).collect::<Vec<_>>();
+        let actual = match_indices(&*hay, needle).map(|(i, _)| i).collect::<Vec<_>>();
+        assert_eq!(actual, expected, "{:?} in {:?}", set, hay);
+        let mut actual = rmatch_indices(&*hay, needle).map(|(i, _)| i).collect::<Vec<_>>();
+        actual.reverse();
+        assert_eq!(actual, expected, "{:?} in {:?}", set, hay);
+
+        let trimmed = hay.iter().position(|b| !set.contains(b)).unwrap_or(hay.len());
+        assert_eq!(trim_start(&*hay, needle), &hay[trimmed..]);
+    }
+}
+
+#[test]
+fn test_byte_set() {
+    let needle = ByteSet::new(b"aa");
+    assert!(needle.contains(b'a'));
+    assert!(!needle.contains(b'b'));
+    assert_eq!(rfind(&b"banana"[..], needle), Some(5));
+
+    let needle = ByteSet::new(b"");
+    assert_eq!(find(&b"abc"[..], needle), None);
+    assert_eq!(trim(&b"abc"[..], needle), b"abc");
+
+    let needle = ByteSet::new(&[0, 0x80, 0xff, 0x7f]);
+    assert_eq!(
+        split(vec![1, 0xff, 2, 0x80, 0x80, 3], needle).collect::<Vec<_>>(),
+        vec![vec![1], vec![2], vec![], vec![3]]
+    );
+    assert_eq!(trim_end(&[1, 0, 0x7f][..], needle), &[1]);
+}
+
+#[test]
+fn test_char_set_ascii() {
+    let cases: &[(&[char], &str)] = &[
+        (&[','], "ü;ö:ä.ß"),
+        (&[',', ';'], "ö:ä.ß"),
+        (&[',', ';', ':'], "ä.ß"),
+        (&[',', ';', ':', '.'], "ß"),
+    ];
+    for &(chars, last) in cases {
+        let set = CharSet::new(chars);
+        assert_eq!(
+            split("é,ü;ö:ä.ß", &set).count(),
+            chars.len() + 1
+        );
+        assert_eq!(
+            rsplit("é,ü;ö:ä.ß", &set).next(),
+            Some(last)
+        );
+        assert!(starts_with(",", &set));
+        assert!(ends_with("x,", &set));
+        assert_eq!(trim(",,é,,", &set), "é");
+    }
+
+    let set = CharSet::new(&[]);
+    assert_eq!(find("abc", &set), None);
+    assert_eq!(trim("abc", &set), "abc");
+}
+
+#[test]
+fn test_char_set_non_ascii() {
+    let chars = "αβγδεζηθικλμνξοπρστυφχψω ,".chars().collect::<Vec<_>>();
+    let set = CharSet::new(&chars);
+    assert!(set.contains('λ'));
+    assert!(set.contains(' '));
+    assert!(!set.contains('Λ'));
+    assert!(!set.contains('a'));
+
+    let hay = "ABΓ, δEΖ";
+    assert_eq!(
+        match_indices(hay, &set).collect::<Vec<_>>(),
+        vec![(4, ","), (5, " "), (6, "δ")]
+    );
+    assert_eq!(
+        rmatch_indices(hay, &set).collect::<Vec<_>>(),
+        vec![(6, "δ"), (5, " "), (4, ",")]
+    );
+    assert_eq!(trim_start("αβ, γΓ", &set), "Γ");
+    assert_eq!(trim_end("Γαβ, γ", &set), "Γ");
+    assert_eq!(trim("ω", &set), "");
+}
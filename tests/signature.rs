@@ -0,0 +1,107 @@
+extern crate pattern_3;
+
+mod common;
+
+use common::{naive_matches, Lcg};
+use pattern_3::*;
+use pattern_3::ext::*;
+
+#[test]
+fn test_parse() {
+    let sig = "4D 5a ?? 9? ?f".parse::<Signature>().unwrap();
+    assert_eq!(sig.len(), 5);
+    assert_eq!(sig.to_string(), "4D 5A ?? 9? ?F");
+    assert!(sig.matches(b"\x4d\x5a\x00\x90\x0f"));
+    assert!(sig.matches(b"\x4d\x5a\xff\x9f\xff"));
+    assert!(!sig.matches(b"\x4d\x5a\x00\xa0\x0f"));
+    assert!(!sig.matches(b"\x4d\x5a\x00\x90\x0e"));
+    assert!(!sig.matches(b"\x4d\x5a\x00\x90"));
+
+    assert_eq!("4D5A\n????".parse::<Signature>().unwrap(), "4D 5A ?? ??".parse().unwrap());
+    assert_eq!(
+        Signature::new(&[Some(0x4d), None]),
+        "4D ??".parse().unwrap()
+    );
+    assert!("".parse::<Signature>().unwrap().is_empty());
+
+    let err = "4D 5G".parse::<Signature>().unwrap_err();
+    assert_eq!(err.position(), Some(4));
+    assert_eq!(err.to_string(), "invalid character in signature at 4");
+    let err = "4D 5".parse::<Signature>().unwrap_err();
+    assert_eq!(err.position(), None);
+}
+
+#[test]
+fn test_against_naive() {
+    let mut rng = Lcg(11);
+    let digits = ["0", "1", "?", "0?", "?1", "??", "01", "10"];
+    for _ in 0..2000 {
+        let pattern = (0..rng.next(5))
+            .map(|_| {
+                let d = digits[rng.next(digits.len() as u64) as usize];
+                if d.len() == 2 { d.to_owned() } else { format!("0{}", d) }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let sig = pattern.parse::<Signature>().unwrap();
+        let hay = (0..rng.next(16)).map(|_| [0x00, 0x01, 0x10, 0x11][rng.next(4) as usize]).collect::<Vec<u8>>();
+        let len = sig.len();
+
+        if len != 0 {
+            let expected = naive_matches(hay.len(), len, false, |i| sig.matches(&hay[i..(i + len)]));
+            let actual = match_ranges(&*hay, &sig).map(|(r, _)| r).collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{:?} {:?}", pattern, hay);
+        }
+
+        let first = (0..hay.len().saturating_sub(len) + 1)
+            .find(|&i| i + len <= hay.len() && sig.matches(&hay[i..(i + len)]));
+        assert_eq!(find(&*hay, &sig), first, "{:?} {:?}", pattern, hay);
+        let last = (0..hay.len().saturating_sub(len) + 1)
+            .rev()
+            .find(|&i| i + len <= hay.len() && sig.matches(&hay[i..(i + len)]));
+        assert_eq!(rfind(&*hay, &sig), last, "{:?} {:?}", pattern, hay);
+        assert_eq!(contains(&*hay, &sig), first.is_some());
+
+        let prefix = hay.len() >= len && sig.matches(&hay[..len]);
+        assert_eq!(starts_with(&*hay, &sig), prefix, "{:?} {:?}", pattern, hay);
+        let suffix = hay.len() >= len && sig.matches(&hay[(hay.len() - len)..]);
+        assert_eq!(ends_with(&*hay, &sig), suffix, "{:?} {:?}", pattern, hay);
+    }
+}
+
+#[test]
+fn test_empty() {
+    let sig = Signature::new(&[]);
+    assert_eq!(
+        match_ranges(&b"ab"[..], &sig).map(|(r, _)| r).collect::<Vec<_>>(),
+        vec![0..0, 1..1, 2..2]
+    );
+    assert_eq!(
+        rmatch_ranges(&b"ab"[..], &sig).map(|(r, _)| r).collect::<Vec<_>>(),
+        vec![2..2, 1..1, 0..0]
+    );
+}
+
+#[test]
+fn test_firmware() {
+    let mut image = vec![0xffu8; 64];
+    image[10..16].copy_from_slice(b"\x4d\x5a\x00\x00\x90\x00");
+    image[40..46].copy_from_slice(b"\x4d\x5a\x12\x34\x90\x00");
+    image[50..54].copy_from_slice(b"\x7fELF");
+
+    let pe = "4D 5A ?? ?? 90 00".parse::<Signature>().unwrap();
+    let elf = "7F 45 4C 46".parse::<Signature>().unwrap();
+    assert_eq!(
+        match_ranges(&*image, &pe).map(|(r, _)| r).collect::<Vec<_>>(),
+        vec![10..16, 40..46]
+    );
+    assert_eq!(rfind(&*image, &pe), Some(40));
+    assert_eq!(find(&*image, &elf), Some(50));
+    assert!(starts_with(&image[40..], &pe));
+    assert_eq!(split(&*image, &pe).count(), 3);
+
+    // A signature without any exact byte.
+    let wild = "?F F?".parse::<Signature>().unwrap();
+    assert_eq!(find(&*image, &wild), Some(0));
+    assert_eq!(find(&image[10..], &wild), Some(6));
+}
@@ -0,0 +1,79 @@
+extern crate pattern_3;
+
+mod common;
+
+use common::Lcg;
+use pattern_3::*;
+use pattern_3::ext::*;
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+
+fn expected<R: RangeBounds<u32>>(hay: &[u32], range: &R) -> Option<std::ops::Range<usize>> {
+    let start = hay.iter().position(|x| range.contains(x))?;
+    let len = hay[start..].iter().take_while(|x| range.contains(x)).count();
+    Some(start..(start + len))
+}
+
+#[test]
+fn test_against_linear() {
+    let mut rng = Lcg(23);
+    for _ in 0..2000 {
+        let mut hay = (0..rng.next(12)).map(|_| rng.next(6) as u32).collect::<Vec<_>>();
+        hay.sort();
+        let key = rng.next(7) as u32;
+        let lo = rng.next(7) as u32;
+        let hi = rng.next(7) as u32;
+
+        assert_eq!(find_range(&*hay, Sorted(key)), expected(&hay, &(key..=key)), "{:?} {}", hay, key);
+        assert_eq!(rfind_range(&*hay, Sorted(key)), expected(&hay, &(key..=key)));
+        assert_eq!(find_range(&*hay, SortedRange(lo..hi)), expected(&hay, &(lo..hi)), "{:?} {}..{}", hay, lo, hi);
+        assert_eq!(find_range(&*hay, SortedRange(lo..=hi)), expected(&hay, &(lo..=hi)));
+        assert_eq!(find_range(&*hay, SortedRange(..hi)), expected(&hay, &(..hi)));
+        let excluded = (Bound::Excluded(lo), Bound::Unbounded);
+        assert_eq!(find_range(&*hay, SortedRange(excluded)), expected(&hay, &excluded));
+
+        let count = hay.iter().filter(|&&x| x == key).count();
+        assert_eq!(matches(&*hay, Sorted(key)).count(), (count > 0) as usize);
+        assert_eq!(starts_with(&*hay, Sorted(key)), hay.first() == Some(&key));
+        assert_eq!(ends_with(&*hay, Sorted(key)), hay.last() == Some(&key));
+        assert_eq!(trim(&*hay, SortedRange(..lo)), &hay[hay.iter().filter(|&&x| x < lo).count()..]);
+    }
+}
+
+#[test]
+fn test_split() {
+    let hay = vec![1, 1, 2, 3, 3, 3, 4];
+    assert_eq!(split(&*hay, Sorted(3)).collect::<Vec<_>>(), vec![&[1, 1, 2][..], &[4]]);
+    assert_eq!(rsplit(&*hay, Sorted(1)).collect::<Vec<_>>(), vec![&[2, 3, 3, 3, 4][..], &[]]);
+    assert_eq!(split(&*hay, SortedRange(2..=3)).collect::<Vec<_>>(), vec![&[1, 1][..], &[4]]);
+    assert_eq!(split(hay, Sorted(7)).collect::<Vec<_>>(), vec![vec![1, 1, 2, 3, 3, 3, 4]]);
+
+    let words = ["apple", "banana", "cherry", "cranberry", "date"];
+    assert_eq!(find_range(&words[..], SortedRange("c".."d")), Some(2..4));
+}
+
+/// Counts the comparisons made while searching.
+#[derive(Debug, PartialEq, Eq)]
+struct Counted<'a>(u32, &'a Cell<usize>);
+
+impl<'a> PartialOrd for Counted<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> Ord for Counted<'a> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.1.set(self.1.get() + 1);
+        self.0.cmp(&other.0)
+    }
+}
+
+#[test]
+fn test_logarithmic() {
+    let comparisons = Cell::new(0);
+    let hay = (0..(1 << 16)).map(|i| Counted(i / 4, &comparisons)).collect::<Vec<_>>();
+    assert_eq!(find_range(&*hay, Sorted(Counted(1000, &comparisons))), Some(4000..4004));
+    assert!(comparisons.get() <= 2 * 17, "{} comparisons", comparisons.get());
+}
@@ -690,3 +690,18 @@ fn test_mut_str() {
     }
 }
 
+#[test]
+fn test_searcher_restart() {
+    // A searcher may be restarted on any other range, e.g. when an
+    // alternation advances past a position, so a partial match left behind
+    // by a failed search must not carry over.
+    let mut searcher = Needle::<&str>::into_searcher("abab");
+    assert_eq!(searcher.rsearch(Span::from("aabaaab")), None);
+    assert_eq!(searcher.rsearch(unsafe { Span::from_parts("abababab", 0..5) }), Some(0..4));
+    assert_eq!(searcher.rsearch(unsafe { Span::from_parts("aabaab", 0..5) }), None);
+
+    let mut searcher = Needle::<&str>::into_searcher("abab");
+    assert_eq!(searcher.search(unsafe { Span::from_parts("abababab", 0..7) }), Some(0..4));
+    assert_eq!(searcher.search(unsafe { Span::from_parts("xabab", 1..4) }), None);
+    assert_eq!(searcher.search(unsafe { Span::from_parts("abababab", 2..8) }), Some(2..6));
+}
@@ -0,0 +1,217 @@
+extern crate pattern_3;
+
+mod common;
+
+use common::Lcg;
+use pattern_3::*;
+use pattern_3::ext::*;
+use std::ops::Range;
+
+/// The longest pattern which is a prefix of `s`, with the lowest index.
+fn string(rng: &mut Lcg, alphabet: &[&str], max_len: u64) -> String {
+    let len = rng.next(max_len + 1);
+    rng.pick(alphabet, len as usize).concat()
+}
+
+fn longest_prefix(patterns: &[String], s: &str) -> Option<(usize, usize)> {
+    patterns
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| s.starts_with(&***p))
+        .map(|(i, p)| (p.len(), !i))
+        .max()
+        .map(|(len, i)| (len, !i))
+}
+
+fn longest_suffix(patterns: &[String], s: &str) -> Option<(usize, usize)> {
+    patterns
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| s.ends_with(&***p))
+        .map(|(i, p)| (p.len(), !i))
+        .max()
+        .map(|(len, i)| (len, !i))
+}
+
+fn naive_matches(patterns: &[String], hay: &str) -> Vec<(Range<usize>, usize)> {
+    let mut result = Vec::new();
+    let mut pos = 0;
+    loop {
+        let found = hay
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(Some(hay.len()))
+            .filter(|&i| i >= pos)
+            .filter_map(|i| longest_prefix(patterns, &hay[i..]).map(|(len, index)| (i..(i + len), index)))
+            .find(|(r, _)| !(r.start == pos && r.end == pos && !result.is_empty()));
+        match found {
+            Some((r, index)) => {
+                pos = r.end;
+                result.push((r, index));
+            }
+            None => return result,
+        }
+    }
+}
+
+fn naive_rmatches(patterns: &[String], hay: &str) -> Vec<(Range<usize>, usize)> {
+    let mut result = Vec::new();
+    let mut pos = hay.len();
+    loop {
+        let found = hay
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(Some(hay.len()))
+            .rev()
+            .filter(|&i| i <= pos)
+            .filter_map(|i| longest_suffix(patterns, &hay[..i]).map(|(len, index)| ((i - len)..i, index)))
+            .find(|(r, _)| !(r.start == pos && r.end == pos && !result.is_empty()));
+        match found {
+            Some((r, index)) => {
+                pos = r.start;
+                result.push((r, index));
+            }
+            None => return result,
+        }
+    }
+}
+
+#[test]
+fn test_against_naive() {
+    let mut rng = Lcg(29);
+    let alphabet = ["a", "b", "é"];
+    for _ in 0..1500 {
+        let patterns = (0..(1 + rng.next(4))).map(|_| string(&mut rng, &alphabet, 3)).collect::<Vec<_>>();
+        let hay = string(&mut rng, &alphabet, 10);
+        let trie = Trie::<u8, str>::new(&patterns);
+
+        let mut searcher = Needle::<&str>::into_searcher(&trie);
+        let mut actual = Vec::new();
+        let mut span = Span::from(&*hay);
+        while let Some(r) = searcher.search(span.clone()) {
+            actual.push((r.clone(), searcher.pattern_index().unwrap()));
+            let (h, range) = span.into_parts();
+            if r.end == range.end {
+                break;
+            }
+            span = unsafe { Span::from_parts(h, r.end..range.end) };
+        }
+        assert_eq!(actual, naive_matches(&patterns, &hay), "{:?} {:?}", patterns, hay);
+        assert_eq!(searcher.search(Span::from("")), None);
+        assert_eq!(searcher.pattern_index(), None);
+
+        assert_eq!(
+            rmatch_ranges(&*hay, &trie).map(|(r, _)| r).collect::<Vec<_>>(),
+            naive_rmatches(&patterns, &hay).into_iter().map(|(r, _)| r).collect::<Vec<_>>(),
+            "{:?} {:?}", patterns, hay
+        );
+
+        let mut consumer = Needle::<&str>::into_consumer(&trie);
+        let expected = longest_prefix(&patterns, &hay);
+        assert_eq!(consumer.consume(Span::from(&*hay)), expected.map(|(len, _)| len));
+        assert_eq!(consumer.pattern_index(), expected.map(|(_, i)| i));
+        let expected = longest_suffix(&patterns, &hay);
+        assert_eq!(consumer.rconsume(Span::from(&*hay)), expected.map(|(len, _)| hay.len() - len));
+        assert_eq!(consumer.pattern_index(), expected.map(|(_, i)| i));
+
+        // The same patterns as byte slices, where empty matches are not
+        // restricted to character boundaries.
+        if patterns.iter().any(|p| p.is_empty()) {
+            continue;
+        }
+        let trie = Trie::new(&patterns.iter().map(|p| p.as_bytes()).collect::<Vec<_>>());
+        assert_eq!(
+            match_ranges(hay.as_bytes(), &trie)
+                .map(|(r, _)| r)
+                .collect::<Vec<_>>(),
+            naive_matches(&patterns, &hay).into_iter().map(|(r, _)| r).collect::<Vec<_>>(),
+            "{:?} {:?}", patterns, hay
+        );
+    }
+}
+
+#[test]
+fn test_lexer() {
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    enum Token {
+        Let,
+        Ident,
+        Eq,
+        EqEq,
+        Space,
+    }
+    let keywords = ["let", "=", "==", " "];
+    let kinds = [Token::Let, Token::Eq, Token::EqEq, Token::Space];
+    let trie = Trie::<u8, str>::new(&keywords);
+    let mut consumer = Needle::<&str>::into_consumer(&trie);
+
+    let mut src = "let lettuce == x";
+    let mut tokens = Vec::new();
+    while !src.is_empty() {
+        let end = match consumer.consume(Span::from(src)) {
+            Some(end) => {
+                tokens.push(kinds[consumer.pattern_index().unwrap()]);
+                end
+            }
+            None => {
+                tokens.push(Token::Ident);
+                src.find(' ').unwrap_or(src.len())
+            }
+        };
+        src = &src[end..];
+    }
+    assert_eq!(tokens, vec![
+        Token::Let, Token::Space, Token::Let, Token::Ident, Token::Space,
+        Token::EqEq, Token::Space, Token::Ident,
+    ]);
+}
+
+#[test]
+fn test_slices() {
+    let routes: [&[&str]; 3] = [&["api"], &["api", "v1"], &["static"]];
+    let path = ["api", "v1", "users", "42"];
+    let trie = Trie::new(&routes);
+    let mut consumer = Needle::<&[&str]>::into_consumer(&trie);
+    assert_eq!(consumer.consume(Span::from(&path[..])), Some(2));
+    assert_eq!(consumer.pattern_index(), Some(1));
+    assert_eq!(consumer.consume(Span::from(&["api", "v2"][..])), Some(1));
+    assert_eq!(consumer.pattern_index(), Some(0));
+    assert_eq!(consumer.consume(Span::from(&["users"][..])), None);
+    assert_eq!(consumer.pattern_index(), None);
+
+    let suffixes = [&[1, 2][..], &[2]];
+    let trie = Trie::new(&suffixes);
+    assert_eq!(trim_end(&[3, 1, 2, 2, 1, 2][..], &trie), &[3]);
+    assert!(ends_with(vec![5, 2], &trie));
+}
+
+#[test]
+fn test_duplicates_and_empty() {
+    let patterns = ["ab", "", "ab"];
+    let trie = Trie::<u8, str>::new(&patterns);
+    let mut consumer = Needle::<&str>::into_consumer(&trie);
+    assert_eq!(consumer.consume(Span::from("abc")), Some(2));
+    assert_eq!(consumer.pattern_index(), Some(0));
+    assert_eq!(consumer.consume(Span::from("xyz")), Some(0));
+    assert_eq!(consumer.pattern_index(), Some(1));
+    assert_eq!(
+        match_ranges("xaby", &trie).map(|(r, _)| r).collect::<Vec<_>>(),
+        vec![0..0, 1..3, 4..4]
+    );
+}
+
+#[test]
+fn test_reuse() {
+    let keywords = vec!["fn".to_string(), "for".to_string(), "if".to_string()];
+    let trie = Trie::<u8, str>::new(&keywords);
+    let words = ["for", "fnord", "iffy", "while"];
+    let prefixed = words.iter().filter(|w| starts_with(**w, &trie)).count();
+    assert_eq!(prefixed, 3);
+    assert_eq!(trim_start("ifforfn", &trie), "");
+    assert!(ends_with("elif", &trie));
+
+    let cloned = trie.clone();
+    let mut consumer = Needle::<&str>::into_consumer(&cloned);
+    assert_eq!(consumer.rconsume(Span::from("xfor")), Some(1));
+    assert_eq!(consumer.pattern_index(), Some(1));
+}